@@ -1,6 +1,6 @@
 use crate::audio_feedback::{play_feedback_sound, play_feedback_sound_blocking, SoundType};
 use crate::managers::audio::AudioRecordingManager;
-use crate::managers::history::HistoryManager;
+use crate::managers::history::{discard_journal, HistoryManager, TranscriptionMetadata};
 use crate::managers::transcription::TranscriptionManager;
 use crate::settings::{get_settings, AppSettings};
 use crate::shortcut;
@@ -23,7 +23,10 @@ pub trait ShortcutAction: Send + Sync {
 }
 
 // Transcribe Action
-struct TranscribeAction;
+struct TranscribeAction {
+    /// Add the dictation to the latest history entry instead of a new one.
+    append: bool,
+}
 
 async fn maybe_convert_chinese_variant(
     settings: &AppSettings,
@@ -143,6 +146,7 @@ impl ShortcutAction for TranscribeAction {
             change_tray_icon(app, TrayIconState::Idle);
             if let Some(err) = recording_error {
                 let _ = app.emit("recording-error", err);
+                play_feedback_sound(app, SoundType::Error);
             }
         }
 
@@ -161,6 +165,16 @@ impl ShortcutAction for TranscribeAction {
 
         let ah = app.clone();
         let rm = Arc::clone(&app.state::<Arc<AudioRecordingManager>>());
+
+        // Nothing to stop when the recording never started or already ended at
+        // its maximum duration; leave the ongoing transcription's UI alone
+        if rm.active_binding().as_deref() != Some(binding_id) {
+            debug!("No recording active for binding: {}", binding_id);
+            if let Ok(mut states) = app.state::<ManagedToggleState>().lock() {
+                states.active_toggles.insert(binding_id.to_string(), false);
+            }
+            return;
+        }
         let tm = Arc::clone(&app.state::<Arc<TranscriptionManager>>());
         let hm = Arc::clone(&app.state::<Arc<HistoryManager>>());
 
@@ -174,6 +188,7 @@ impl ShortcutAction for TranscribeAction {
         play_feedback_sound(app, SoundType::Stop);
 
         let binding_id = binding_id.to_string(); // Clone binding_id for the async task
+        let append = self.append;
 
         tauri::async_runtime::spawn(async move {
             let binding_id = binding_id.clone(); // Clone for the inner async task
@@ -183,7 +198,11 @@ impl ShortcutAction for TranscribeAction {
             );
 
             let stop_recording_time = Instant::now();
-            if let Some(samples) = rm.stop_recording(&binding_id) {
+            let recording = rm.stop_recording(&binding_id);
+            // Kept until the recording is in history, so a crash before then
+            // can still recover it
+            let journal = rm.take_journal();
+            if let Some(samples) = recording {
                 debug!(
                     "Recording stopped and samples retrieved in {:?}, sample count: {}",
                     stop_recording_time.elapsed(),
@@ -192,8 +211,9 @@ impl ShortcutAction for TranscribeAction {
 
                 let transcription_time = Instant::now();
                 let samples_clone = samples.clone(); // Clone for history saving
-                match tm.transcribe(samples) {
-                    Ok(transcription) => {
+                match tm.transcribe_detailed(samples) {
+                    Ok(output) => {
+                        let transcription = output.text;
                         debug!(
                             "Transcription completed in {:?}: '{}'",
                             transcription_time.elapsed(),
@@ -201,6 +221,18 @@ impl ShortcutAction for TranscribeAction {
                         );
                         if !transcription.is_empty() {
                             let settings = get_settings(&ah);
+                            let durations = rm.last_recording_durations();
+                            let metadata = TranscriptionMetadata {
+                                model_id: output.model_id,
+                                engine: output.engine.map(str::to_string),
+                                language: Some(settings.selected_language.clone()),
+                                translate: Some(settings.translate_to_english),
+                                audio_duration_ms: Some(durations.audio.as_millis() as u64),
+                                speech_duration_ms: Some(durations.speech.as_millis() as u64),
+                                inference_ms: Some(output.inference.as_millis() as u64),
+                                paste_method: Some(settings.paste_method.as_str().to_string()),
+                                binding_id: Some(binding_id.clone()),
+                            };
                             let mut final_text = transcription.clone();
 
                             // Check if Chinese variant conversion is needed
@@ -210,15 +242,38 @@ impl ShortcutAction for TranscribeAction {
                                 final_text = converted_text;
                             }
 
+                            // Only the new portion is pasted, continuing the
+                            // previous paste
+                            if append && !settings.append_trailing_space {
+                                final_text.insert(0, ' ');
+                            }
+
                             // Save to history
                             let hm_clone = Arc::clone(&hm);
                             let transcription_for_history = transcription.clone();
                             tauri::async_runtime::spawn(async move {
-                                if let Err(e) = hm_clone
-                                    .save_transcription(samples_clone, transcription_for_history)
-                                    .await
-                                {
-                                    error!("Failed to save transcription to history: {}", e);
+                                let saved = if append {
+                                    hm_clone
+                                        .append_to_latest(
+                                            samples_clone,
+                                            transcription_for_history,
+                                            metadata,
+                                        )
+                                        .await
+                                } else {
+                                    hm_clone
+                                        .save_transcription(
+                                            samples_clone,
+                                            transcription_for_history,
+                                            metadata,
+                                        )
+                                        .await
+                                };
+                                match saved {
+                                    Ok(()) => discard_journal(journal),
+                                    Err(e) => {
+                                        error!("Failed to save transcription to history: {}", e)
+                                    }
                                 }
                             });
 
@@ -227,11 +282,17 @@ impl ShortcutAction for TranscribeAction {
                             let paste_time = Instant::now();
                             ah.run_on_main_thread(move || {
                                 match utils::paste(final_text, ah_clone.clone()) {
-                                    Ok(()) => debug!(
-                                        "Text pasted successfully in {:?}",
-                                        paste_time.elapsed()
-                                    ),
-                                    Err(e) => error!("Failed to paste transcription: {}", e),
+                                    Ok(()) => {
+                                        debug!(
+                                            "Text pasted successfully in {:?}",
+                                            paste_time.elapsed()
+                                        );
+                                        play_feedback_sound(&ah_clone, SoundType::Complete);
+                                    }
+                                    Err(e) => {
+                                        error!("Failed to paste transcription: {}", e);
+                                        play_feedback_sound(&ah_clone, SoundType::Error);
+                                    }
                                 }
                                 // Hide the overlay after transcription is complete
                                 utils::hide_recording_overlay(&ah_clone);
@@ -243,18 +304,23 @@ impl ShortcutAction for TranscribeAction {
                                 change_tray_icon(&ah, TrayIconState::Idle);
                             });
                         } else {
+                            discard_journal(journal);
+                            play_feedback_sound(&ah, SoundType::Empty);
                             utils::hide_recording_overlay(&ah);
                             change_tray_icon(&ah, TrayIconState::Idle);
                         }
                     }
                     Err(err) => {
                         debug!("Global Shortcut Transcription error: {}", err);
+                        play_feedback_sound(&ah, SoundType::Error);
                         utils::hide_recording_overlay(&ah);
                         change_tray_icon(&ah, TrayIconState::Idle);
                     }
                 }
             } else {
+                discard_journal(journal);
                 debug!("No samples retrieved from recording stop");
+                play_feedback_sound(&ah, SoundType::Empty);
                 utils::hide_recording_overlay(&ah);
                 change_tray_icon(&ah, TrayIconState::Idle);
             }
@@ -285,6 +351,19 @@ impl ShortcutAction for CancelAction {
     }
 }
 
+// Pause Action
+struct PauseAction;
+
+impl ShortcutAction for PauseAction {
+    fn start(&self, app: &AppHandle, _binding_id: &str, _shortcut_str: &str) {
+        utils::toggle_recording_pause(app);
+    }
+
+    fn stop(&self, _app: &AppHandle, _binding_id: &str, _shortcut_str: &str) {
+        // Pausing toggles on press, nothing to do on release
+    }
+}
+
 // Test Action
 struct TestAction;
 
@@ -313,12 +392,20 @@ pub static ACTION_MAP: Lazy<HashMap<String, Arc<dyn ShortcutAction>>> = Lazy::ne
     let mut map = HashMap::new();
     map.insert(
         "transcribe".to_string(),
-        Arc::new(TranscribeAction) as Arc<dyn ShortcutAction>,
+        Arc::new(TranscribeAction { append: false }) as Arc<dyn ShortcutAction>,
+    );
+    map.insert(
+        "append".to_string(),
+        Arc::new(TranscribeAction { append: true }) as Arc<dyn ShortcutAction>,
     );
     map.insert(
         "cancel".to_string(),
         Arc::new(CancelAction) as Arc<dyn ShortcutAction>,
     );
+    map.insert(
+        "pause".to_string(),
+        Arc::new(PauseAction) as Arc<dyn ShortcutAction>,
+    );
     map.insert(
         "test".to_string(),
         Arc::new(TestAction) as Arc<dyn ShortcutAction>,
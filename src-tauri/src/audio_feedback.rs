@@ -3,15 +3,138 @@ use crate::settings::{self, AppSettings};
 use cpal::traits::{DeviceTrait, HostTrait};
 use log::{debug, error, warn};
 use rodio::OutputStreamBuilder;
-use std::fs::File;
+use serde::Serialize;
+use specta::Type;
+use std::fs::{self, File};
 use std::io::BufReader;
 use std::path::{Path, PathBuf};
 use std::thread;
-use tauri::{AppHandle, Manager};
+use tauri::{path::BaseDirectory, AppHandle, Manager};
 
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
 pub enum SoundType {
     Start,
     Stop,
+    Cancel,
+    Error,
+    /// A transcription was pasted.
+    Complete,
+    /// A recording produced no text.
+    Empty,
+}
+
+impl SoundType {
+    pub const ALL: [SoundType; 6] = [
+        SoundType::Start,
+        SoundType::Stop,
+        SoundType::Cancel,
+        SoundType::Error,
+        SoundType::Complete,
+        SoundType::Empty,
+    ];
+
+    /// File name, without extension, of this event's sound in a theme folder.
+    pub fn name(self) -> &'static str {
+        match self {
+            SoundType::Start => "start",
+            SoundType::Stop => "stop",
+            SoundType::Cancel => "cancel",
+            SoundType::Error => "error",
+            SoundType::Complete => "complete",
+            SoundType::Empty => "empty",
+        }
+    }
+
+    pub fn from_name(name: &str) -> Option<Self> {
+        Self::ALL.into_iter().find(|sound| sound.name() == name)
+    }
+}
+
+/// Folder under the app data dir holding one subfolder per sound theme.
+const SOUND_THEMES_DIR: &str = "sound_themes";
+
+/// Audio formats looked for in theme folders, in order of preference.
+const SOUND_EXTENSIONS: [&str; 4] = ["wav", "ogg", "flac", "mp3"];
+
+/// A sound theme folder found in the themes directory.
+#[derive(Clone, Debug, Serialize, Type)]
+pub struct SoundThemeInfo {
+    pub name: String,
+    /// Events the theme has its own sound for; the rest use the built-in theme.
+    pub events: Vec<String>,
+}
+
+fn sound_themes_dir(app: &AppHandle) -> Option<PathBuf> {
+    app.path()
+        .app_data_dir()
+        .ok()
+        .map(|dir| dir.join(SOUND_THEMES_DIR))
+}
+
+fn theme_sound(theme_dir: &Path, sound_type: SoundType) -> Option<PathBuf> {
+    SOUND_EXTENSIONS
+        .iter()
+        .map(|ext| theme_dir.join(format!("{}.{}", sound_type.name(), ext)))
+        .find(|path| path.is_file())
+}
+
+/// Lists the theme folders in the sound themes directory.
+pub fn list_sound_themes(app: &AppHandle) -> Vec<SoundThemeInfo> {
+    let Some(dir) = sound_themes_dir(app) else {
+        return Vec::new();
+    };
+    let Ok(entries) = fs::read_dir(&dir) else {
+        return Vec::new();
+    };
+
+    let mut themes: Vec<SoundThemeInfo> = entries
+        .filter_map(|entry| entry.ok().map(|entry| entry.path()))
+        .filter(|path| path.is_dir())
+        .filter_map(|path| {
+            let name = path.file_name()?.to_str()?.to_string();
+            let events = SoundType::ALL
+                .into_iter()
+                .filter(|&sound| theme_sound(&path, sound).is_some())
+                .map(|sound| sound.name().to_string())
+                .collect();
+            Some(SoundThemeInfo { name, events })
+        })
+        .collect();
+    themes.sort_by(|a, b| a.name.cmp(&b.name));
+    themes
+}
+
+/// Whether `name` is a folder in the sound themes directory.
+pub fn sound_theme_exists(app: &AppHandle, name: &str) -> bool {
+    list_sound_themes(app)
+        .iter()
+        .any(|theme| theme.name == name)
+}
+
+/// Where an event's sound comes from.
+#[derive(Debug)]
+enum SoundFile {
+    /// A file in the selected theme folder.
+    Themed(PathBuf),
+    /// A built-in or custom sound, resolved against a base directory.
+    BuiltIn(String, BaseDirectory),
+}
+
+/// Picks the sound for an event: the theme folder's if it has one, otherwise
+/// the built-in theme's.
+fn select_sound(
+    themes_dir: Option<&Path>,
+    settings: &AppSettings,
+    sound_type: SoundType,
+) -> SoundFile {
+    // A theme folder may provide any subset of the events
+    if let (Some(dir), Some(theme)) = (themes_dir, &settings.sound_theme_folder) {
+        if let Some(path) = theme_sound(&dir.join(theme), sound_type) {
+            return SoundFile::Themed(path);
+        }
+    }
+    let (file, base_dir) = get_sound_path(settings, sound_type);
+    SoundFile::BuiltIn(file, base_dir)
 }
 
 fn resolve_sound_path(
@@ -19,24 +142,30 @@ fn resolve_sound_path(
     settings: &AppSettings,
     sound_type: SoundType,
 ) -> Option<PathBuf> {
-    let sound_file = get_sound_path(settings, sound_type);
-    let base_dir = get_sound_base_dir(settings);
-    app.path().resolve(&sound_file, base_dir).ok()
-}
-
-fn get_sound_path(settings: &AppSettings, sound_type: SoundType) -> String {
-    match (settings.sound_theme, sound_type) {
-        (SoundTheme::Custom, SoundType::Start) => "custom_start.wav".to_string(),
-        (SoundTheme::Custom, SoundType::Stop) => "custom_stop.wav".to_string(),
-        (_, SoundType::Start) => settings.sound_theme.to_start_path(),
-        (_, SoundType::Stop) => settings.sound_theme.to_stop_path(),
+    match select_sound(sound_themes_dir(app).as_deref(), settings, sound_type) {
+        SoundFile::Themed(path) => Some(path),
+        SoundFile::BuiltIn(file, base_dir) => app.path().resolve(&file, base_dir).ok(),
     }
 }
 
-fn get_sound_base_dir(settings: &AppSettings) -> tauri::path::BaseDirectory {
-    match settings.sound_theme {
-        SoundTheme::Custom => tauri::path::BaseDirectory::AppData,
-        _ => tauri::path::BaseDirectory::Resource,
+/// The built-in themes only have start and stop sounds, so the events that end
+/// a recording early reuse the stop sound. Completion borrows the start sound of
+/// another built-in theme, so it is told apart from the stop sound just before.
+fn get_sound_path(settings: &AppSettings, sound_type: SoundType) -> (String, BaseDirectory) {
+    match (settings.sound_theme, sound_type) {
+        (SoundTheme::Pop, SoundType::Complete) => {
+            (SoundTheme::Marimba.to_start_path(), BaseDirectory::Resource)
+        }
+        (_, SoundType::Complete) => (SoundTheme::Pop.to_start_path(), BaseDirectory::Resource),
+        (SoundTheme::Custom, SoundType::Start) => {
+            ("custom_start.wav".to_string(), BaseDirectory::AppData)
+        }
+        (SoundTheme::Custom, _) => ("custom_stop.wav".to_string(), BaseDirectory::AppData),
+        (_, SoundType::Start) => (
+            settings.sound_theme.to_start_path(),
+            BaseDirectory::Resource,
+        ),
+        _ => (settings.sound_theme.to_stop_path(), BaseDirectory::Resource),
     }
 }
 
@@ -135,3 +264,55 @@ fn play_audio_file(
 
     Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn sound_types_round_trip_through_their_names() {
+        for sound in SoundType::ALL {
+            assert_eq!(SoundType::from_name(sound.name()), Some(sound));
+        }
+        assert_eq!(SoundType::from_name("Start"), None);
+        assert_eq!(SoundType::from_name("chime"), None);
+    }
+
+    #[test]
+    fn theme_folders_fall_back_per_event() {
+        let dir = tempfile::tempdir().unwrap();
+        let theme = dir.path().join("soft");
+        fs::create_dir(&theme).unwrap();
+        fs::write(theme.join("start.ogg"), b"").unwrap();
+
+        let mut settings = settings::get_default_settings();
+        settings.sound_theme = SoundTheme::Marimba;
+        settings.sound_theme_folder = Some("soft".to_string());
+
+        let file = |sound| match select_sound(Some(dir.path()), &settings, sound) {
+            SoundFile::Themed(path) => path.to_string_lossy().into_owned(),
+            SoundFile::BuiltIn(file, _) => file,
+        };
+        assert_eq!(
+            file(SoundType::Start),
+            theme.join("start.ogg").to_string_lossy()
+        );
+        assert_eq!(file(SoundType::Cancel), "resources/marimba_stop.wav");
+        assert_eq!(file(SoundType::Complete), "resources/pop_start.wav");
+    }
+
+    #[test]
+    fn every_event_has_a_built_in_sound() {
+        let mut settings = settings::get_default_settings();
+        for theme in [SoundTheme::Marimba, SoundTheme::Pop, SoundTheme::Custom] {
+            settings.sound_theme = theme;
+            for sound in SoundType::ALL {
+                let (file, _) = get_sound_path(&settings, sound);
+                assert!(file.ends_with(".wav"), "{theme:?} {sound:?}: {file}");
+            }
+        }
+        settings.sound_theme = SoundTheme::Pop;
+        let (file, _) = get_sound_path(&settings, SoundType::Complete);
+        assert_eq!(file, "resources/marimba_start.wav");
+    }
+}
@@ -50,3 +50,157 @@ pub fn list_output_devices() -> Result<Vec<CpalDeviceInfo>, Box<dyn std::error::
 
     Ok(out)
 }
+
+/// Number of channels the device captures with its default input config.
+pub fn input_channel_count(device: &cpal::Device) -> Option<u16> {
+    device.default_input_config().ok().map(|c| c.channels())
+}
+
+/// A PulseAudio/PipeWire monitor source, which captures whatever an output
+/// device is playing.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct MonitorSource {
+    pub name: String,
+    pub description: String,
+}
+
+/// Source name the sound server resolves to the default output's monitor.
+pub const DEFAULT_MONITOR_SOURCE: &str = "@DEFAULT_MONITOR@";
+
+/// Lists monitor sources via `pactl`, which also works on PipeWire through
+/// pipewire-pulse. Empty on other platforms or when no sound server runs.
+pub fn list_monitor_sources() -> Vec<MonitorSource> {
+    #[cfg(target_os = "linux")]
+    {
+        let output = std::process::Command::new("pactl")
+            .args(["list", "sources"])
+            .env("LC_ALL", "C")
+            .output();
+
+        match output {
+            Ok(output) if output.status.success() => {
+                parse_monitor_sources(&String::from_utf8_lossy(&output.stdout))
+            }
+            Ok(output) => {
+                log::debug!("pactl list sources failed: {}", output.status);
+                Vec::new()
+            }
+            Err(e) => {
+                log::debug!("pactl not available: {}", e);
+                Vec::new()
+            }
+        }
+    }
+    #[cfg(not(target_os = "linux"))]
+    {
+        Vec::new()
+    }
+}
+
+#[cfg(any(target_os = "linux", test))]
+fn parse_monitor_sources(output: &str) -> Vec<MonitorSource> {
+    let mut sources = Vec::new();
+    let mut name: Option<String> = None;
+
+    for line in output.lines() {
+        let line = line.trim();
+        if line.starts_with("Source #") {
+            name = None;
+        } else if let Some(value) = line.strip_prefix("Name: ") {
+            name = Some(value.to_string());
+        } else if let Some(value) = line.strip_prefix("Description: ") {
+            if let Some(name) = name.take().filter(|n| n.ends_with(".monitor")) {
+                sources.push(MonitorSource {
+                    name,
+                    description: value.to_string(),
+                });
+            }
+        }
+    }
+
+    sources
+}
+
+/// Checks whether a device name matches a user-provided pattern.
+///
+/// Patterns without wildcards must match exactly. Patterns containing `*`
+/// (any run of characters) or `?` (a single character) are matched
+/// case-insensitively, so `"*USB*"` matches `"Blue Yeti USB Microphone"`.
+pub fn device_name_matches(pattern: &str, name: &str) -> bool {
+    if !pattern.contains(['*', '?']) {
+        return pattern == name;
+    }
+
+    let pattern: Vec<char> = pattern.to_lowercase().chars().collect();
+    let name: Vec<char> = name.to_lowercase().chars().collect();
+
+    let (mut p, mut n) = (0, 0);
+    let mut star: Option<(usize, usize)> = None;
+
+    while n < name.len() {
+        if p < pattern.len() && (pattern[p] == '?' || pattern[p] == name[n]) {
+            p += 1;
+            n += 1;
+        } else if p < pattern.len() && pattern[p] == '*' {
+            star = Some((p, n));
+            p += 1;
+        } else if let Some((star_p, star_n)) = star {
+            // Let the last `*` swallow one more character and retry
+            p = star_p + 1;
+            n = star_n + 1;
+            star = Some((star_p, star_n + 1));
+        } else {
+            return false;
+        }
+    }
+
+    pattern[p..].iter().all(|&c| c == '*')
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn exact_names_match_without_wildcards() {
+        assert!(device_name_matches(
+            "MacBook Pro Microphone",
+            "MacBook Pro Microphone"
+        ));
+        assert!(!device_name_matches(
+            "macbook pro microphone",
+            "MacBook Pro Microphone"
+        ));
+    }
+
+    #[test]
+    fn parses_only_monitor_sources() {
+        let output = "Source #0
+	State: SUSPENDED
+	Name: alsa_output.pci-0000_00_1f.3.analog-stereo.monitor
+	Description: Monitor of Built-in Audio Analog Stereo
+	Driver: module-alsa-card.c
+
+Source #1
+	State: RUNNING
+	Name: alsa_input.pci-0000_00_1f.3.analog-stereo
+	Description: Built-in Audio Analog Stereo
+";
+        assert_eq!(
+            parse_monitor_sources(output),
+            vec![MonitorSource {
+                name: "alsa_output.pci-0000_00_1f.3.analog-stereo.monitor".to_string(),
+                description: "Monitor of Built-in Audio Analog Stereo".to_string(),
+            }]
+        );
+    }
+
+    #[test]
+    fn wildcards_match_case_insensitively() {
+        assert!(device_name_matches("*usb*", "Blue Yeti USB Microphone"));
+        assert!(device_name_matches("Jabra*", "Jabra Evolve2 65"));
+        assert!(device_name_matches("hw:?,0", "hw:1,0"));
+        assert!(!device_name_matches("Jabra*", "Blue Yeti"));
+        assert!(!device_name_matches("hw:?,0", "hw:10,0"));
+    }
+}
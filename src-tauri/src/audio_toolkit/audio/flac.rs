@@ -0,0 +1,66 @@
+//! FLAC encoding of 16-bit mono recordings with `flacenc`. Decoding is left
+//! to `claxon`.
+
+use anyhow::{anyhow, Result};
+use flacenc::{bitsink::ByteSink, component::BitRepr, error::Verify, source::MemSource};
+use std::io::Write;
+
+const BITS_PER_SAMPLE: usize = 16;
+
+/// Encodes 16-bit mono samples as a complete FLAC stream.
+pub fn encode_flac<W: Write>(mut writer: W, samples: &[i16], sample_rate: u32) -> Result<()> {
+    let config = flacenc::config::Encoder::default()
+        .into_verified()
+        .map_err(|(_, e)| anyhow!("Invalid FLAC encoder config: {e}"))?;
+
+    let pcm: Vec<i32> = samples.iter().map(|&s| s as i32).collect();
+    let source = MemSource::from_samples(&pcm, 1, BITS_PER_SAMPLE, sample_rate as usize);
+    let stream = flacenc::encode_with_fixed_block_size(&config, source, config.block_size)
+        .map_err(|e| anyhow!("Failed to encode FLAC: {e}"))?;
+
+    let mut sink = ByteSink::new();
+    stream
+        .write(&mut sink)
+        .map_err(|e| anyhow!("Failed to write FLAC stream: {e}"))?;
+    writer.write_all(sink.as_slice())?;
+    writer.flush()?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Cursor;
+
+    fn round_trip(samples: &[i16], sample_rate: u32) -> Vec<i16> {
+        let mut encoded = Vec::new();
+        encode_flac(&mut encoded, samples, sample_rate).unwrap();
+
+        let mut reader = claxon::FlacReader::new(Cursor::new(encoded)).unwrap();
+        assert_eq!(reader.streaminfo().sample_rate, sample_rate);
+        reader.samples().map(|s| s.unwrap() as i16).collect()
+    }
+
+    #[test]
+    fn round_trips_speech_like_audio() {
+        let samples: Vec<i16> = (0..10_000)
+            .map(|i| {
+                let t = i as f32 / 16000.0;
+                ((t * 220.0 * std::f32::consts::TAU).sin() * 12000.0
+                    + (t * 3100.0 * std::f32::consts::TAU).sin() * 900.0) as i16
+            })
+            .collect();
+        assert_eq!(round_trip(&samples, 16000), samples);
+
+        let mut encoded = Vec::new();
+        encode_flac(&mut encoded, &samples, 16000).unwrap();
+        // Noticeably smaller than the 16 bits per sample of the equivalent WAV
+        assert!(encoded.len() < samples.len() * 2 * 4 / 5);
+    }
+
+    #[test]
+    fn round_trips_extremes_and_short_blocks() {
+        let samples = [i16::MIN, i16::MAX, 0, -1, 1, i16::MAX, i16::MIN];
+        assert_eq!(round_trip(&samples, 44100), samples);
+    }
+}
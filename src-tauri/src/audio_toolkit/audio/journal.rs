@@ -0,0 +1,95 @@
+use std::{
+    fs::{File, OpenOptions},
+    io::{self, BufWriter, Read, Write},
+    path::{Path, PathBuf},
+};
+
+/// Roughly one second of 16 kHz audio is buffered before hitting the disk.
+const JOURNAL_BUFFER_BYTES: usize = 32 * 1024;
+
+/// Appends recorded samples to disk as raw 16-bit little-endian PCM, so a
+/// recording survives the app being killed before it is saved.
+///
+/// Opening an existing journal appends to it, which lets a recording that
+/// moves to another device keep writing to the same file.
+pub struct RecordingJournal {
+    path: PathBuf,
+    writer: BufWriter<File>,
+}
+
+impl RecordingJournal {
+    pub fn open<P: AsRef<Path>>(path: P) -> io::Result<Self> {
+        let path = path.as_ref().to_path_buf();
+        if let Some(dir) = path.parent() {
+            std::fs::create_dir_all(dir)?;
+        }
+        let file = OpenOptions::new().create(true).append(true).open(&path)?;
+        Ok(Self {
+            path,
+            writer: BufWriter::with_capacity(JOURNAL_BUFFER_BYTES, file),
+        })
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    pub fn append(&mut self, samples: &[f32]) -> io::Result<()> {
+        for &sample in samples {
+            let sample_i16 = (sample.clamp(-1.0, 1.0) * i16::MAX as f32) as i16;
+            self.writer.write_all(&sample_i16.to_le_bytes())?;
+        }
+        Ok(())
+    }
+
+    pub fn flush(&mut self) -> io::Result<()> {
+        self.writer.flush()?;
+        self.writer.get_ref().sync_data()
+    }
+}
+
+/// Reads back a journal written by [`RecordingJournal`]. A half-written
+/// sample at the end, left by a crash mid-write, is ignored.
+pub fn read_journal<P: AsRef<Path>>(path: P) -> io::Result<Vec<f32>> {
+    let mut bytes = Vec::new();
+    File::open(path)?.read_to_end(&mut bytes)?;
+    Ok(bytes
+        .chunks_exact(2)
+        .map(|pair| i16::from_le_bytes([pair[0], pair[1]]) as f32 / i16::MAX as f32)
+        .collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn appends_across_reopens_and_ignores_torn_writes() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("journal").join("recording.pcm");
+
+        let mut journal = RecordingJournal::open(&path).unwrap();
+        journal.append(&[0.5, -0.5]).unwrap();
+        journal.flush().unwrap();
+        drop(journal);
+
+        let mut journal = RecordingJournal::open(&path).unwrap();
+        journal.append(&[1.0]).unwrap();
+        journal.flush().unwrap();
+        drop(journal);
+
+        // Simulate a crash halfway through writing a sample
+        OpenOptions::new()
+            .append(true)
+            .open(&path)
+            .unwrap()
+            .write_all(&[0x7F])
+            .unwrap();
+
+        let samples = read_journal(&path).unwrap();
+        assert_eq!(samples.len(), 3);
+        assert!((samples[0] - 0.5).abs() < 1e-4);
+        assert!((samples[1] + 0.5).abs() < 1e-4);
+        assert_eq!(samples[2], 1.0);
+    }
+}
@@ -0,0 +1,141 @@
+/// Samples at or above this magnitude are counted as clipped.
+const CLIP_THRESHOLD: f32 = 0.999;
+
+/// Level reported for digital silence.
+pub const SILENCE_DBFS: f32 = -100.0;
+
+/// Loudness summary of a block of samples.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct InputLevel {
+    pub rms_dbfs: f32,
+    pub peak_dbfs: f32,
+    pub clipped_samples: usize,
+    pub sample_count: usize,
+}
+
+impl InputLevel {
+    /// Measures a block of samples in one go.
+    pub fn measure(samples: &[f32]) -> Self {
+        let mut meter = LevelMeter::new(usize::MAX);
+        meter.accumulate(samples);
+        meter.summary()
+    }
+}
+
+fn to_dbfs(amplitude: f32) -> f32 {
+    if amplitude <= 0.0 {
+        SILENCE_DBFS
+    } else {
+        (20.0 * amplitude.log10()).max(SILENCE_DBFS)
+    }
+}
+
+/// Accumulates samples and reports an [`InputLevel`] for each full window.
+pub struct LevelMeter {
+    window: usize,
+    sum_squares: f64,
+    peak: f32,
+    clipped: usize,
+    count: usize,
+}
+
+impl LevelMeter {
+    pub fn new(window: usize) -> Self {
+        Self {
+            window: window.max(1),
+            sum_squares: 0.0,
+            peak: 0.0,
+            clipped: 0,
+            count: 0,
+        }
+    }
+
+    pub fn reset(&mut self) {
+        self.sum_squares = 0.0;
+        self.peak = 0.0;
+        self.clipped = 0;
+        self.count = 0;
+    }
+
+    /// Feeds samples, calling `emit` every time a window completes.
+    pub fn feed(&mut self, mut samples: &[f32], mut emit: impl FnMut(InputLevel)) {
+        while !samples.is_empty() {
+            let take = (self.window - self.count).min(samples.len());
+            self.accumulate(&samples[..take]);
+            samples = &samples[take..];
+
+            if self.count == self.window {
+                emit(self.summary());
+                self.reset();
+            }
+        }
+    }
+
+    /// Level of the samples fed since the last reset or full window, if any.
+    pub fn level(&self) -> Option<InputLevel> {
+        (self.count > 0).then(|| self.summary())
+    }
+
+    fn accumulate(&mut self, samples: &[f32]) {
+        for &sample in samples {
+            let magnitude = sample.abs();
+            self.sum_squares += (sample as f64) * (sample as f64);
+            self.peak = self.peak.max(magnitude);
+            if magnitude >= CLIP_THRESHOLD {
+                self.clipped += 1;
+            }
+        }
+        self.count += samples.len();
+    }
+
+    fn summary(&self) -> InputLevel {
+        let rms = if self.count == 0 {
+            0.0
+        } else {
+            (self.sum_squares / self.count as f64).sqrt() as f32
+        };
+
+        InputLevel {
+            rms_dbfs: to_dbfs(rms),
+            peak_dbfs: to_dbfs(self.peak),
+            clipped_samples: self.clipped,
+            sample_count: self.count,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn measures_full_scale_square_wave() {
+        let level = InputLevel::measure(&[1.0, -1.0, 1.0, -1.0]);
+        assert!(level.rms_dbfs.abs() < 1e-4);
+        assert!(level.peak_dbfs.abs() < 1e-4);
+        assert_eq!(level.clipped_samples, 4);
+        assert_eq!(level.sample_count, 4);
+    }
+
+    #[test]
+    fn silence_reports_floor() {
+        let level = InputLevel::measure(&[0.0; 8]);
+        assert_eq!(level.rms_dbfs, SILENCE_DBFS);
+        assert_eq!(level.peak_dbfs, SILENCE_DBFS);
+        assert_eq!(level.clipped_samples, 0);
+    }
+
+    #[test]
+    fn emits_one_level_per_window() {
+        let mut meter = LevelMeter::new(4);
+        let mut levels = Vec::new();
+        meter.feed(&[0.5; 6], |level| levels.push(level));
+        meter.feed(&[0.1; 2], |level| levels.push(level));
+
+        assert_eq!(levels.len(), 2);
+        assert!((levels[0].peak_dbfs - to_dbfs(0.5)).abs() < 1e-4);
+        // Second window mixes the two blocks
+        assert!((levels[1].peak_dbfs - to_dbfs(0.5)).abs() < 1e-4);
+        assert!(levels[1].rms_dbfs < levels[0].rms_dbfs);
+    }
+}
@@ -1,12 +1,34 @@
 // Re-export all audio components
 mod device;
+mod flac;
+mod journal;
+mod meter;
+mod monitor;
+mod opus;
+mod preprocess;
 mod recorder;
 mod resampler;
+mod source;
+mod spill;
 mod utils;
 mod visualizer;
 
-pub use device::{list_input_devices, list_output_devices, CpalDeviceInfo};
-pub use recorder::AudioRecorder;
+pub use device::{
+    device_name_matches, input_channel_count, list_input_devices, list_monitor_sources,
+    list_output_devices, CpalDeviceInfo, MonitorSource, DEFAULT_MONITOR_SOURCE,
+};
+pub use flac::encode_flac;
+pub use journal::{read_journal, RecordingJournal};
+pub use meter::{InputLevel, LevelMeter, SILENCE_DBFS};
+pub use monitor::PulseMonitorSource;
+pub use opus::{decode_ogg_opus, encode_ogg_opus};
+pub use preprocess::{AudioPreprocessor, PreprocessConfig};
+pub use recorder::{AudioRecorder, ChannelMix, RecordingLevels, RecordingLimitEvent};
 pub use resampler::FrameResampler;
-pub use utils::save_wav_file;
+pub use source::{AudioSource, MemorySource, Pace, RunningSource, WavFileSource};
+pub use spill::SpillBuffer;
+pub use utils::{
+    decode_audio, encode_audio, read_audio_file, save_audio_file, save_wav_file, write_audio_file,
+    AudioFileFormat,
+};
 pub use visualizer::AudioVisualiser;
@@ -0,0 +1,193 @@
+//! Captures a PulseAudio/PipeWire monitor source through `parec`, which ships
+//! with the same utilities as the `pactl` used to list the sources.
+
+use std::io::Read;
+use std::process::{Child, Command, Stdio};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{mpsc, Arc};
+use std::thread;
+use std::time::Duration;
+
+use super::{list_monitor_sources, AudioSource, RunningSource, DEFAULT_MONITOR_SOURCE};
+
+/// Rate the sound server is asked to deliver the monitor at.
+const MONITOR_SAMPLE_RATE: u32 = 48000;
+
+/// Bytes read from `parec` at a time: 10 ms of mono `f32` samples.
+const READ_SIZE: usize = (MONITOR_SAMPLE_RATE as usize / 100) * 4;
+
+/// How long `parec` may take to deliver its first samples before the source
+/// is considered unavailable.
+const START_TIMEOUT: Duration = Duration::from_millis(1500);
+
+/// Records a monitor source, mixed down to mono by the sound server.
+///
+/// Starting fails unless the source is listed and `parec` delivers audio
+/// from it. `error_cb` is called if capture ends later without the source
+/// being dropped, e.g. when the sound server goes away.
+pub struct PulseMonitorSource {
+    source: String,
+    error_cb: Option<Arc<dyn Fn(String) + Send + Sync + 'static>>,
+}
+
+impl PulseMonitorSource {
+    pub fn new(
+        source: impl Into<String>,
+        error_cb: Option<Arc<dyn Fn(String) + Send + Sync + 'static>>,
+    ) -> Self {
+        Self {
+            source: source.into(),
+            error_cb,
+        }
+    }
+}
+
+impl AudioSource for PulseMonitorSource {
+    fn start(self: Box<Self>, sample_tx: mpsc::Sender<Vec<f32>>) -> Result<RunningSource, String> {
+        if self.source != DEFAULT_MONITOR_SOURCE
+            && !list_monitor_sources().iter().any(|s| s.name == self.source)
+        {
+            return Err(format!("Monitor source {} not found", self.source));
+        }
+
+        let mut child = Command::new("parec")
+            .arg(format!("--device={}", self.source))
+            .arg("--format=float32le")
+            .arg(format!("--rate={MONITOR_SAMPLE_RATE}"))
+            .arg("--channels=1")
+            .arg("--latency-msec=30")
+            .arg("--raw")
+            .stdin(Stdio::null())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::null())
+            .spawn()
+            .map_err(|e| format!("Failed to start parec for {}: {e}", self.source))?;
+        let mut stdout = child
+            .stdout
+            .take()
+            .ok_or_else(|| "parec has no output".to_string())?;
+
+        let source = self.source.clone();
+        let stopping = Arc::new(AtomicBool::new(false));
+        // Reports the first read, so a source parec can't capture fails to start
+        let (started_tx, started_rx) = mpsc::sync_channel::<Result<(), String>>(1);
+        let reader = thread::spawn({
+            let stopping = stopping.clone();
+            let PulseMonitorSource { source, error_cb } = *self;
+            move || {
+                let mut started_tx = Some(started_tx);
+                let mut buf = vec![0u8; READ_SIZE];
+                let mut pending = Vec::new();
+                let ended = loop {
+                    match stdout.read(&mut buf) {
+                        Ok(0) => break "capture ended".to_string(),
+                        Ok(read) => {
+                            if let Some(tx) = started_tx.take() {
+                                let _ = tx.send(Ok(()));
+                            }
+                            let samples = decode_f32le(&mut pending, &buf[..read]);
+                            if sample_tx.send(samples).is_err() {
+                                return; // recorder closed
+                            }
+                        }
+                        Err(e) => break e.to_string(),
+                    }
+                };
+
+                // Ending before any audio is a start failure, reported by start
+                if let Some(tx) = started_tx {
+                    let _ = tx.send(Err(ended));
+                    return;
+                }
+                if !stopping.load(Ordering::SeqCst) {
+                    let message = format!("Monitor source {source}: {ended}");
+                    log::warn!("{message}");
+                    if let Some(cb) = error_cb {
+                        cb(message);
+                    }
+                }
+            }
+        });
+
+        let mut capture = MonitorCapture {
+            child,
+            reader: Some(reader),
+            stopping,
+        };
+        match started_rx.recv_timeout(START_TIMEOUT) {
+            Ok(Ok(())) => {
+                log::info!("Capturing monitor source {}", source);
+                Ok(RunningSource::new(MONITOR_SAMPLE_RATE, capture))
+            }
+            Ok(Err(ended)) => {
+                let status = capture.child.wait().map_err(|e| e.to_string())?;
+                Err(format!(
+                    "parec could not capture {} ({}): {}",
+                    source, status, ended
+                ))
+            }
+            Err(_) => Err(format!(
+                "No audio from monitor source {} within {:?}",
+                source, START_TIMEOUT
+            )),
+        }
+    }
+}
+
+/// Stops `parec` when the recorder lets go of the source.
+struct MonitorCapture {
+    child: Child,
+    reader: Option<thread::JoinHandle<()>>,
+    stopping: Arc<AtomicBool>,
+}
+
+impl Drop for MonitorCapture {
+    fn drop(&mut self) {
+        self.stopping.store(true, Ordering::SeqCst);
+        let _ = self.child.kill();
+        let _ = self.child.wait();
+        if let Some(reader) = self.reader.take() {
+            let _ = reader.join();
+        }
+    }
+}
+
+/// Decodes little-endian `f32` samples, keeping a partial sample split
+/// across reads in `pending` until the rest arrives.
+fn decode_f32le(pending: &mut Vec<u8>, bytes: &[u8]) -> Vec<f32> {
+    pending.extend_from_slice(bytes);
+    let whole = pending.len() - pending.len() % 4;
+    let samples = pending[..whole]
+        .chunks_exact(4)
+        .map(|b| f32::from_le_bytes([b[0], b[1], b[2], b[3]]))
+        .collect();
+    pending.drain(..whole);
+    samples
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn decodes_samples_split_across_reads() {
+        let bytes: Vec<u8> = [0.5f32, -0.25]
+            .iter()
+            .flat_map(|s| s.to_le_bytes())
+            .collect();
+        let mut pending = Vec::new();
+
+        assert_eq!(decode_f32le(&mut pending, &bytes[..3]), Vec::<f32>::new());
+        assert_eq!(decode_f32le(&mut pending, &bytes[3..6]), vec![0.5]);
+        assert_eq!(decode_f32le(&mut pending, &bytes[6..]), vec![-0.25]);
+        assert!(pending.is_empty());
+    }
+
+    #[test]
+    fn fails_to_start_on_an_unlisted_source() {
+        let (sample_tx, _sample_rx) = mpsc::channel();
+        let source = Box::new(PulseMonitorSource::new("no.such.monitor", None));
+        let error = source.start(sample_tx).err().unwrap();
+        assert!(error.contains("not found"));
+    }
+}
@@ -0,0 +1,204 @@
+//! Mono Ogg Opus files (RFC 7845), encoded and decoded with libopus.
+
+use anyhow::{anyhow, bail, Result};
+use audiopus::{
+    coder::{Decoder, Encoder},
+    Application, Bitrate, Channels, SampleRate,
+};
+use ogg::{PacketReader, PacketWriteEndInfo, PacketWriter};
+use std::io::{Read, Seek, Write};
+
+/// Opus granule positions always count samples at 48 kHz.
+const GRANULE_RATE: u64 = 48000;
+
+/// Duration of each Opus packet.
+const FRAME_MS: usize = 20;
+
+/// Largest packet libopus will produce for a single frame.
+const MAX_PACKET_SIZE: usize = 4000;
+
+const STREAM_SERIAL: u32 = 0x4861_6E64;
+
+fn opus_rate(sample_rate: u32) -> Result<SampleRate> {
+    Ok(match sample_rate {
+        8000 => SampleRate::Hz8000,
+        12000 => SampleRate::Hz12000,
+        16000 => SampleRate::Hz16000,
+        24000 => SampleRate::Hz24000,
+        48000 => SampleRate::Hz48000,
+        _ => bail!("Opus does not support a sample rate of {sample_rate} Hz"),
+    })
+}
+
+/// Encodes mono samples as an Ogg Opus stream at `bitrate` bits per second.
+pub fn encode_ogg_opus<W: Write>(
+    writer: W,
+    samples: &[f32],
+    sample_rate: u32,
+    bitrate: u32,
+) -> Result<()> {
+    let mut encoder = Encoder::new(opus_rate(sample_rate)?, Channels::Mono, Application::Voip)?;
+    encoder.set_bitrate(Bitrate::BitsPerSecond(bitrate as i32))?;
+
+    let granule_scale = GRANULE_RATE / sample_rate as u64;
+    let pre_skip = encoder.lookahead()? as u64 * granule_scale;
+    let frame_len = sample_rate as usize * FRAME_MS / 1000;
+
+    let mut ogg = PacketWriter::new(writer);
+    ogg.write_packet(
+        id_header(sample_rate, pre_skip as u16).into_boxed_slice(),
+        STREAM_SERIAL,
+        PacketWriteEndInfo::EndPage,
+        0,
+    )?;
+    ogg.write_packet(
+        comment_header().into_boxed_slice(),
+        STREAM_SERIAL,
+        PacketWriteEndInfo::EndPage,
+        0,
+    )?;
+
+    // The encoder's lookahead delays its output, so keep feeding silence until
+    // every input sample has made it into a packet
+    let total_frames = (samples.len() + pre_skip as usize / granule_scale as usize)
+        .div_ceil(frame_len)
+        .max(1);
+    let end_granule = pre_skip + samples.len() as u64 * granule_scale;
+
+    let mut frame = vec![0.0f32; frame_len];
+    let mut packet = vec![0u8; MAX_PACKET_SIZE];
+    for index in 0..total_frames {
+        let start = (index * frame_len).min(samples.len());
+        let end = (start + frame_len).min(samples.len());
+        frame.fill(0.0);
+        frame[..end - start].copy_from_slice(&samples[start..end]);
+
+        let len = encoder.encode_float(&frame, &mut packet)?;
+        let last = index + 1 == total_frames;
+        let granule = if last {
+            end_granule
+        } else {
+            ((index + 1) * frame_len) as u64 * granule_scale
+        };
+        ogg.write_packet(
+            packet[..len].to_vec().into_boxed_slice(),
+            STREAM_SERIAL,
+            if last {
+                PacketWriteEndInfo::EndStream
+            } else {
+                PacketWriteEndInfo::NormalPacket
+            },
+            granule,
+        )?;
+    }
+
+    ogg.inner_mut().flush()?;
+    Ok(())
+}
+
+/// Decodes an Ogg Opus stream, returning mono samples at the rate recorded
+/// in its header (or 48 kHz when libopus can't decode at that rate).
+pub fn decode_ogg_opus<R: Read + Seek>(reader: R) -> Result<(Vec<f32>, u32)> {
+    let mut ogg = PacketReader::new(reader);
+
+    let head = ogg
+        .read_packet()?
+        .ok_or_else(|| anyhow!("Empty Ogg stream"))?;
+    if head.data.len() < 19 || &head.data[..8] != b"OpusHead" {
+        bail!("Not an Ogg Opus stream");
+    }
+    let channels = head.data[9];
+    let pre_skip = u16::from_le_bytes([head.data[10], head.data[11]]) as u64;
+    let input_rate =
+        u32::from_le_bytes([head.data[12], head.data[13], head.data[14], head.data[15]]);
+    if channels != 1 {
+        bail!("Only mono Opus recordings are supported, found {channels} channels");
+    }
+
+    let sample_rate = if opus_rate(input_rate).is_ok() {
+        input_rate
+    } else {
+        GRANULE_RATE as u32
+    };
+    let granule_scale = GRANULE_RATE / sample_rate as u64;
+    let mut decoder = Decoder::new(opus_rate(sample_rate)?, Channels::Mono)?;
+
+    // Skip the comment header
+    ogg.read_packet()?;
+
+    let mut samples = Vec::new();
+    let mut frame = vec![0.0f32; sample_rate as usize * 120 / 1000];
+    let mut end_granule = None;
+    while let Some(packet) = ogg.read_packet()? {
+        let len = decoder.decode_float(Some(&packet.data), &mut frame, false)?;
+        samples.extend_from_slice(&frame[..len]);
+        if packet.last_in_stream() {
+            end_granule = Some(packet.absgp_page());
+            break;
+        }
+    }
+
+    // Trim the encoder delay at the front and the padding at the end
+    let skip = ((pre_skip / granule_scale) as usize).min(samples.len());
+    let mut samples = samples.split_off(skip);
+    if let Some(end) = end_granule {
+        samples.truncate((end.saturating_sub(pre_skip) / granule_scale) as usize);
+    }
+
+    Ok((samples, sample_rate))
+}
+
+fn id_header(sample_rate: u32, pre_skip: u16) -> Vec<u8> {
+    let mut header = Vec::with_capacity(19);
+    header.extend_from_slice(b"OpusHead");
+    header.push(1); // version
+    header.push(1); // channels
+    header.extend_from_slice(&pre_skip.to_le_bytes());
+    header.extend_from_slice(&sample_rate.to_le_bytes());
+    header.extend_from_slice(&0i16.to_le_bytes()); // output gain
+    header.push(0); // mono/stereo channel mapping
+    header
+}
+
+fn comment_header() -> Vec<u8> {
+    let vendor = b"Handy";
+    let mut header = Vec::new();
+    header.extend_from_slice(b"OpusTags");
+    header.extend_from_slice(&(vendor.len() as u32).to_le_bytes());
+    header.extend_from_slice(vendor);
+    header.extend_from_slice(&0u32.to_le_bytes()); // no user comments
+    header
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Cursor;
+
+    #[test]
+    fn round_trips_length_and_rate() {
+        let samples: Vec<f32> = (0..16000)
+            .map(|i| (i as f32 / 16000.0 * 300.0 * std::f32::consts::TAU).sin() * 0.5)
+            .collect();
+
+        let mut encoded = Vec::new();
+        encode_ogg_opus(&mut encoded, &samples, 16000, 24000).unwrap();
+        // 24 kbit/s is about 3 kB per second
+        assert!(encoded.len() < 4000);
+
+        let (decoded, rate) = decode_ogg_opus(Cursor::new(encoded)).unwrap();
+        assert_eq!(rate, 16000);
+        assert_eq!(decoded.len(), samples.len());
+
+        // Lossy, but the tone should survive with roughly the same energy
+        let energy = |s: &[f32]| s.iter().map(|x| x * x).sum::<f32>() / s.len() as f32;
+        let ratio = energy(&decoded[1600..]) / energy(&samples[1600..]);
+        assert!((0.7..1.3).contains(&ratio), "energy ratio {ratio}");
+    }
+
+    #[test]
+    fn rejects_unsupported_rates() {
+        let mut encoded = Vec::new();
+        assert!(encode_ogg_opus(&mut encoded, &[0.0; 100], 44100, 24000).is_err());
+    }
+}
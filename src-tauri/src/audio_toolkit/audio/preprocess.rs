@@ -0,0 +1,390 @@
+use rustfft::{num_complex::Complex32, Fft, FftPlanner};
+use std::collections::VecDeque;
+use std::sync::Arc;
+
+// High-pass: removes DC offset and low-frequency rumble below speech
+const HIGH_PASS_CUTOFF_HZ: f32 = 80.0;
+
+// Noise reduction: ~32 ms analysis window with 50% overlap
+const NOISE_WINDOW_SECS: f32 = 0.032;
+const NOISE_OVER_SUBTRACTION: f32 = 4.0;
+const NOISE_GAIN_FLOOR: f32 = 0.1;
+const NOISE_FLOOR_RISE: f32 = 0.02;
+const NOISE_FLOOR_FALL: f32 = 0.1;
+
+// AGC: normalise speech towards -20 dBFS RMS, without boosting silence
+const AGC_TARGET_RMS: f32 = 0.1;
+const AGC_GATE_RMS: f32 = 0.003;
+const AGC_MIN_GAIN: f32 = 0.25;
+const AGC_MAX_GAIN: f32 = 10.0;
+const AGC_ATTACK: f32 = 0.5;
+const AGC_RELEASE: f32 = 0.05;
+const AGC_BLOCK_SECS: f32 = 0.01;
+
+/// Which preprocessing stages run on captured audio before VAD.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct PreprocessConfig {
+    pub high_pass: bool,
+    pub noise_reduction: bool,
+    pub auto_gain: bool,
+}
+
+impl PreprocessConfig {
+    pub fn is_enabled(&self) -> bool {
+        self.high_pass || self.noise_reduction || self.auto_gain
+    }
+}
+
+/// Runs the enabled stages in order: high-pass, noise reduction, AGC.
+///
+/// Output always has the same length as the input. Noise reduction delays
+/// the signal by [`AudioPreprocessor::latency`] samples; push that many zeros
+/// to flush the tail.
+pub struct AudioPreprocessor {
+    high_pass: Option<HighPassFilter>,
+    noise_reduction: Option<NoiseReducer>,
+    auto_gain: Option<AutoGain>,
+}
+
+impl AudioPreprocessor {
+    pub fn new(sample_rate: u32, config: PreprocessConfig) -> Self {
+        Self {
+            high_pass: config
+                .high_pass
+                .then(|| HighPassFilter::new(sample_rate, HIGH_PASS_CUTOFF_HZ)),
+            noise_reduction: config
+                .noise_reduction
+                .then(|| NoiseReducer::new(sample_rate)),
+            auto_gain: config.auto_gain.then(|| AutoGain::new(sample_rate)),
+        }
+    }
+
+    pub fn latency(&self) -> usize {
+        self.noise_reduction.as_ref().map_or(0, |nr| nr.latency())
+    }
+
+    pub fn process(&mut self, samples: &mut [f32]) {
+        if let Some(hp) = &mut self.high_pass {
+            hp.process(samples);
+        }
+        if let Some(nr) = &mut self.noise_reduction {
+            nr.process(samples);
+        }
+        if let Some(agc) = &mut self.auto_gain {
+            agc.process(samples);
+        }
+    }
+}
+
+/// Second-order Butterworth high-pass (RBJ biquad).
+struct HighPassFilter {
+    b0: f32,
+    b1: f32,
+    b2: f32,
+    a1: f32,
+    a2: f32,
+    x1: f32,
+    x2: f32,
+    y1: f32,
+    y2: f32,
+}
+
+impl HighPassFilter {
+    fn new(sample_rate: u32, cutoff_hz: f32) -> Self {
+        let w0 = 2.0 * std::f32::consts::PI * cutoff_hz / sample_rate as f32;
+        let alpha = w0.sin() / (2.0 * std::f32::consts::FRAC_1_SQRT_2);
+        let cos_w0 = w0.cos();
+        let a0 = 1.0 + alpha;
+
+        Self {
+            b0: (1.0 + cos_w0) / 2.0 / a0,
+            b1: -(1.0 + cos_w0) / a0,
+            b2: (1.0 + cos_w0) / 2.0 / a0,
+            a1: -2.0 * cos_w0 / a0,
+            a2: (1.0 - alpha) / a0,
+            x1: 0.0,
+            x2: 0.0,
+            y1: 0.0,
+            y2: 0.0,
+        }
+    }
+
+    fn process(&mut self, samples: &mut [f32]) {
+        for sample in samples.iter_mut() {
+            let x = *sample;
+            let y = self.b0 * x + self.b1 * self.x1 + self.b2 * self.x2
+                - self.a1 * self.y1
+                - self.a2 * self.y2;
+            self.x2 = self.x1;
+            self.x1 = x;
+            self.y2 = self.y1;
+            self.y1 = y;
+            *sample = y;
+        }
+    }
+}
+
+/// Streaming spectral subtraction with a minimum-tracking noise estimate.
+struct NoiseReducer {
+    fft: Arc<dyn Fft<f32>>,
+    ifft: Arc<dyn Fft<f32>>,
+    window: Vec<f32>,
+    window_size: usize,
+    hop: usize,
+    /// Previous frame plus the input samples received since it was processed
+    history: Vec<f32>,
+    overlap: Vec<f32>,
+    output: VecDeque<f32>,
+    noise: Vec<f32>,
+    spectrum: Vec<Complex32>,
+}
+
+impl NoiseReducer {
+    fn new(sample_rate: u32) -> Self {
+        let window_size = ((sample_rate as f32 * NOISE_WINDOW_SECS) as usize)
+            .next_power_of_two()
+            .max(4);
+        let hop = window_size / 2;
+
+        let mut planner = FftPlanner::<f32>::new();
+        let fft = planner.plan_fft_forward(window_size);
+        let ifft = planner.plan_fft_inverse(window_size);
+
+        // Square-root periodic Hann, applied on analysis and synthesis, so the
+        // overlapped windows sum to one
+        let window = (0..window_size)
+            .map(|i| {
+                let hann = 0.5
+                    * (1.0 - (2.0 * std::f32::consts::PI * i as f32 / window_size as f32).cos());
+                hann.sqrt()
+            })
+            .collect();
+
+        Self {
+            fft,
+            ifft,
+            window,
+            window_size,
+            hop,
+            history: vec![0.0; window_size],
+            overlap: vec![0.0; window_size],
+            output: VecDeque::from(vec![0.0; hop]),
+            noise: vec![0.0; window_size / 2 + 1],
+            spectrum: vec![Complex32::new(0.0, 0.0); window_size],
+        }
+    }
+
+    fn latency(&self) -> usize {
+        self.window_size
+    }
+
+    fn process(&mut self, samples: &mut [f32]) {
+        for sample in samples.iter_mut() {
+            self.history.push(*sample);
+
+            if self.history.len() == self.window_size + self.hop {
+                self.history.drain(..self.hop);
+                self.process_frame();
+            }
+
+            *sample = self.output.pop_front().unwrap_or(0.0);
+        }
+    }
+
+    fn process_frame(&mut self) {
+        for ((bin, &x), &w) in self
+            .spectrum
+            .iter_mut()
+            .zip(&self.history)
+            .zip(&self.window)
+        {
+            *bin = Complex32::new(x * w, 0.0);
+        }
+        self.fft.process(&mut self.spectrum);
+
+        let bins = self.window_size / 2 + 1;
+
+        for k in 0..bins {
+            let magnitude = self.spectrum[k].norm();
+
+            // Track drops faster than rises, so speech doesn't get absorbed
+            // into the noise estimate
+            let noise = &mut self.noise[k];
+            let rate = if magnitude < *noise {
+                NOISE_FLOOR_FALL
+            } else {
+                NOISE_FLOOR_RISE
+            };
+            *noise += (magnitude - *noise) * rate;
+
+            let gain = if magnitude > f32::EPSILON {
+                let ratio = *noise / magnitude;
+                (1.0 - NOISE_OVER_SUBTRACTION * ratio * ratio)
+                    .max(NOISE_GAIN_FLOOR * NOISE_GAIN_FLOOR)
+                    .sqrt()
+            } else {
+                NOISE_GAIN_FLOOR
+            };
+
+            self.spectrum[k] *= gain;
+            // Keep the spectrum conjugate-symmetric for a real output
+            if k > 0 && k < self.window_size - k {
+                self.spectrum[self.window_size - k] = self.spectrum[k].conj();
+            }
+        }
+
+        self.ifft.process(&mut self.spectrum);
+
+        let scale = 1.0 / self.window_size as f32;
+        for ((acc, bin), &w) in self
+            .overlap
+            .iter_mut()
+            .zip(&self.spectrum)
+            .zip(&self.window)
+        {
+            *acc += bin.re * scale * w;
+        }
+
+        self.output.extend(self.overlap.drain(..self.hop));
+        self.overlap.resize(self.window_size, 0.0);
+    }
+}
+
+/// Block-based automatic gain control with a silence gate.
+struct AutoGain {
+    block: usize,
+    gain: f32,
+}
+
+impl AutoGain {
+    fn new(sample_rate: u32) -> Self {
+        Self {
+            block: ((sample_rate as f32 * AGC_BLOCK_SECS) as usize).max(1),
+            gain: 1.0,
+        }
+    }
+
+    fn process(&mut self, samples: &mut [f32]) {
+        for chunk in samples.chunks_mut(self.block) {
+            let rms = (chunk.iter().map(|s| s * s).sum::<f32>() / chunk.len() as f32).sqrt();
+
+            let start_gain = self.gain;
+            if rms > AGC_GATE_RMS {
+                let desired = (AGC_TARGET_RMS / rms).clamp(AGC_MIN_GAIN, AGC_MAX_GAIN);
+                let rate = if desired < self.gain {
+                    AGC_ATTACK
+                } else {
+                    AGC_RELEASE
+                };
+                self.gain += (desired - self.gain) * rate;
+            }
+
+            // Ramp across the block to avoid zipper noise
+            let step = (self.gain - start_gain) / chunk.len() as f32;
+            for (i, sample) in chunk.iter_mut().enumerate() {
+                let gain = start_gain + step * (i + 1) as f32;
+                *sample = (*sample * gain).clamp(-1.0, 1.0);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const RATE: u32 = 16000;
+
+    fn sine(freq: f32, amplitude: f32, len: usize) -> Vec<f32> {
+        (0..len)
+            .map(|i| amplitude * (2.0 * std::f32::consts::PI * freq * i as f32 / RATE as f32).sin())
+            .collect()
+    }
+
+    fn rms(samples: &[f32]) -> f32 {
+        (samples.iter().map(|s| s * s).sum::<f32>() / samples.len() as f32).sqrt()
+    }
+
+    #[test]
+    fn disabled_stages_pass_audio_through() {
+        let mut pre = AudioPreprocessor::new(RATE, PreprocessConfig::default());
+        let input = sine(440.0, 0.3, 480);
+        let mut output = input.clone();
+        pre.process(&mut output);
+        assert_eq!(output, input);
+        assert_eq!(pre.latency(), 0);
+    }
+
+    #[test]
+    fn high_pass_removes_dc_offset() {
+        let mut filter = HighPassFilter::new(RATE, HIGH_PASS_CUTOFF_HZ);
+        let mut samples = vec![0.5; RATE as usize];
+        filter.process(&mut samples);
+        assert!(samples[samples.len() - 480..]
+            .iter()
+            .all(|s| s.abs() < 1e-3));
+    }
+
+    #[test]
+    fn noise_reduction_keeps_length_and_delays_by_latency() {
+        let mut reducer = NoiseReducer::new(RATE);
+        let latency = reducer.latency();
+        let mut samples = vec![0.0; 480];
+        samples[0] = 1.0;
+        reducer.process(&mut samples);
+        assert_eq!(samples.len(), 480);
+        assert!(samples[..latency.min(480)].iter().all(|&s| s == 0.0));
+    }
+
+    #[test]
+    fn noise_reduction_attenuates_steady_noise() {
+        let mut reducer = NoiseReducer::new(RATE);
+        // Deterministic pseudo-random hiss
+        let mut state = 1u32;
+        let mut noise: Vec<f32> = (0..RATE as usize * 2)
+            .map(|_| {
+                state = state.wrapping_mul(1_103_515_245).wrapping_add(12_345);
+                ((state >> 16) as f32 / 32768.0 - 1.0) * 0.05
+            })
+            .collect();
+        let before = rms(&noise[RATE as usize..]);
+        reducer.process(&mut noise);
+        let after = rms(&noise[RATE as usize..]);
+        assert!(after < before * 0.5, "before {before}, after {after}");
+    }
+
+    #[test]
+    fn noise_reduction_preserves_intermittent_tone() {
+        let mut reducer = NoiseReducer::new(RATE);
+        // 300 ms bursts separated by 300 ms of silence, like syllables
+        let burst = (RATE as usize * 3) / 10;
+        let mut samples: Vec<f32> = sine(1000.0, 0.3, RATE as usize * 3)
+            .into_iter()
+            .enumerate()
+            .map(|(i, s)| if (i / burst) % 2 == 1 { 0.0 } else { s })
+            .collect();
+        let before = rms(&samples);
+
+        reducer.process(&mut samples);
+        let mut tail = vec![0.0; reducer.latency()];
+        reducer.process(&mut tail);
+        samples.drain(..tail.len());
+        samples.extend(tail);
+
+        let after = rms(&samples);
+        assert!(after > before * 0.8, "before {before}, after {after}");
+    }
+
+    #[test]
+    fn auto_gain_boosts_quiet_speech_but_not_silence() {
+        let mut agc = AutoGain::new(RATE);
+        let mut quiet = sine(300.0, 0.02, RATE as usize * 2);
+        agc.process(&mut quiet);
+        assert!(rms(&quiet[RATE as usize..]) > 0.05);
+
+        let mut agc = AutoGain::new(RATE);
+        let mut silence = vec![0.001; 4800];
+        agc.process(&mut silence);
+        assert!(silence.iter().all(|&s| (s - 0.001).abs() < 1e-6));
+    }
+}
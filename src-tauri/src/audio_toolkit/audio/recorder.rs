@@ -1,6 +1,11 @@
 use std::{
+    collections::VecDeque,
     io::Error,
-    sync::{mpsc, Arc, Mutex},
+    path::PathBuf,
+    sync::{
+        atomic::{AtomicU32, AtomicUsize, Ordering},
+        mpsc, Arc, Mutex,
+    },
     time::Duration,
 };
 
@@ -10,15 +15,74 @@ use cpal::{
 };
 
 use crate::audio_toolkit::{
-    audio::{AudioVisualiser, FrameResampler},
+    audio::{
+        AudioPreprocessor, AudioSource, AudioVisualiser, FrameResampler, InputLevel, LevelMeter,
+        PreprocessConfig, PulseMonitorSource, RecordingJournal, RunningSource, SpillBuffer,
+    },
     constants,
     vad::{self, VadFrame},
     VoiceActivityDetector,
 };
 
+/// How multi-channel input is reduced to the mono signal fed to the pipeline.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum ChannelMix {
+    /// Average all channels.
+    #[default]
+    Average,
+    /// Use whichever channel carries the most energy in each callback buffer.
+    Loudest,
+    /// Use a single channel by zero-based index.
+    Channel(usize),
+}
+
+/// Reported while recording against a maximum duration.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum RecordingLimitEvent {
+    /// The limit will be reached after `remaining` more audio.
+    Approaching { remaining: Duration },
+    /// The limit was reached; later audio is dropped until the recording stops.
+    Reached,
+}
+
+/// Levels of a finished recording, measured before preprocessing.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct RecordingLevels {
+    /// All input, pauses between words included.
+    pub input: InputLevel,
+    /// Only the frames voice activity detection kept as speech; `None` when
+    /// there were none.
+    pub speech: Option<InputLevel>,
+}
+
+/// Software gain shared with the worker, so it changes without reopening
+/// the stream.
+#[derive(Clone)]
+struct SharedGain(Arc<AtomicU32>);
+
+impl SharedGain {
+    fn new(gain: f32) -> Self {
+        Self(Arc::new(AtomicU32::new(gain.to_bits())))
+    }
+
+    fn get(&self) -> f32 {
+        f32::from_bits(self.0.load(Ordering::Relaxed))
+    }
+
+    fn set(&self, gain: f32) {
+        self.0.store(gain.to_bits(), Ordering::Relaxed);
+    }
+}
+
 enum Cmd {
-    Start,
+    /// Start recording, optionally journaling accepted audio to the given file.
+    Start(Option<PathBuf>),
+    /// Stop capturing without ending the recording.
+    Pause,
+    Resume,
     Stop(mpsc::Sender<Vec<f32>>),
+    /// Stop once the source has delivered its last sample.
+    StopAtEnd(mpsc::Sender<Vec<f32>>),
     Shutdown,
 }
 
@@ -28,6 +92,18 @@ pub struct AudioRecorder {
     worker_handle: Option<std::thread::JoinHandle<()>>,
     vad: Option<Arc<Mutex<Box<dyn vad::VoiceActivityDetector>>>>,
     level_cb: Option<Arc<dyn Fn(Vec<f32>) + Send + Sync + 'static>>,
+    meter_cb: Option<Arc<dyn Fn(InputLevel) + Send + Sync + 'static>>,
+    error_cb: Option<Arc<dyn Fn(String) + Send + Sync + 'static>>,
+    limit_cb: Option<Arc<dyn Fn(RecordingLimitEvent) + Send + Sync + 'static>>,
+    channel_mix: ChannelMix,
+    preprocess: PreprocessConfig,
+    gain: SharedGain,
+    monitor_source: Option<String>,
+    max_duration: Option<Duration>,
+    spill_after: Option<Duration>,
+    spill_dir: PathBuf,
+    captured: Arc<AtomicUsize>,
+    recorded_levels: Arc<Mutex<Option<RecordingLevels>>>,
 }
 
 impl AudioRecorder {
@@ -38,6 +114,18 @@ impl AudioRecorder {
             worker_handle: None,
             vad: None,
             level_cb: None,
+            meter_cb: None,
+            error_cb: None,
+            limit_cb: None,
+            channel_mix: ChannelMix::default(),
+            preprocess: PreprocessConfig::default(),
+            gain: SharedGain::new(1.0),
+            monitor_source: None,
+            max_duration: None,
+            spill_after: None,
+            spill_dir: std::env::temp_dir(),
+            captured: Arc::new(AtomicUsize::new(0)),
+            recorded_levels: Arc::new(Mutex::new(None)),
         })
     }
 
@@ -54,15 +142,84 @@ impl AudioRecorder {
         self
     }
 
+    /// Registers a callback receiving RMS/peak levels and clip counts for
+    /// every metering window of raw input (after software gain).
+    pub fn with_meter_callback<F>(mut self, cb: F) -> Self
+    where
+        F: Fn(InputLevel) + Send + Sync + 'static,
+    {
+        self.meter_cb = Some(Arc::new(cb));
+        self
+    }
+
+    /// Registers a callback invoked from the audio thread whenever the input
+    /// stream reports an error (for example when the device is unplugged).
+    /// The callback must not block; hand the error off to another thread.
+    pub fn with_error_callback<F>(mut self, cb: F) -> Self
+    where
+        F: Fn(String) + Send + Sync + 'static,
+    {
+        self.error_cb = Some(Arc::new(cb));
+        self
+    }
+
+    /// Registers a callback invoked from the audio thread as a recording nears
+    /// and reaches its maximum duration. Like the error callback, it must not
+    /// block.
+    pub fn with_limit_callback<F>(mut self, cb: F) -> Self
+    where
+        F: Fn(RecordingLimitEvent) + Send + Sync + 'static,
+    {
+        self.limit_cb = Some(Arc::new(cb));
+        self
+    }
+
+    /// Sets the channel downmix used by streams opened after this call.
+    pub fn set_channel_mix(&mut self, channel_mix: ChannelMix) {
+        self.channel_mix = channel_mix;
+    }
+
+    /// Sets the preprocessing stages used by streams opened after this call.
+    pub fn set_preprocess_config(&mut self, preprocess: PreprocessConfig) {
+        self.preprocess = preprocess;
+    }
+
+    /// Sets the software gain multiplier, taking effect on an open stream
+    /// right away.
+    pub fn set_gain(&self, gain: f32) {
+        self.gain.set(gain);
+    }
+
+    /// Sets a PulseAudio/PipeWire monitor source to mix into the microphone
+    /// for streams opened after this call.
+    pub fn set_monitor_source(&mut self, monitor_source: Option<String>) {
+        self.monitor_source = monitor_source;
+    }
+
+    /// Caps each recording made on streams opened after this call. Audio past
+    /// the limit is dropped, and the limit callback is told shortly before and
+    /// once it is reached.
+    pub fn set_max_duration(&mut self, max_duration: Option<Duration>) {
+        self.max_duration = max_duration;
+    }
+
+    /// Moves recorded audio beyond this much to a temporary file, for streams
+    /// opened after this call. While recording, memory then holds at most this
+    /// much audio; `stop` still returns the whole recording in one buffer.
+    pub fn set_spill_after(&mut self, spill_after: Option<Duration>) {
+        self.spill_after = spill_after;
+    }
+
+    /// Directory spilled audio is written to, the system temp dir by default.
+    pub fn set_spill_dir(&mut self, spill_dir: PathBuf) {
+        self.spill_dir = spill_dir;
+    }
+
     pub fn open(&mut self, device: Option<Device>) -> Result<(), Box<dyn std::error::Error>> {
         if self.worker_handle.is_some() {
             return Ok(()); // already open
         }
 
-        let (sample_tx, sample_rx) = mpsc::channel::<Vec<f32>>();
-        let (cmd_tx, cmd_rx) = mpsc::channel::<Cmd>();
-        let (init_tx, init_rx) = mpsc::sync_channel::<Result<(), String>>(1);
-
         let host = crate::audio_toolkit::get_cpal_host();
         let device = match device {
             Some(dev) => dev,
@@ -71,81 +228,145 @@ impl AudioRecorder {
                 .ok_or_else(|| Error::new(std::io::ErrorKind::NotFound, "No input device found"))?,
         };
 
-        let thread_device = device.clone();
-        let vad = self.vad.clone();
-        // Move the optional level callback into the worker thread
-        let level_cb = self.level_cb.clone();
+        let source = CpalSource {
+            device: device.clone(),
+            channel_mix: self.channel_mix,
+            error_cb: self.error_cb.clone(),
+        };
+        self.spawn_worker(Box::new(source), None)?;
+        self.device = Some(device);
+        Ok(())
+    }
 
-        let worker = std::thread::spawn(move || {
-            let init_result = (|| -> Result<(cpal::Stream, u32), String> {
-                let config = AudioRecorder::get_preferred_config(&thread_device)
-                    .map_err(|e| format!("Failed to fetch preferred config: {e}"))?;
-
-                let sample_rate = config.sample_rate().0;
-                let channels = config.channels() as usize;
-
-                log::info!(
-                    "Using device: {:?}\nSample rate: {}\nChannels: {}\nFormat: {:?}",
-                    thread_device.name(),
-                    sample_rate,
-                    channels,
-                    config.sample_format()
-                );
-
-                let stream = match config.sample_format() {
-                    cpal::SampleFormat::U8 => AudioRecorder::build_stream::<u8>(
-                        &thread_device,
-                        &config,
-                        sample_tx,
-                        channels,
-                    )
-                    .map_err(|e| format!("Failed to build input stream: {e}"))?,
-                    cpal::SampleFormat::I8 => AudioRecorder::build_stream::<i8>(
-                        &thread_device,
-                        &config,
-                        sample_tx,
-                        channels,
-                    )
-                    .map_err(|e| format!("Failed to build input stream: {e}"))?,
-                    cpal::SampleFormat::I16 => AudioRecorder::build_stream::<i16>(
-                        &thread_device,
-                        &config,
-                        sample_tx,
-                        channels,
-                    )
-                    .map_err(|e| format!("Failed to build input stream: {e}"))?,
-                    cpal::SampleFormat::I32 => AudioRecorder::build_stream::<i32>(
-                        &thread_device,
-                        &config,
-                        sample_tx,
-                        channels,
-                    )
-                    .map_err(|e| format!("Failed to build input stream: {e}"))?,
-                    cpal::SampleFormat::F32 => AudioRecorder::build_stream::<f32>(
-                        &thread_device,
-                        &config,
-                        sample_tx,
-                        channels,
-                    )
-                    .map_err(|e| format!("Failed to build input stream: {e}"))?,
-                    sample_format => {
-                        return Err(format!("Unsupported sample format: {sample_format:?}"));
-                    }
-                };
+    /// Opens the recorder on a PulseAudio/PipeWire monitor source instead of a
+    /// microphone. Capture ending unexpectedly is reported like a stream error.
+    pub fn open_monitor(&mut self, source: &str) -> Result<(), Box<dyn std::error::Error>> {
+        if self.worker_handle.is_some() {
+            return Ok(()); // already open
+        }
+        let monitor = PulseMonitorSource::new(source, self.error_cb.clone());
+        self.spawn_worker(Box::new(monitor), None)
+    }
+
+    /// Opens the recorder on any [`AudioSource`], running its samples through
+    /// the same pipeline as a microphone.
+    pub fn open_source(
+        &mut self,
+        source: Box<dyn AudioSource>,
+    ) -> Result<(), Box<dyn std::error::Error>> {
+        if self.worker_handle.is_some() {
+            return Ok(()); // already open
+        }
+        self.spawn_worker(source, None)
+    }
+
+    /// Records a finite source from its first sample to its last and closes
+    /// the recorder again, returning what `stop` would have.
+    pub fn record_source(
+        &mut self,
+        source: Box<dyn AudioSource>,
+    ) -> Result<Vec<f32>, Box<dyn std::error::Error>> {
+        self.record_to_end(source, None)
+    }
 
-                stream
-                    .play()
-                    .map_err(|e| format!("Failed to start microphone stream: {e}"))?;
+    /// Like [`record_source`](Self::record_source), journaling the recording
+    /// the way [`start_journaled`](Self::start_journaled) does.
+    pub fn record_source_journaled(
+        &mut self,
+        source: Box<dyn AudioSource>,
+        journal: PathBuf,
+    ) -> Result<Vec<f32>, Box<dyn std::error::Error>> {
+        self.record_to_end(source, Some(journal))
+    }
+
+    fn record_to_end(
+        &mut self,
+        source: Box<dyn AudioSource>,
+        journal: Option<PathBuf>,
+    ) -> Result<Vec<f32>, Box<dyn std::error::Error>> {
+        if self.worker_handle.is_some() {
+            return Err("Recorder is already open".into());
+        }
+        self.spawn_worker(source, Some(Cmd::Start(journal)))?;
 
-                Ok((stream, sample_rate))
-            })();
+        let (resp_tx, resp_rx) = mpsc::channel();
+        let sent = match &self.cmd_tx {
+            Some(tx) => tx.send(Cmd::StopAtEnd(resp_tx)).is_ok(),
+            None => false,
+        };
+        let samples = if sent { resp_rx.recv().ok() } else { None };
+        self.close()?;
+
+        samples.ok_or_else(|| "Recorder worker stopped unexpectedly".into())
+    }
+
+    fn spawn_worker(
+        &mut self,
+        source: Box<dyn AudioSource>,
+        start: Option<Cmd>,
+    ) -> Result<(), Box<dyn std::error::Error>> {
+        let (sample_tx, sample_rx) = mpsc::channel::<Vec<f32>>();
+        let (cmd_tx, cmd_rx) = mpsc::channel::<Cmd>();
+        let (init_tx, init_rx) = mpsc::sync_channel::<Result<(), String>>(1);
+
+        // Queued before the worker runs, so recording covers the first sample
+        if let Some(start) = start {
+            cmd_tx.send(start)?;
+        }
+
+        // Move the VAD and optional callbacks into the worker thread
+        let config = PipelineConfig {
+            vad: self.vad.clone(),
+            preprocess: self.preprocess,
+            gain: self.gain.clone(),
+            level_cb: self.level_cb.clone(),
+            meter_cb: self.meter_cb.clone(),
+            limit: RecordingLimit::new(self.max_duration, self.limit_cb.clone()),
+            spill_threshold: self.spill_after.map(whisper_samples),
+            spill_dir: self.spill_dir.clone(),
+            captured: self.captured.clone(),
+            recorded_levels: self.recorded_levels.clone(),
+        };
+        let monitor_source = self.monitor_source.clone();
+
+        let worker = std::thread::spawn(move || {
+            let init_result = source.start(sample_tx);
 
             match init_result {
-                Ok((stream, sample_rate)) => {
+                Ok(running) => {
+                    // The monitor is optional: if it can't be opened, keep recording
+                    // from the microphone alone. Its failures only end the mix, so
+                    // they don't go to the microphone's error callback
+                    let monitor = monitor_source.and_then(|source| {
+                        let (monitor_tx, monitor_rx) = mpsc::channel::<Vec<f32>>();
+                        match Box::new(PulseMonitorSource::new(source.as_str(), None))
+                            .start(monitor_tx)
+                        {
+                            Ok(running) => {
+                                let mixer = MonitorMixer::new(running.sample_rate, monitor_rx);
+                                Some((running, mixer))
+                            }
+                            Err(e) => {
+                                log::warn!("Failed to open monitor source {source}: {e}");
+                                None
+                            }
+                        }
+                    });
+                    let (monitor_capture, monitor_mixer) = monitor.unzip();
+
                     let _ = init_tx.send(Ok(()));
-                    // Keep the stream alive while we process samples.
-                    run_consumer(sample_rate, vad, sample_rx, cmd_rx, level_cb);
-                    drop(stream);
+                    // Keep the source alive while we process samples.
+                    run_consumer(
+                        InputFeed {
+                            sample_rate: running.sample_rate,
+                            sample_rx,
+                        },
+                        monitor_mixer,
+                        cmd_rx,
+                        config,
+                    );
+                    drop(monitor_capture);
+                    drop(running);
                 }
                 Err(error_message) => {
                     let normalized_error = normalize_microphone_error(error_message);
@@ -157,7 +378,6 @@ impl AudioRecorder {
 
         match init_rx.recv() {
             Ok(Ok(())) => {
-                self.device = Some(device);
                 self.cmd_tx = Some(cmd_tx);
                 self.worker_handle = Some(worker);
                 Ok(())
@@ -183,19 +403,60 @@ impl AudioRecorder {
 
     pub fn start(&self) -> Result<(), Box<dyn std::error::Error>> {
         if let Some(tx) = &self.cmd_tx {
-            tx.send(Cmd::Start)?;
+            tx.send(Cmd::Start(None))?;
         }
         Ok(())
     }
 
-    pub fn stop(&self) -> Result<Vec<f32>, Box<dyn std::error::Error>> {
-        let (resp_tx, resp_rx) = mpsc::channel();
+    /// Starts recording and appends every accepted sample to `journal` as it
+    /// is recorded. An existing journal is appended to.
+    pub fn start_journaled(&self, journal: PathBuf) -> Result<(), Box<dyn std::error::Error>> {
+        if let Some(tx) = &self.cmd_tx {
+            tx.send(Cmd::Start(Some(journal)))?;
+        }
+        Ok(())
+    }
+
+    /// Stops capturing audio while keeping what was recorded so far. Nothing
+    /// heard while paused ends up in the recording.
+    pub fn pause(&self) -> Result<(), Box<dyn std::error::Error>> {
+        if let Some(tx) = &self.cmd_tx {
+            tx.send(Cmd::Pause)?;
+        }
+        Ok(())
+    }
+
+    /// Continues a paused recording, appending to what was recorded before.
+    pub fn resume(&self) -> Result<(), Box<dyn std::error::Error>> {
         if let Some(tx) = &self.cmd_tx {
-            tx.send(Cmd::Stop(resp_tx))?;
+            tx.send(Cmd::Resume)?;
         }
+        Ok(())
+    }
+
+    pub fn stop(&self) -> Result<Vec<f32>, Box<dyn std::error::Error>> {
+        let Some(tx) = &self.cmd_tx else {
+            return Err("Recorder is not open".into());
+        };
+        let (resp_tx, resp_rx) = mpsc::channel();
+        tx.send(Cmd::Stop(resp_tx))?;
         Ok(resp_rx.recv()?) // wait for the samples
     }
 
+    /// How much audio the current or last recording captured, including what
+    /// voice activity detection left out but not paused time.
+    pub fn captured_duration(&self) -> Duration {
+        Duration::from_secs_f64(
+            self.captured.load(Ordering::Relaxed) as f64 / constants::WHISPER_SAMPLE_RATE as f64,
+        )
+    }
+
+    /// Levels of the input over the last finished recording, measured before
+    /// preprocessing.
+    pub fn recorded_levels(&self) -> Option<RecordingLevels> {
+        *self.recorded_levels.lock().unwrap()
+    }
+
     pub fn close(&mut self) -> Result<(), Box<dyn std::error::Error>> {
         if let Some(tx) = self.cmd_tx.take() {
             let _ = tx.send(Cmd::Shutdown);
@@ -206,89 +467,220 @@ impl AudioRecorder {
         self.device = None;
         Ok(())
     }
+}
 
-    fn build_stream<T>(
-        device: &cpal::Device,
-        config: &cpal::SupportedStreamConfig,
-        sample_tx: mpsc::Sender<Vec<f32>>,
-        channels: usize,
-    ) -> Result<cpal::Stream, cpal::BuildStreamError>
-    where
-        T: Sample + SizedSample + Send + 'static,
-        f32: cpal::FromSample<T>,
-    {
-        let mut output_buffer = Vec::new();
+/// Records from a cpal input device.
+struct CpalSource {
+    device: Device,
+    channel_mix: ChannelMix,
+    error_cb: Option<Arc<dyn Fn(String) + Send + Sync + 'static>>,
+}
 
-        let stream_cb = move |data: &[T], _: &cpal::InputCallbackInfo| {
-            output_buffer.clear();
+impl AudioSource for CpalSource {
+    fn start(self: Box<Self>, sample_tx: mpsc::Sender<Vec<f32>>) -> Result<RunningSource, String> {
+        let (stream, sample_rate) =
+            open_input_stream(&self.device, sample_tx, self.channel_mix, self.error_cb)?;
+        Ok(RunningSource::new(sample_rate, stream))
+    }
+}
 
-            if channels == 1 {
-                // Direct conversion without intermediate Vec
-                output_buffer.extend(data.iter().map(|&sample| sample.to_sample::<f32>()));
-            } else {
-                // Convert to mono directly
-                let frame_count = data.len() / channels;
-                output_buffer.reserve(frame_count);
-
-                for frame in data.chunks_exact(channels) {
-                    let mono_sample = frame
-                        .iter()
-                        .map(|&sample| sample.to_sample::<f32>())
-                        .sum::<f32>()
-                        / channels as f32;
-                    output_buffer.push(mono_sample);
-                }
+/// Builds and starts an input stream on `device`, returning it together
+/// with its sample rate.
+fn open_input_stream(
+    device: &cpal::Device,
+    sample_tx: mpsc::Sender<Vec<f32>>,
+    channel_mix: ChannelMix,
+    error_cb: Option<Arc<dyn Fn(String) + Send + Sync + 'static>>,
+) -> Result<(cpal::Stream, u32), String> {
+    let config = get_preferred_config(device)
+        .map_err(|e| format!("Failed to fetch preferred config: {e}"))?;
+
+    let sample_rate = config.sample_rate().0;
+    let channels = config.channels() as usize;
+
+    log::info!(
+        "Using device: {:?}\nSample rate: {}\nChannels: {}\nFormat: {:?}",
+        device.name(),
+        sample_rate,
+        channels,
+        config.sample_format()
+    );
+
+    let stream = match config.sample_format() {
+        cpal::SampleFormat::U8 => {
+            build_stream::<u8>(device, &config, sample_tx, channels, channel_mix, error_cb)
+        }
+        cpal::SampleFormat::I8 => {
+            build_stream::<i8>(device, &config, sample_tx, channels, channel_mix, error_cb)
+        }
+        cpal::SampleFormat::I16 => {
+            build_stream::<i16>(device, &config, sample_tx, channels, channel_mix, error_cb)
+        }
+        cpal::SampleFormat::I32 => {
+            build_stream::<i32>(device, &config, sample_tx, channels, channel_mix, error_cb)
+        }
+        cpal::SampleFormat::F32 => {
+            build_stream::<f32>(device, &config, sample_tx, channels, channel_mix, error_cb)
+        }
+        sample_format => {
+            return Err(format!("Unsupported sample format: {sample_format:?}"));
+        }
+    }
+    .map_err(|e| format!("Failed to build input stream: {e}"))?;
+
+    stream
+        .play()
+        .map_err(|e| format!("Failed to start microphone stream: {e}"))?;
+
+    Ok((stream, sample_rate))
+}
+
+fn build_stream<T>(
+    device: &cpal::Device,
+    config: &cpal::SupportedStreamConfig,
+    sample_tx: mpsc::Sender<Vec<f32>>,
+    channels: usize,
+    channel_mix: ChannelMix,
+    error_cb: Option<Arc<dyn Fn(String) + Send + Sync + 'static>>,
+) -> Result<cpal::Stream, cpal::BuildStreamError>
+where
+    T: Sample + SizedSample + Send + 'static,
+    f32: cpal::FromSample<T>,
+{
+    let channel_mix = match channel_mix {
+        ChannelMix::Channel(index) if index >= channels => {
+            log::warn!(
+                "Channel {} not available on a {}-channel device, using the last channel",
+                index,
+                channels
+            );
+            ChannelMix::Channel(channels - 1)
+        }
+        other => other,
+    };
+
+    let mut output_buffer = Vec::new();
+
+    let stream_cb = move |data: &[T], _: &cpal::InputCallbackInfo| {
+        downmix_into(data, channels, channel_mix, &mut output_buffer);
+
+        if sample_tx.send(output_buffer.clone()).is_err() {
+            log::error!("Failed to send samples");
+        }
+    };
+
+    device.build_input_stream(
+        &config.clone().into(),
+        stream_cb,
+        move |err| {
+            log::error!("Stream error: {}", err);
+            if let Some(cb) = &error_cb {
+                cb(err.to_string());
             }
+        },
+        None,
+    )
+}
+
+fn get_preferred_config(
+    device: &cpal::Device,
+) -> Result<cpal::SupportedStreamConfig, Box<dyn std::error::Error>> {
+    let supported_configs = device.supported_input_configs()?;
+    let mut best_config: Option<cpal::SupportedStreamConfigRange> = None;
 
-            if sample_tx.send(output_buffer.clone()).is_err() {
-                log::error!("Failed to send samples");
+    // Try to find a config that supports 16kHz, prioritizing better formats
+    for config_range in supported_configs {
+        if config_range.min_sample_rate().0 <= constants::WHISPER_SAMPLE_RATE
+            && config_range.max_sample_rate().0 >= constants::WHISPER_SAMPLE_RATE
+        {
+            match best_config {
+                None => best_config = Some(config_range),
+                Some(ref current) => {
+                    // Prioritize F32 > I16 > I32 > others
+                    let score = |fmt: cpal::SampleFormat| match fmt {
+                        cpal::SampleFormat::F32 => 4,
+                        cpal::SampleFormat::I16 => 3,
+                        cpal::SampleFormat::I32 => 2,
+                        _ => 1,
+                    };
+
+                    if score(config_range.sample_format()) > score(current.sample_format()) {
+                        best_config = Some(config_range);
+                    }
+                }
             }
-        };
+        }
+    }
 
-        device.build_input_stream(
-            &config.clone().into(),
-            stream_cb,
-            |err| log::error!("Stream error: {}", err),
-            None,
-        )
+    if let Some(config) = best_config {
+        return Ok(config.with_sample_rate(cpal::SampleRate(constants::WHISPER_SAMPLE_RATE)));
     }
 
-    fn get_preferred_config(
-        device: &cpal::Device,
-    ) -> Result<cpal::SupportedStreamConfig, Box<dyn std::error::Error>> {
-        let supported_configs = device.supported_input_configs()?;
-        let mut best_config: Option<cpal::SupportedStreamConfigRange> = None;
+    // If no config supports 16kHz, fall back to default
+    Ok(device.default_input_config()?)
+}
 
-        // Try to find a config that supports 16kHz, prioritizing better formats
-        for config_range in supported_configs {
-            if config_range.min_sample_rate().0 <= constants::WHISPER_SAMPLE_RATE
-                && config_range.max_sample_rate().0 >= constants::WHISPER_SAMPLE_RATE
-            {
-                match best_config {
-                    None => best_config = Some(config_range),
-                    Some(ref current) => {
-                        // Prioritize F32 > I16 > I32 > others
-                        let score = |fmt: cpal::SampleFormat| match fmt {
-                            cpal::SampleFormat::F32 => 4,
-                            cpal::SampleFormat::I16 => 3,
-                            cpal::SampleFormat::I32 => 2,
-                            _ => 1,
-                        };
-
-                        if score(config_range.sample_format()) > score(current.sample_format()) {
-                            best_config = Some(config_range);
-                        }
-                    }
+/// Converts an interleaved input buffer to mono according to `channel_mix`.
+fn downmix_into<T>(data: &[T], channels: usize, channel_mix: ChannelMix, out: &mut Vec<f32>)
+where
+    T: Sample,
+    f32: cpal::FromSample<T>,
+{
+    out.clear();
+
+    if channels == 1 {
+        // Direct conversion without intermediate Vec
+        out.extend(data.iter().map(|&sample| sample.to_sample::<f32>()));
+        return;
+    }
+
+    let frame_count = data.len() / channels;
+    out.reserve(frame_count);
+
+    let selected = match channel_mix {
+        ChannelMix::Average => None,
+        ChannelMix::Channel(index) => Some(index.min(channels - 1)),
+        ChannelMix::Loudest => {
+            let mut energy = vec![0.0f32; channels];
+            for frame in data.chunks_exact(channels) {
+                for (acc, &sample) in energy.iter_mut().zip(frame) {
+                    let value = sample.to_sample::<f32>();
+                    *acc += value * value;
                 }
             }
+            energy
+                .iter()
+                .enumerate()
+                .max_by(|a, b| a.1.total_cmp(b.1))
+                .map(|(index, _)| index)
         }
+    };
 
-        if let Some(config) = best_config {
-            return Ok(config.with_sample_rate(cpal::SampleRate(constants::WHISPER_SAMPLE_RATE)));
+    match selected {
+        Some(index) => out.extend(
+            data.chunks_exact(channels)
+                .map(|frame| frame[index].to_sample::<f32>()),
+        ),
+        None => {
+            for frame in data.chunks_exact(channels) {
+                let mono_sample = frame
+                    .iter()
+                    .map(|&sample| sample.to_sample::<f32>())
+                    .sum::<f32>()
+                    / channels as f32;
+                out.push(mono_sample);
+            }
         }
+    }
+}
 
-        // If no config supports 16kHz, fall back to default
-        Ok(device.default_input_config()?)
+/// Scales samples by `gain`, hard-limiting the result to full scale.
+fn apply_gain(samples: &mut [f32], gain: f32) {
+    if gain == 1.0 {
+        return;
+    }
+    for sample in samples.iter_mut() {
+        *sample = (*sample * gain).clamp(-1.0, 1.0);
     }
 }
 
@@ -307,101 +699,746 @@ fn normalize_microphone_error(error_message: String) -> String {
     error_message
 }
 
-fn run_consumer(
-    in_sample_rate: u32,
-    vad: Option<Arc<Mutex<Box<dyn vad::VoiceActivityDetector>>>>,
+/// How long the consumer waits for audio before checking for commands.
+const COMMAND_POLL_INTERVAL: Duration = Duration::from_millis(50);
+
+/// Duration of the frames handed to the preprocessor and VAD.
+const FRAME_DURATION: Duration = Duration::from_millis(30);
+
+/// Length of each input level metering window.
+const METER_WINDOW: Duration = Duration::from_millis(100);
+
+/// Most monitor audio buffered ahead of the microphone before the oldest is
+/// dropped, which bounds drift between the two device clocks.
+const MONITOR_MAX_BUFFER: Duration = Duration::from_millis(500);
+
+/// Samples from one input stream, at the stream's own rate.
+struct InputFeed {
+    sample_rate: u32,
     sample_rx: mpsc::Receiver<Vec<f32>>,
-    cmd_rx: mpsc::Receiver<Cmd>,
-    level_cb: Option<Arc<dyn Fn(Vec<f32>) + Send + Sync + 'static>>,
-) {
-    let mut frame_resampler = FrameResampler::new(
-        in_sample_rate as usize,
-        constants::WHISPER_SAMPLE_RATE as usize,
-        Duration::from_millis(30),
-    );
+}
 
-    let mut processed_samples = Vec::<f32>::new();
-    let mut recording = false;
-
-    // ---------- spectrum visualisation setup ---------------------------- //
-    const BUCKETS: usize = 16;
-    const WINDOW_SIZE: usize = 512;
-    let mut visualizer = AudioVisualiser::new(
-        in_sample_rate,
-        WINDOW_SIZE,
-        BUCKETS,
-        400.0,  // vocal_min_hz
-        4000.0, // vocal_max_hz
-    );
+/// Resamples a monitor stream and adds it onto microphone frames.
+struct MonitorMixer {
+    sample_rx: mpsc::Receiver<Vec<f32>>,
+    resampler: FrameResampler,
+    pending: VecDeque<f32>,
+    max_pending: usize,
+}
+
+impl MonitorMixer {
+    fn new(sample_rate: u32, sample_rx: mpsc::Receiver<Vec<f32>>) -> Self {
+        Self {
+            sample_rx,
+            resampler: FrameResampler::new(
+                sample_rate as usize,
+                constants::WHISPER_SAMPLE_RATE as usize,
+                FRAME_DURATION,
+            ),
+            pending: VecDeque::new(),
+            max_pending: (constants::WHISPER_SAMPLE_RATE as f64 * MONITOR_MAX_BUFFER.as_secs_f64())
+                as usize,
+        }
+    }
+
+    /// Moves everything the monitor stream delivered into the mix buffer.
+    /// Returns `false` once the monitor has stopped delivering for good.
+    fn poll(&mut self) -> bool {
+        let connected = loop {
+            match self.sample_rx.try_recv() {
+                Ok(raw) => self
+                    .resampler
+                    .push(&raw, |frame| self.pending.extend(frame.iter().copied())),
+                Err(mpsc::TryRecvError::Empty) => break true,
+                Err(mpsc::TryRecvError::Disconnected) => break false,
+            }
+        };
+        if self.pending.len() > self.max_pending {
+            let excess = self.pending.len() - self.max_pending;
+            self.pending.drain(..excess);
+        }
+        connected
+    }
+
+    fn mix_into(&mut self, samples: &mut [f32]) {
+        let available = samples.len().min(self.pending.len());
+        for (sample, monitor) in samples.iter_mut().zip(self.pending.drain(..available)) {
+            *sample = (*sample + monitor).clamp(-1.0, 1.0);
+        }
+    }
+}
+
+/// Frames at the Whisper rate, on their way into the recording.
+struct FrameSink {
+    monitor: Option<MonitorMixer>,
+    vad: Option<Arc<Mutex<Box<dyn vad::VoiceActivityDetector>>>>,
+    preprocessor: Option<AudioPreprocessor>,
+    scratch: Vec<f32>,
+    processed_samples: SpillBuffer,
+    journal: Option<RecordingJournal>,
+    limit: Option<RecordingLimit>,
+    captured: Arc<AtomicUsize>,
+    /// Unprocessed frames that were kept as speech.
+    speech_meter: LevelMeter,
+}
+
+impl FrameSink {
+    fn handle(&mut self, samples: &[f32], recording: bool) {
+        let raw = samples;
+        let samples = if self.monitor.is_some() || self.preprocessor.is_some() {
+            self.scratch.clear();
+            self.scratch.extend_from_slice(samples);
+            if let Some(mixer) = &mut self.monitor {
+                mixer.mix_into(&mut self.scratch);
+            }
+            if let Some(pre) = &mut self.preprocessor {
+                pre.process(&mut self.scratch);
+            }
+            &self.scratch[..]
+        } else {
+            samples
+        };
 
-    fn handle_frame(
-        samples: &[f32],
-        recording: bool,
-        vad: &Option<Arc<Mutex<Box<dyn vad::VoiceActivityDetector>>>>,
-        out_buf: &mut Vec<f32>,
-    ) {
         if !recording {
             return;
         }
+        if let Some(limit) = &mut self.limit {
+            if !limit.admit(samples.len()) {
+                return;
+            }
+        }
+        self.captured.fetch_add(samples.len(), Ordering::Relaxed);
 
-        if let Some(vad_arc) = vad {
+        if let Some(vad_arc) = &self.vad {
             let mut det = vad_arc.lock().unwrap();
-            match det.push_frame(samples).unwrap_or(VadFrame::Speech(samples)) {
-                VadFrame::Speech(buf) => out_buf.extend_from_slice(buf),
-                VadFrame::Noise => {}
+            if let VadFrame::Speech(buf) =
+                det.push_frame(samples).unwrap_or(VadFrame::Speech(samples))
+            {
+                self.speech_meter.feed(raw, |_| {});
+                record(&mut self.processed_samples, &mut self.journal, buf);
             }
         } else {
-            out_buf.extend_from_slice(samples);
+            self.speech_meter.feed(raw, |_| {});
+            record(&mut self.processed_samples, &mut self.journal, samples);
         }
     }
+}
 
-    loop {
-        let raw = match sample_rx.recv() {
-            Ok(s) => s,
-            Err(_) => break, // stream closed
-        };
+/// Appends accepted audio to the recording and its journal, if any.
+fn record(recording: &mut SpillBuffer, journal: &mut Option<RecordingJournal>, samples: &[f32]) {
+    recording.extend_from_slice(samples);
+    if let Some(j) = journal {
+        if let Err(e) = j.append(samples) {
+            log::error!("Failed to write {}: {e}", j.path().display());
+            *journal = None;
+        }
+    }
+}
+
+/// Longest warning given before a recording reaches its maximum duration.
+const LIMIT_WARNING: Duration = Duration::from_secs(30);
 
-        // ---------- spectrum processing ---------------------------------- //
-        if let Some(buckets) = visualizer.feed(&raw) {
-            if let Some(cb) = &level_cb {
+fn whisper_samples(duration: Duration) -> usize {
+    (constants::WHISPER_SAMPLE_RATE as f64 * duration.as_secs_f64()).round() as usize
+}
+
+/// Tracks how much of its maximum duration a recording has used.
+struct RecordingLimit {
+    max_samples: usize,
+    warn_at: usize,
+    elapsed: usize,
+    warned: bool,
+    cb: Option<Arc<dyn Fn(RecordingLimitEvent) + Send + Sync + 'static>>,
+}
+
+impl RecordingLimit {
+    fn new(
+        max_duration: Option<Duration>,
+        cb: Option<Arc<dyn Fn(RecordingLimitEvent) + Send + Sync + 'static>>,
+    ) -> Option<Self> {
+        let max_samples = whisper_samples(max_duration?);
+        // Short limits are warned about halfway through
+        let warning = whisper_samples(LIMIT_WARNING).min(max_samples / 2);
+        Some(Self {
+            max_samples,
+            warn_at: max_samples - warning,
+            elapsed: 0,
+            warned: false,
+            cb,
+        })
+    }
+
+    fn reset(&mut self) {
+        self.elapsed = 0;
+        self.warned = false;
+    }
+
+    /// Counts a frame of recorded time, returning false once the limit has
+    /// been used up.
+    fn admit(&mut self, samples: usize) -> bool {
+        if self.elapsed >= self.max_samples {
+            return false;
+        }
+        self.elapsed += samples;
+
+        if !self.warned && self.elapsed >= self.warn_at {
+            self.warned = true;
+            let remaining = self.max_samples.saturating_sub(self.elapsed);
+            self.notify(RecordingLimitEvent::Approaching {
+                remaining: Duration::from_secs_f64(
+                    remaining as f64 / constants::WHISPER_SAMPLE_RATE as f64,
+                ),
+            });
+        }
+        if self.elapsed >= self.max_samples {
+            self.notify(RecordingLimitEvent::Reached);
+        }
+        true
+    }
+
+    fn notify(&self, event: RecordingLimitEvent) {
+        if let Some(cb) = &self.cb {
+            cb(event);
+        }
+    }
+}
+
+/// Recorder settings carried into the worker thread.
+struct PipelineConfig {
+    vad: Option<Arc<Mutex<Box<dyn vad::VoiceActivityDetector>>>>,
+    preprocess: PreprocessConfig,
+    gain: SharedGain,
+    level_cb: Option<Arc<dyn Fn(Vec<f32>) + Send + Sync + 'static>>,
+    meter_cb: Option<Arc<dyn Fn(InputLevel) + Send + Sync + 'static>>,
+    limit: Option<RecordingLimit>,
+    spill_threshold: Option<usize>,
+    spill_dir: PathBuf,
+    captured: Arc<AtomicUsize>,
+    recorded_levels: Arc<Mutex<Option<RecordingLevels>>>,
+}
+
+/// Everything between the raw source samples and the recorded buffer.
+struct Pipeline {
+    gain: SharedGain,
+    /// Source samples after gain, when the gain isn't unity.
+    gained: Vec<f32>,
+    frame_resampler: FrameResampler,
+    frames: FrameSink,
+    recording: bool,
+    paused: bool,
+    meter: LevelMeter,
+    /// Raw input over the whole recording, for the level warnings.
+    recording_meter: LevelMeter,
+    recorded_levels: Arc<Mutex<Option<RecordingLevels>>>,
+    visualizer: AudioVisualiser,
+    level_cb: Option<Arc<dyn Fn(Vec<f32>) + Send + Sync + 'static>>,
+    meter_cb: Option<Arc<dyn Fn(InputLevel) + Send + Sync + 'static>>,
+}
+
+impl Pipeline {
+    fn new(in_sample_rate: u32, monitor: Option<MonitorMixer>, config: PipelineConfig) -> Self {
+        // ---------- spectrum visualisation setup ---------------------------- //
+        const BUCKETS: usize = 16;
+        const WINDOW_SIZE: usize = 512;
+
+        let PipelineConfig {
+            vad,
+            preprocess,
+            gain,
+            level_cb,
+            meter_cb,
+            limit,
+            spill_threshold,
+            spill_dir,
+            captured,
+            recorded_levels,
+        } = config;
+
+        Self {
+            gain,
+            gained: Vec::new(),
+            frame_resampler: FrameResampler::new(
+                in_sample_rate as usize,
+                constants::WHISPER_SAMPLE_RATE as usize,
+                FRAME_DURATION,
+            ),
+            frames: FrameSink {
+                monitor,
+                vad,
+                // Preprocessing runs on every frame, even while idle, so the filter and
+                // noise estimates are already settled when recording starts
+                preprocessor: preprocess
+                    .is_enabled()
+                    .then(|| AudioPreprocessor::new(constants::WHISPER_SAMPLE_RATE, preprocess)),
+                scratch: Vec::new(),
+                processed_samples: SpillBuffer::new(spill_threshold, spill_dir),
+                journal: None,
+                limit,
+                captured,
+                speech_meter: LevelMeter::new(usize::MAX),
+            },
+            recording: false,
+            paused: false,
+            meter: LevelMeter::new(
+                (in_sample_rate as f64 * METER_WINDOW.as_secs_f64()).round() as usize
+            ),
+            recording_meter: LevelMeter::new(usize::MAX),
+            recorded_levels,
+            visualizer: AudioVisualiser::new(
+                in_sample_rate,
+                WINDOW_SIZE,
+                BUCKETS,
+                400.0,  // vocal_min_hz
+                4000.0, // vocal_max_hz
+            ),
+            level_cb,
+            meter_cb,
+        }
+    }
+
+    fn poll_monitor(&mut self) {
+        if let Some(mixer) = &mut self.frames.monitor {
+            if !mixer.poll() {
+                log::warn!("Monitor source stopped, recording the microphone alone");
+                self.frames.monitor = None;
+            }
+        }
+    }
+
+    fn push(&mut self, raw: &[f32]) {
+        let raw = with_gain(raw, self.gain.get(), &mut self.gained);
+
+        // ---------- spectrum processing -------------------------- //
+        if let Some(buckets) = self.visualizer.feed(raw) {
+            if let Some(cb) = &self.level_cb {
                 cb(buckets);
             }
         }
 
-        // ---------- existing pipeline ------------------------------------ //
-        frame_resampler.push(&raw, &mut |frame: &[f32]| {
-            handle_frame(frame, recording, &vad, &mut processed_samples)
+        // ---------- level metering ------------------------------- //
+        if let Some(cb) = &self.meter_cb {
+            self.meter.feed(raw, |level| cb(level));
+        }
+        if self.recording {
+            self.recording_meter.feed(raw, |_| {});
+        }
+
+        // ---------- existing pipeline ---------------------------- //
+        let recording = self.recording;
+        let frames = &mut self.frames;
+        self.frame_resampler
+            .push(raw, |frame| frames.handle(frame, recording));
+    }
+
+    fn start(&mut self, journal: Option<PathBuf>) {
+        // Recording without a journal is better than not recording at all
+        self.frames.journal = journal.and_then(|path| {
+            RecordingJournal::open(&path)
+                .inspect_err(|e| log::error!("Failed to open {}: {e}", path.display()))
+                .ok()
         });
+        self.frames.processed_samples.clear();
+        self.frames.captured.store(0, Ordering::Relaxed);
+        self.recording_meter.reset();
+        self.frames.speech_meter.reset();
+        if let Some(limit) = &mut self.frames.limit {
+            limit.reset();
+        }
+        self.recording = true;
+        self.paused = false;
+        self.visualizer.reset(); // Reset visualization buffer
+        if let Some(v) = &self.frames.vad {
+            v.lock().unwrap().reset();
+        }
+    }
 
-        // non-blocking check for a command
-        while let Ok(cmd) = cmd_rx.try_recv() {
-            match cmd {
-                Cmd::Start => {
-                    processed_samples.clear();
-                    recording = true;
-                    visualizer.reset(); // Reset visualization buffer
-                    if let Some(v) = &vad {
-                        v.lock().unwrap().reset();
-                    }
-                }
-                Cmd::Stop(reply_tx) => {
-                    recording = false;
-
-                    // Drain any audio chunks that were captured but not yet consumed
-                    while let Ok(remaining) = sample_rx.try_recv() {
-                        frame_resampler.push(&remaining, &mut |frame: &[f32]| {
-                            handle_frame(frame, true, &vad, &mut processed_samples)
-                        });
-                    }
+    fn pause(&mut self, sample_rx: &mpsc::Receiver<Vec<f32>>) {
+        if !self.recording {
+            return;
+        }
+        // Audio that arrived before the pause still belongs to the recording
+        while let Ok(raw) = sample_rx.try_recv() {
+            self.push(&raw);
+        }
+        self.recording = false;
+        self.paused = true;
+    }
 
-                    frame_resampler.finish(&mut |frame: &[f32]| {
-                        handle_frame(frame, true, &vad, &mut processed_samples)
-                    });
+    fn resume(&mut self) {
+        if !self.paused {
+            return;
+        }
+        self.paused = false;
+        self.recording = true;
+        // Speech before the pause says nothing about speech after it
+        if let Some(v) = &self.frames.vad {
+            v.lock().unwrap().reset();
+        }
+    }
+
+    /// Stops recording and returns everything recorded, including audio that
+    /// was captured but not yet consumed.
+    fn finish(&mut self, sample_rx: &mpsc::Receiver<Vec<f32>>) -> Vec<f32> {
+        // A paused recording ended with the pause, so the audio still queued
+        // is only drained
+        let recording = self.recording;
+        self.recording = false;
+        self.paused = false;
+
+        // Drain any audio chunks that were captured but not yet consumed
+        self.poll_monitor();
+        let frames = &mut self.frames;
+        while let Ok(remaining) = sample_rx.try_recv() {
+            let remaining = with_gain(&remaining, self.gain.get(), &mut self.gained);
+            if recording {
+                self.recording_meter.feed(remaining, |_| {});
+            }
+            self.frame_resampler
+                .push(remaining, |frame| frames.handle(frame, recording));
+        }
+        self.frame_resampler
+            .finish(|frame| frames.handle(frame, recording));
+        // Measured before the padding below
+        *self.recorded_levels.lock().unwrap() =
+            self.recording_meter.level().map(|input| RecordingLevels {
+                input,
+                speech: frames.speech_meter.level(),
+            });
+
+        // Push silence through so the preprocessor's delayed tail
+        // ends up in the recording
+        let latency = frames.preprocessor.as_ref().map_or(0, |pre| pre.latency());
+        if recording && latency > 0 {
+            // The padding wasn't captured, so it doesn't count
+            let captured = frames.captured.load(Ordering::Relaxed);
+            let frame_samples = (constants::WHISPER_SAMPLE_RATE as f64
+                * FRAME_DURATION.as_secs_f64())
+            .round() as usize;
+            let silence = vec![0.0; frame_samples];
+            for _ in 0..latency.div_ceil(frame_samples) {
+                frames.handle(&silence, true);
+            }
+            frames.captured.store(captured, Ordering::Relaxed);
+        }
+
+        if let Some(mut journal) = frames.journal.take() {
+            if let Err(e) = journal.flush() {
+                log::error!("Failed to flush {}: {e}", journal.path().display());
+            }
+        }
+
+        frames.processed_samples.take()
+    }
+}
+
+/// Returns `raw` scaled by `gain`, using `scratch` unless the gain is unity.
+fn with_gain<'a>(raw: &'a [f32], gain: f32, scratch: &'a mut Vec<f32>) -> &'a [f32] {
+    if gain == 1.0 {
+        return raw;
+    }
+    scratch.clear();
+    scratch.extend_from_slice(raw);
+    apply_gain(scratch, gain);
+    scratch
+}
+
+fn run_consumer(
+    input: InputFeed,
+    monitor: Option<MonitorMixer>,
+    cmd_rx: mpsc::Receiver<Cmd>,
+    config: PipelineConfig,
+) {
+    let InputFeed {
+        sample_rate,
+        sample_rx,
+    } = input;
 
-                    let _ = reply_tx.send(std::mem::take(&mut processed_samples));
+    let mut pipeline = Pipeline::new(sample_rate, monitor, config);
+    let mut source_ended = false;
+    let mut stop_at_end: Option<mpsc::Sender<Vec<f32>>> = None;
+
+    loop {
+        // Commands go first so a recording started before the source's first
+        // sample doesn't miss it
+        loop {
+            match cmd_rx.try_recv() {
+                Ok(Cmd::Start(journal)) => pipeline.start(journal),
+                Ok(Cmd::Pause) => pipeline.pause(&sample_rx),
+                Ok(Cmd::Resume) => pipeline.resume(),
+                Ok(Cmd::Stop(reply_tx)) => {
+                    let _ = reply_tx.send(pipeline.finish(&sample_rx));
                 }
-                Cmd::Shutdown => return,
+                Ok(Cmd::StopAtEnd(reply_tx)) => stop_at_end = Some(reply_tx),
+                Ok(Cmd::Shutdown) | Err(mpsc::TryRecvError::Disconnected) => return,
+                Err(mpsc::TryRecvError::Empty) => break,
             }
         }
+
+        if source_ended {
+            // Finite sources stay open until the recorder is closed
+            if let Some(reply_tx) = stop_at_end.take() {
+                let _ = reply_tx.send(pipeline.finish(&sample_rx));
+            }
+            std::thread::sleep(COMMAND_POLL_INTERVAL);
+            continue;
+        }
+
+        // Wake up periodically even when no audio arrives, so commands are
+        // still answered after the device has stopped delivering samples.
+        pipeline.poll_monitor();
+
+        match sample_rx.recv_timeout(COMMAND_POLL_INTERVAL) {
+            Ok(raw) => pipeline.push(&raw),
+            Err(mpsc::RecvTimeoutError::Timeout) => {}
+            Err(mpsc::RecvTimeoutError::Disconnected) => source_ended = true,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::audio_toolkit::audio::MemorySource;
+
+    // Two channels: a live mic on the left, mostly silence on the right
+    const STEREO: [f32; 6] = [0.5, 0.0, -0.5, 0.25, 0.25, 0.0];
+
+    #[test]
+    fn average_mixes_all_channels() {
+        let mut out = Vec::new();
+        downmix_into(&STEREO, 2, ChannelMix::Average, &mut out);
+        assert_eq!(out, vec![0.25, -0.125, 0.125]);
+    }
+
+    #[test]
+    fn channel_picks_a_single_input() {
+        let mut out = Vec::new();
+        downmix_into(&STEREO, 2, ChannelMix::Channel(0), &mut out);
+        assert_eq!(out, vec![0.5, -0.5, 0.25]);
+    }
+
+    #[test]
+    fn loudest_picks_the_channel_with_most_energy() {
+        let mut out = Vec::new();
+        downmix_into(&STEREO, 2, ChannelMix::Loudest, &mut out);
+        assert_eq!(out, vec![0.5, -0.5, 0.25]);
+    }
+
+    #[test]
+    fn records_a_whole_memory_source() {
+        let samples: Vec<f32> = (0..16000).map(|i| (i as f32 * 0.01).sin() * 0.5).collect();
+        let mut recorder = AudioRecorder::new().unwrap();
+
+        let recorded = recorder
+            .record_source(Box::new(MemorySource::new(samples.clone(), 16000)))
+            .unwrap();
+
+        // The final partial frame is padded up to a whole frame
+        assert_eq!(recorded.len(), 16320);
+        assert_eq!(&recorded[..samples.len()], &samples[..]);
+    }
+
+    #[test]
+    fn meters_memory_source_input() {
+        let levels = Arc::new(Mutex::new(Vec::new()));
+        let sink = levels.clone();
+        let mut recorder = AudioRecorder::new()
+            .unwrap()
+            .with_meter_callback(move |level| sink.lock().unwrap().push(level));
+
+        recorder
+            .record_source(Box::new(MemorySource::new(vec![0.5; 48000], 48000)))
+            .unwrap();
+
+        let levels = levels.lock().unwrap();
+        assert_eq!(levels.len(), 10);
+        assert!(levels
+            .iter()
+            .all(|level| (level.peak_dbfs + 6.02).abs() < 0.01));
+    }
+
+    #[test]
+    fn measures_the_recording_before_gain_control() {
+        let mut recorder = AudioRecorder::new().unwrap();
+        recorder.set_preprocess_config(PreprocessConfig {
+            auto_gain: true,
+            ..Default::default()
+        });
+
+        recorder
+            .record_source(Box::new(MemorySource::new(vec![0.01; 16000], 16000)))
+            .unwrap();
+
+        let levels = recorder.recorded_levels().unwrap();
+        assert!((levels.input.rms_dbfs + 40.0).abs() < 0.01);
+        assert_eq!(levels.input.sample_count, 16000);
+    }
+
+    /// Keeps every other frame as speech.
+    struct AlternatingVad(bool);
+
+    impl vad::VoiceActivityDetector for AlternatingVad {
+        fn push_frame<'a>(&'a mut self, frame: &'a [f32]) -> anyhow::Result<VadFrame<'a>> {
+            self.0 = !self.0;
+            Ok(if self.0 {
+                VadFrame::Speech(frame)
+            } else {
+                VadFrame::Noise
+            })
+        }
+    }
+
+    #[test]
+    fn measures_speech_apart_from_pauses() {
+        let mut recorder = AudioRecorder::new()
+            .unwrap()
+            .with_vad(Box::new(AlternatingVad(false)));
+        // Loud speech frames alternating with silent pauses, 30 ms each
+        let samples: Vec<f32> = (0..20)
+            .flat_map(|i| vec![if i % 2 == 0 { 0.5 } else { 0.0 }; 480])
+            .collect();
+
+        recorder
+            .record_source(Box::new(MemorySource::new(samples, 16000)))
+            .unwrap();
+
+        let levels = recorder.recorded_levels().unwrap();
+        let speech = levels.speech.unwrap();
+        assert!((speech.rms_dbfs + 6.02).abs() < 0.01);
+        assert!(levels.input.rms_dbfs < speech.rms_dbfs - 2.0);
+    }
+
+    #[test]
+    fn journals_what_is_recorded() {
+        let dir = tempfile::tempdir().unwrap();
+        let journal = dir.path().join("recording.pcm");
+        let samples = vec![0.25; 4800];
+
+        let mut recorder = AudioRecorder::new().unwrap();
+        let recorded = recorder
+            .record_source_journaled(
+                Box::new(MemorySource::new(samples.clone(), 16000)),
+                journal.clone(),
+            )
+            .unwrap();
+
+        let journaled = crate::audio_toolkit::audio::read_journal(&journal).unwrap();
+        assert_eq!(recorded.len(), samples.len());
+        assert_eq!(journaled.len(), recorded.len());
+        assert!(journaled
+            .iter()
+            .zip(&recorded)
+            .all(|(j, r)| (j - r).abs() < 1e-3));
+    }
+
+    #[test]
+    fn leaves_paused_audio_out_of_the_recording() {
+        let (sample_tx, sample_rx) = mpsc::channel();
+        let captured = Arc::new(AtomicUsize::new(0));
+        let mut pipeline = Pipeline::new(
+            16000,
+            None,
+            PipelineConfig {
+                vad: None,
+                preprocess: PreprocessConfig::default(),
+                gain: SharedGain::new(1.0),
+                level_cb: None,
+                meter_cb: None,
+                limit: None,
+                spill_threshold: None,
+                spill_dir: std::env::temp_dir(),
+                captured: captured.clone(),
+                recorded_levels: Arc::new(Mutex::new(None)),
+            },
+        );
+
+        pipeline.start(None);
+        pipeline.push(&[0.25; 4800]);
+        // Queued audio from before the pause is kept
+        sample_tx.send(vec![0.25; 4800]).unwrap();
+        pipeline.pause(&sample_rx);
+        pipeline.push(&[0.75; 4800]);
+        pipeline.resume();
+        pipeline.push(&[0.5; 4800]);
+        sample_tx.send(vec![0.75; 4800]).unwrap();
+        pipeline.pause(&sample_rx);
+        sample_tx.send(vec![0.75; 4800]).unwrap();
+        let recorded = pipeline.finish(&sample_rx);
+
+        assert_eq!(recorded.len(), 4800 * 4);
+        assert!(recorded[..9600].iter().all(|&s| s == 0.25));
+        assert!(recorded[9600..14400].iter().all(|&s| s == 0.5));
+        assert!(recorded[14400..].iter().all(|&s| s == 0.75));
+        assert_eq!(captured.load(Ordering::Relaxed), recorded.len());
+    }
+
+    #[test]
+    fn applies_gain_changes_without_reopening() {
+        let (_sample_tx, sample_rx) = mpsc::channel();
+        let gain = SharedGain::new(1.0);
+        let mut pipeline = Pipeline::new(
+            16000,
+            None,
+            PipelineConfig {
+                vad: None,
+                preprocess: PreprocessConfig::default(),
+                gain: gain.clone(),
+                level_cb: None,
+                meter_cb: None,
+                limit: None,
+                spill_threshold: None,
+                spill_dir: std::env::temp_dir(),
+                captured: Arc::new(AtomicUsize::new(0)),
+                recorded_levels: Arc::new(Mutex::new(None)),
+            },
+        );
+
+        pipeline.start(None);
+        pipeline.push(&[0.25; 4800]);
+        gain.set(2.0);
+        pipeline.push(&[0.25; 4800]);
+        let recorded = pipeline.finish(&sample_rx);
+
+        assert!(recorded[..4800].iter().all(|&s| s == 0.25));
+        assert!(recorded[4800..].iter().all(|&s| s == 0.5));
+    }
+
+    #[test]
+    fn stops_recording_at_the_limit() {
+        let events = Arc::new(Mutex::new(Vec::new()));
+        let sink = events.clone();
+        let mut recorder = AudioRecorder::new()
+            .unwrap()
+            .with_limit_callback(move |event| sink.lock().unwrap().push(event));
+        recorder.set_max_duration(Some(Duration::from_millis(500)));
+        recorder.set_spill_after(Some(Duration::from_millis(100)));
+        let spill_dir = tempfile::tempdir().unwrap();
+        recorder.set_spill_dir(spill_dir.path().to_path_buf());
+
+        let recorded = recorder
+            .record_source(Box::new(MemorySource::new(vec![0.25; 32000], 16000)))
+            .unwrap();
+
+        // Frames are admitted until half a second has been recorded
+        assert_eq!(recorded.len(), 8160);
+        assert_eq!(recorder.captured_duration(), Duration::from_millis(510));
+        assert!(recorded.iter().all(|&s| s == 0.25));
+
+        let events = events.lock().unwrap();
+        assert_eq!(events.len(), 2);
+        assert!(matches!(
+            events[0],
+            RecordingLimitEvent::Approaching { remaining } if remaining <= Duration::from_millis(250)
+        ));
+        assert_eq!(events[1], RecordingLimitEvent::Reached);
+    }
+
+    #[test]
+    fn resamples_sources_to_whisper_rate() {
+        let mut recorder = AudioRecorder::new().unwrap();
+        let recorded = recorder
+            .record_source(Box::new(MemorySource::new(vec![0.0; 48000], 48000)))
+            .unwrap();
+        assert!((recorded.len() as i64 - 16000).abs() <= 480);
     }
 }
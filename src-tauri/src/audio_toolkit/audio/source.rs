@@ -0,0 +1,285 @@
+use std::{
+    any::Any,
+    path::{Path, PathBuf},
+    sync::mpsc,
+    thread,
+    time::{Duration, Instant},
+};
+
+use super::utils::int_sample_scale;
+
+/// Default number of frames delivered per chunk by the non-device sources.
+const DEFAULT_CHUNK_SIZE: usize = 1024;
+
+/// Something that delivers mono `f32` samples to the recorder pipeline.
+///
+/// `start` runs on the recorder's worker thread. Samples are sent on
+/// `sample_tx` until the source ends, at which point the sender is dropped.
+pub trait AudioSource: Send {
+    fn start(self: Box<Self>, sample_tx: mpsc::Sender<Vec<f32>>) -> Result<RunningSource, String>;
+}
+
+/// A started source. The handle is kept alive on the worker thread until
+/// the recorder closes.
+pub struct RunningSource {
+    pub sample_rate: u32,
+    _handle: Box<dyn Any>,
+}
+
+impl RunningSource {
+    pub fn new(sample_rate: u32, handle: impl Any) -> Self {
+        Self {
+            sample_rate,
+            _handle: Box::new(handle),
+        }
+    }
+}
+
+/// How fast the file and in-memory sources deliver their samples.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Pace {
+    /// As fast as the pipeline consumes them.
+    Unthrottled,
+    /// At the speed a device would record them.
+    RealTime,
+    /// Real time multiplied by a factor, e.g. `4.0` for four times faster.
+    Speed(f32),
+}
+
+impl Pace {
+    /// Wall-clock time at which `frames` samples should have been delivered.
+    fn due(&self, frames: usize, sample_rate: u32) -> Option<Duration> {
+        let real_time = Duration::from_secs_f64(frames as f64 / sample_rate as f64);
+        match *self {
+            Pace::Unthrottled => None,
+            Pace::RealTime => Some(real_time),
+            Pace::Speed(factor) if factor > 0.0 => Some(real_time.div_f32(factor)),
+            Pace::Speed(_) => None,
+        }
+    }
+}
+
+/// Sends chunks from `next_chunk` on a feeder thread, respecting `pace`.
+fn spawn_feeder(
+    mut next_chunk: impl FnMut() -> Option<Vec<f32>> + Send + 'static,
+    sample_rate: u32,
+    pace: Pace,
+    sample_tx: mpsc::Sender<Vec<f32>>,
+) -> thread::JoinHandle<()> {
+    thread::spawn(move || {
+        let started = Instant::now();
+        let mut delivered = 0usize;
+
+        while let Some(chunk) = next_chunk() {
+            delivered += chunk.len();
+            if sample_tx.send(chunk).is_err() {
+                break; // recorder closed
+            }
+            if let Some(due) = pace.due(delivered, sample_rate) {
+                if let Some(wait) = due.checked_sub(started.elapsed()) {
+                    thread::sleep(wait);
+                }
+            }
+        }
+    })
+}
+
+/// Feeds a buffer of mono samples, e.g. for tests or synthetic signals.
+pub struct MemorySource {
+    samples: Vec<f32>,
+    sample_rate: u32,
+    chunk_size: usize,
+    pace: Pace,
+}
+
+impl MemorySource {
+    pub fn new(samples: Vec<f32>, sample_rate: u32) -> Self {
+        Self {
+            samples,
+            sample_rate,
+            chunk_size: DEFAULT_CHUNK_SIZE,
+            pace: Pace::Unthrottled,
+        }
+    }
+
+    pub fn with_chunk_size(mut self, chunk_size: usize) -> Self {
+        self.chunk_size = chunk_size.max(1);
+        self
+    }
+
+    pub fn with_pace(mut self, pace: Pace) -> Self {
+        self.pace = pace;
+        self
+    }
+}
+
+impl AudioSource for MemorySource {
+    fn start(self: Box<Self>, sample_tx: mpsc::Sender<Vec<f32>>) -> Result<RunningSource, String> {
+        let MemorySource {
+            samples,
+            sample_rate,
+            chunk_size,
+            pace,
+        } = *self;
+
+        let mut offset = 0;
+        let feeder = spawn_feeder(
+            move || {
+                if offset >= samples.len() {
+                    return None;
+                }
+                let end = (offset + chunk_size).min(samples.len());
+                let chunk = samples[offset..end].to_vec();
+                offset = end;
+                Some(chunk)
+            },
+            sample_rate,
+            pace,
+            sample_tx,
+        );
+
+        Ok(RunningSource::new(sample_rate, feeder))
+    }
+}
+
+/// Plays a WAV file into the pipeline, downmixing it to mono.
+pub struct WavFileSource {
+    path: PathBuf,
+    chunk_size: usize,
+    pace: Pace,
+}
+
+impl WavFileSource {
+    pub fn new<P: AsRef<Path>>(path: P, pace: Pace) -> Self {
+        Self {
+            path: path.as_ref().to_path_buf(),
+            chunk_size: DEFAULT_CHUNK_SIZE,
+            pace,
+        }
+    }
+
+    pub fn with_chunk_size(mut self, chunk_size: usize) -> Self {
+        self.chunk_size = chunk_size.max(1);
+        self
+    }
+}
+
+impl AudioSource for WavFileSource {
+    fn start(self: Box<Self>, sample_tx: mpsc::Sender<Vec<f32>>) -> Result<RunningSource, String> {
+        let reader = hound::WavReader::open(&self.path)
+            .map_err(|e| format!("Failed to open {}: {e}", self.path.display()))?;
+        let spec = reader.spec();
+        let channels = spec.channels.max(1) as usize;
+
+        log::info!(
+            "Playing {}\nSample rate: {}\nChannels: {}\nFormat: {:?} {} bit",
+            self.path.display(),
+            spec.sample_rate,
+            channels,
+            spec.sample_format,
+            spec.bits_per_sample
+        );
+
+        let mut samples: Box<dyn Iterator<Item = f32> + Send> = match spec.sample_format {
+            hound::SampleFormat::Float => {
+                Box::new(reader.into_samples::<f32>().map_while(Result::ok))
+            }
+            hound::SampleFormat::Int => {
+                let scale = int_sample_scale(spec.bits_per_sample.into()).ok_or_else(|| {
+                    format!(
+                        "Unsupported bit depth in {}: {}",
+                        self.path.display(),
+                        spec.bits_per_sample
+                    )
+                })?;
+                Box::new(
+                    reader
+                        .into_samples::<i32>()
+                        .map_while(Result::ok)
+                        .map(move |s| s as f32 * scale),
+                )
+            }
+        };
+
+        let chunk_size = self.chunk_size;
+        let feeder = spawn_feeder(
+            move || {
+                let mut chunk = Vec::with_capacity(chunk_size);
+                let mut frame = Vec::with_capacity(channels);
+                while chunk.len() < chunk_size {
+                    frame.clear();
+                    frame.extend(samples.by_ref().take(channels));
+                    if frame.len() < channels {
+                        break;
+                    }
+                    chunk.push(frame.iter().sum::<f32>() / channels as f32);
+                }
+                (!chunk.is_empty()).then_some(chunk)
+            },
+            spec.sample_rate,
+            self.pace,
+            sample_tx,
+        );
+
+        Ok(RunningSource::new(spec.sample_rate, feeder))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn collect(source: impl AudioSource) -> (u32, Vec<Vec<f32>>) {
+        let (tx, rx) = mpsc::channel();
+        let running = Box::new(source).start(tx).unwrap();
+        (running.sample_rate, rx.iter().collect())
+    }
+
+    #[test]
+    fn memory_source_delivers_all_samples_in_chunks() {
+        let samples: Vec<f32> = (0..10).map(|i| i as f32).collect();
+        let (rate, chunks) = collect(MemorySource::new(samples.clone(), 8000).with_chunk_size(4));
+
+        assert_eq!(rate, 8000);
+        assert_eq!(chunks.iter().map(Vec::len).collect::<Vec<_>>(), [4, 4, 2]);
+        assert_eq!(chunks.concat(), samples);
+    }
+
+    #[test]
+    fn real_time_pace_takes_as_long_as_the_audio() {
+        let started = Instant::now();
+        let (_, chunks) = collect(
+            MemorySource::new(vec![0.0; 1600], 16000)
+                .with_chunk_size(160)
+                .with_pace(Pace::Speed(2.0)),
+        );
+        assert_eq!(chunks.len(), 10);
+        // 100 ms of audio at double speed
+        assert!(started.elapsed() >= Duration::from_millis(45));
+    }
+
+    #[test]
+    fn wav_source_downmixes_to_mono() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("stereo.wav");
+        let spec = hound::WavSpec {
+            channels: 2,
+            sample_rate: 16000,
+            bits_per_sample: 16,
+            sample_format: hound::SampleFormat::Int,
+        };
+        let mut writer = hound::WavWriter::create(&path, spec).unwrap();
+        for _ in 0..3 {
+            writer.write_sample(i16::MAX / 2).unwrap();
+            writer.write_sample(0i16).unwrap();
+        }
+        writer.finalize().unwrap();
+
+        let (rate, chunks) = collect(WavFileSource::new(&path, Pace::Unthrottled));
+        let mono = chunks.concat();
+
+        assert_eq!(rate, 16000);
+        assert_eq!(mono.len(), 3);
+        assert!(mono.iter().all(|s| (s - 0.25).abs() < 1e-3));
+    }
+}
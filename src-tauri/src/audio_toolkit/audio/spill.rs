@@ -0,0 +1,183 @@
+use std::{
+    fs::{File, OpenOptions},
+    io::{self, BufReader, BufWriter, Read, Write},
+    path::{Path, PathBuf},
+    sync::atomic::{AtomicUsize, Ordering},
+};
+
+static NEXT_SPILL_ID: AtomicUsize = AtomicUsize::new(0);
+
+/// A growing sample buffer that keeps at most `threshold` samples in memory
+/// and moves everything older to a file in `dir`, so a recording in progress
+/// holds no more than `threshold` samples in RAM. [`take`](Self::take) still
+/// returns the whole recording in one buffer, since transcription needs it.
+pub struct SpillBuffer {
+    threshold: Option<usize>,
+    dir: PathBuf,
+    memory: Vec<f32>,
+    spill: Option<SpillFile>,
+}
+
+/// Raw f32 samples written out by a [`SpillBuffer`], removed when dropped.
+struct SpillFile {
+    path: PathBuf,
+    writer: BufWriter<File>,
+    len: usize,
+}
+
+impl SpillFile {
+    fn create(dir: &Path) -> io::Result<Self> {
+        std::fs::create_dir_all(dir)?;
+        let path = dir.join(format!(
+            "handy-spill-{}-{}.f32",
+            std::process::id(),
+            NEXT_SPILL_ID.fetch_add(1, Ordering::Relaxed)
+        ));
+        let file = OpenOptions::new()
+            .create(true)
+            .truncate(true)
+            .read(true)
+            .write(true)
+            .open(&path)?;
+        Ok(Self {
+            path,
+            writer: BufWriter::new(file),
+            len: 0,
+        })
+    }
+
+    fn write(&mut self, samples: &[f32]) -> io::Result<()> {
+        for &sample in samples {
+            self.writer.write_all(&sample.to_le_bytes())?;
+        }
+        self.len += samples.len();
+        Ok(())
+    }
+
+    /// Appends the spilled samples to `samples`, decoding as it reads so the
+    /// file's bytes are never held in memory next to the samples.
+    fn read_into(&mut self, samples: &mut Vec<f32>) -> io::Result<()> {
+        self.writer.flush()?;
+        let mut reader = BufReader::new(File::open(&self.path)?);
+        let mut bytes = [0u8; 4];
+        for _ in 0..self.len {
+            reader.read_exact(&mut bytes)?;
+            samples.push(f32::from_le_bytes(bytes));
+        }
+        Ok(())
+    }
+}
+
+impl Drop for SpillFile {
+    fn drop(&mut self) {
+        let _ = std::fs::remove_file(&self.path);
+    }
+}
+
+impl SpillBuffer {
+    /// Creates a buffer that spills to `dir` once it holds `threshold`
+    /// samples, or never when `threshold` is `None`.
+    pub fn new(threshold: Option<usize>, dir: PathBuf) -> Self {
+        Self {
+            threshold,
+            dir,
+            memory: Vec::new(),
+            spill: None,
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.memory.len() + self.spill.as_ref().map_or(0, |spill| spill.len)
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    pub fn extend_from_slice(&mut self, samples: &[f32]) {
+        self.memory.extend_from_slice(samples);
+
+        let Some(threshold) = self.threshold else {
+            return;
+        };
+        if self.memory.len() < threshold {
+            return;
+        }
+
+        let written = match &mut self.spill {
+            Some(spill) => spill.write(&self.memory),
+            None => SpillFile::create(&self.dir).and_then(|mut spill| {
+                spill.write(&self.memory)?;
+                self.spill = Some(spill);
+                Ok(())
+            }),
+        };
+        match written {
+            Ok(()) => self.memory.clear(),
+            Err(e) => {
+                // Keep going in memory rather than losing audio
+                log::error!("Failed to spill recording to disk: {e}");
+                self.threshold = None;
+            }
+        }
+    }
+
+    /// Discards everything, including any spilled samples.
+    pub fn clear(&mut self) {
+        self.memory.clear();
+        self.spill = None;
+    }
+
+    /// Returns every sample in order and empties the buffer.
+    pub fn take(&mut self) -> Vec<f32> {
+        let memory = std::mem::take(&mut self.memory);
+        let Some(mut spill) = self.spill.take() else {
+            return memory;
+        };
+
+        let mut samples = Vec::with_capacity(spill.len + memory.len());
+        match spill.read_into(&mut samples) {
+            Ok(()) => {
+                samples.extend(memory);
+                samples
+            }
+            Err(e) => {
+                log::error!("Failed to read back {}: {e}", spill.path.display());
+                memory
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn spills_past_the_threshold_and_reassembles_in_order() {
+        let samples: Vec<f32> = (0..1000).map(|i| i as f32 / 1000.0).collect();
+        let dir = tempfile::tempdir().unwrap();
+        let mut buffer = SpillBuffer::new(Some(128), dir.path().join("spill"));
+        for chunk in samples.chunks(30) {
+            buffer.extend_from_slice(chunk);
+        }
+
+        assert!(buffer.memory.len() < 128);
+        let path = buffer.spill.as_ref().unwrap().path.clone();
+        assert!(path.starts_with(dir.path()));
+        assert!(path.exists());
+        assert_eq!(buffer.len(), samples.len());
+
+        assert_eq!(buffer.take(), samples);
+        assert!(buffer.is_empty());
+        assert!(!path.exists());
+    }
+
+    #[test]
+    fn stays_in_memory_without_a_threshold() {
+        let mut buffer = SpillBuffer::new(None, std::env::temp_dir());
+        buffer.extend_from_slice(&[0.5; 10_000]);
+        assert!(buffer.spill.is_none());
+        assert_eq!(buffer.take().len(), 10_000);
+    }
+}
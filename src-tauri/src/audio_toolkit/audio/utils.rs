@@ -1,26 +1,217 @@
-use anyhow::Result;
+use anyhow::{anyhow, bail, Result};
 use hound::{WavSpec, WavWriter};
 use log::debug;
-use std::path::Path;
+use std::{
+    fs,
+    io::{Cursor, Read, Seek},
+    path::Path,
+};
+
+use super::{decode_ogg_opus, encode_flac, encode_ogg_opus};
+use crate::audio_toolkit::constants;
+
+/// Container and codec used to store a recording.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum AudioFileFormat {
+    /// 16-bit PCM WAV.
+    Wav,
+    /// Lossless, and smaller than WAV.
+    Flac,
+    /// Lossy Ogg Opus at the given bitrate in bits per second.
+    Opus { bitrate: u32 },
+}
+
+impl AudioFileFormat {
+    pub fn extension(&self) -> &'static str {
+        match self {
+            AudioFileFormat::Wav => "wav",
+            AudioFileFormat::Flac => "flac",
+            AudioFileFormat::Opus { .. } => "opus",
+        }
+    }
+}
 
 /// Save audio samples as a WAV file
 pub async fn save_wav_file<P: AsRef<Path>>(file_path: P, samples: &[f32]) -> Result<()> {
-    let spec = WavSpec {
-        channels: 1,
-        sample_rate: 16000,
-        bits_per_sample: 16,
-        sample_format: hound::SampleFormat::Int,
-    };
+    write_audio_file(file_path, samples, AudioFileFormat::Wav)
+}
+
+/// Save 16 kHz mono audio samples in the given format
+pub async fn save_audio_file<P: AsRef<Path>>(
+    file_path: P,
+    samples: &[f32],
+    format: AudioFileFormat,
+) -> Result<()> {
+    write_audio_file(file_path, samples, format)
+}
+
+/// Blocking version of [`save_audio_file`]
+pub fn write_audio_file<P: AsRef<Path>>(
+    file_path: P,
+    samples: &[f32],
+    format: AudioFileFormat,
+) -> Result<()> {
+    fs::write(file_path.as_ref(), encode_audio(samples, format)?)?;
+    debug!("Saved {:?} file: {:?}", format, file_path.as_ref());
+    Ok(())
+}
+
+/// Encode 16 kHz mono audio samples in the given format, in memory
+pub fn encode_audio(samples: &[f32], format: AudioFileFormat) -> Result<Vec<u8>> {
+    let mut data = Vec::new();
+    match format {
+        AudioFileFormat::Wav => {
+            let spec = WavSpec {
+                channels: 1,
+                sample_rate: constants::WHISPER_SAMPLE_RATE,
+                bits_per_sample: 16,
+                sample_format: hound::SampleFormat::Int,
+            };
 
-    let mut writer = WavWriter::create(file_path.as_ref(), spec)?;
+            let mut writer = WavWriter::new(Cursor::new(&mut data), spec)?;
 
-    // Convert f32 samples to i16 for WAV
-    for sample in samples {
-        let sample_i16 = (sample * i16::MAX as f32) as i16;
-        writer.write_sample(sample_i16)?;
+            // Convert f32 samples to i16 for WAV
+            for sample in samples {
+                let sample_i16 = (sample * i16::MAX as f32) as i16;
+                writer.write_sample(sample_i16)?;
+            }
+
+            writer.finalize()?;
+        }
+        AudioFileFormat::Flac => {
+            let pcm: Vec<i16> = samples
+                .iter()
+                .map(|sample| (sample * i16::MAX as f32) as i16)
+                .collect();
+            encode_flac(&mut data, &pcm, constants::WHISPER_SAMPLE_RATE)?;
+        }
+        AudioFileFormat::Opus { bitrate } => {
+            encode_ogg_opus(&mut data, samples, constants::WHISPER_SAMPLE_RATE, bitrate)?;
+        }
     }
+    Ok(data)
+}
 
-    writer.finalize()?;
-    debug!("Saved WAV file: {:?}", file_path.as_ref());
-    Ok(())
+/// Read a mono recording saved by [`save_audio_file`], returning its samples
+/// and sample rate. The format is taken from the file extension.
+pub fn read_audio_file<P: AsRef<Path>>(file_path: P) -> Result<(Vec<f32>, u32)> {
+    let path = file_path.as_ref();
+    let extension = path
+        .extension()
+        .and_then(|ext| ext.to_str())
+        .unwrap_or_default();
+    decode_audio(Cursor::new(fs::read(path)?), extension)
+}
+
+/// Decode a recording held in memory or any other reader, by the extension
+/// its file would have.
+pub fn decode_audio<R: Read + Seek>(reader: R, extension: &str) -> Result<(Vec<f32>, u32)> {
+    match extension.to_ascii_lowercase().as_str() {
+        "wav" => {
+            let reader = hound::WavReader::new(reader)?;
+            let spec = reader.spec();
+            let samples = match spec.sample_format {
+                hound::SampleFormat::Float => reader
+                    .into_samples::<f32>()
+                    .collect::<Result<Vec<_>, _>>()?,
+                hound::SampleFormat::Int => {
+                    let scale = int_sample_scale(spec.bits_per_sample.into()).ok_or_else(|| {
+                        anyhow!("Unsupported bit depth: {}", spec.bits_per_sample)
+                    })?;
+                    reader
+                        .into_samples::<i32>()
+                        .map(|sample| sample.map(|s| s as f32 * scale))
+                        .collect::<Result<Vec<_>, _>>()?
+                }
+            };
+            Ok((downmix(samples, spec.channels as usize), spec.sample_rate))
+        }
+        "flac" => {
+            let mut reader = claxon::FlacReader::new(reader)?;
+            let info = reader.streaminfo();
+            let scale = int_sample_scale(info.bits_per_sample)
+                .ok_or_else(|| anyhow!("Unsupported bit depth: {}", info.bits_per_sample))?;
+            let samples = reader
+                .samples()
+                .map(|sample| sample.map(|s| s as f32 * scale))
+                .collect::<Result<Vec<_>, _>>()?;
+            Ok((downmix(samples, info.channels as usize), info.sample_rate))
+        }
+        "opus" | "ogg" => decode_ogg_opus(reader),
+        other => bail!("Unsupported audio file type: {other:?}"),
+    }
+}
+
+/// Factor bringing integer samples of `bits_per_sample` bits into -1..1, or
+/// `None` for a bit depth no valid file has.
+pub(super) fn int_sample_scale(bits_per_sample: u32) -> Option<f32> {
+    (1..=32)
+        .contains(&bits_per_sample)
+        .then(|| 1.0 / (1u64 << (bits_per_sample - 1)) as f32)
+}
+
+fn downmix(samples: Vec<f32>, channels: usize) -> Vec<f32> {
+    if channels <= 1 {
+        return samples;
+    }
+    samples
+        .chunks_exact(channels)
+        .map(|frame| frame.iter().sum::<f32>() / channels as f32)
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn tone() -> Vec<f32> {
+        (0..8000).map(|i| (i as f32 * 0.05).sin() * 0.25).collect()
+    }
+
+    fn round_trip(format: AudioFileFormat) -> Vec<f32> {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join(format!("recording.{}", format.extension()));
+        write_audio_file(&path, &tone(), format).unwrap();
+
+        let (samples, rate) = read_audio_file(&path).unwrap();
+        assert_eq!(rate, constants::WHISPER_SAMPLE_RATE);
+        samples
+    }
+
+    #[test]
+    fn lossless_formats_round_trip() {
+        let original = tone();
+        for format in [AudioFileFormat::Wav, AudioFileFormat::Flac] {
+            let samples = round_trip(format);
+            assert_eq!(samples.len(), original.len());
+            assert!(samples
+                .iter()
+                .zip(&original)
+                .all(|(a, b)| (a - b).abs() < 1e-3));
+        }
+    }
+
+    #[test]
+    fn recordings_decode_from_memory() {
+        let data = encode_audio(&tone(), AudioFileFormat::Flac).unwrap();
+        let (samples, rate) = decode_audio(Cursor::new(data), "FLAC").unwrap();
+        assert_eq!(rate, constants::WHISPER_SAMPLE_RATE);
+        assert_eq!(samples.len(), tone().len());
+
+        assert!(decode_audio(Cursor::new(Vec::new()), "mp3").is_err());
+    }
+
+    #[test]
+    fn rejects_impossible_bit_depths() {
+        assert_eq!(int_sample_scale(16), Some(1.0 / 32768.0));
+        assert_eq!(int_sample_scale(32), Some(1.0 / 2147483648.0));
+        assert_eq!(int_sample_scale(0), None);
+        assert_eq!(int_sample_scale(33), None);
+    }
+
+    #[test]
+    fn opus_keeps_the_recording_length() {
+        let samples = round_trip(AudioFileFormat::Opus { bitrate: 24000 });
+        assert_eq!(samples.len(), tone().len());
+    }
 }
@@ -2,7 +2,7 @@ use hound::WavWriter;
 use std::io::{self, Write};
 
 use handy_app_lib::audio_toolkit::{
-    audio::{list_input_devices, CpalDeviceInfo},
+    audio::{list_input_devices, CpalDeviceInfo, Pace, WavFileSource},
     vad::SmoothedVad,
     AudioRecorder, SileroVad,
 };
@@ -233,6 +233,32 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
                 }
                 Err(e) => println!("Error stopping recording: {}", e),
             },
+            "file" | "f" => {
+                if parts.len() < 2 {
+                    println!("Usage: file <path.wav>");
+                    continue;
+                }
+                if state.is_open {
+                    if let Err(e) = state.close() {
+                        println!("Error closing recorder: {}", e);
+                        continue;
+                    }
+                }
+                let source = WavFileSource::new(parts[1], Pace::Unthrottled);
+                match state.recorder.record_source(Box::new(source)) {
+                    Ok(samples) => {
+                        let filename = format!("recording_{}.wav", state.recording_index);
+                        match save_audio(&samples, &filename) {
+                            Ok(_) => {
+                                println!("Processed {} saved as: {}", parts[1], filename);
+                                state.recording_index += 1;
+                            }
+                            Err(e) => println!("Error saving recording: {}", e),
+                        }
+                    }
+                    Err(e) => println!("Error processing file: {}", e),
+                }
+            }
             "mode" => {
                 if parts.len() > 1 {
                     let new_mode = match parts[1].to_lowercase().as_str() {
@@ -315,6 +341,7 @@ fn print_help() {
         "  start [device_index] | s [device_index]  - Start recording (optionally with device)"
     );
     println!("  stop                                      - Stop recording and save");
+    println!("  file <path> | f <path>                    - Run a WAV file through the pipeline");
     println!(
         "  mode [always|demand]                      - Switch recording mode or show current mode"
     );
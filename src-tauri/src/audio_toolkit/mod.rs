@@ -5,8 +5,13 @@ pub mod utils;
 pub mod vad;
 
 pub use audio::{
-    list_input_devices, list_output_devices, save_wav_file, AudioRecorder, CpalDeviceInfo,
+    decode_audio, encode_audio, list_input_devices, list_output_devices, read_audio_file,
+    save_audio_file, save_wav_file, write_audio_file, AudioFileFormat, AudioRecorder, ChannelMix,
+    CpalDeviceInfo, InputLevel, PreprocessConfig, RecordingLevels, RecordingLimitEvent,
 };
-pub use text::{apply_custom_words, filter_transcription_output};
-pub use utils::get_cpal_host;
+pub use text::{
+    apply_custom_words, apply_replacements, filter_transcription_output, word_substitutions,
+    WordSubstitution,
+};
+pub use utils::{available_host_names, get_cpal_host, host_id_from_name, set_preferred_host};
 pub use vad::{SileroVad, VoiceActivityDetector};
@@ -194,6 +194,127 @@ fn extract_punctuation(word: &str) -> (&str, &str) {
     (prefix, suffix)
 }
 
+/// A run of words replaced by different words between two versions of a
+/// transcript.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct WordSubstitution {
+    pub original: String,
+    pub replacement: String,
+}
+
+/// Longest run of words on either side that still counts as a substitution.
+/// Longer rewrites are edits rather than corrections.
+const MAX_SUBSTITUTION_WORDS: usize = 3;
+
+/// Transcripts longer than this aren't diffed, to bound the quadratic table.
+const MAX_DIFF_WORDS: usize = 2000;
+
+/// Finds the words replaced between an original and an edited transcript
+///
+/// Words are aligned with a longest-common-subsequence diff, comparing them
+/// without surrounding punctuation. Each run of removed words directly
+/// followed by inserted words becomes a substitution; plain insertions and
+/// deletions are ignored.
+pub fn word_substitutions(original: &str, edited: &str) -> Vec<WordSubstitution> {
+    let old: Vec<&str> = original.split_whitespace().map(strip_punctuation).collect();
+    let new: Vec<&str> = edited.split_whitespace().map(strip_punctuation).collect();
+    if old.len() > MAX_DIFF_WORDS || new.len() > MAX_DIFF_WORDS {
+        return Vec::new();
+    }
+
+    // lcs[i][j] is the common subsequence length of old[i..] and new[j..]
+    let mut lcs = vec![vec![0usize; new.len() + 1]; old.len() + 1];
+    for i in (0..old.len()).rev() {
+        for j in (0..new.len()).rev() {
+            lcs[i][j] = if old[i] == new[j] {
+                lcs[i + 1][j + 1] + 1
+            } else {
+                lcs[i + 1][j].max(lcs[i][j + 1])
+            };
+        }
+    }
+
+    let mut substitutions = Vec::new();
+    let (mut i, mut j) = (0, 0);
+    let (mut removed_from, mut inserted_from) = (0, 0);
+    loop {
+        let matched = i < old.len() && j < new.len() && old[i] == new[j];
+        let done = i == old.len() && j == new.len();
+        if matched || done {
+            let removed = &old[removed_from..i];
+            let inserted = &new[inserted_from..j];
+            if !removed.is_empty()
+                && !inserted.is_empty()
+                && removed.len() <= MAX_SUBSTITUTION_WORDS
+                && inserted.len() <= MAX_SUBSTITUTION_WORDS
+            {
+                let original = removed.join(" ");
+                let replacement = inserted.join(" ");
+                if !is_capitalization_change(&original, &replacement) {
+                    substitutions.push(WordSubstitution {
+                        original,
+                        replacement,
+                    });
+                }
+            }
+            if done {
+                break;
+            }
+            i += 1;
+            j += 1;
+            removed_from = i;
+            inserted_from = j;
+        } else if j == new.len() || (i < old.len() && lcs[i + 1][j] >= lcs[i][j + 1]) {
+            i += 1;
+        } else {
+            j += 1;
+        }
+    }
+
+    substitutions
+}
+
+/// Whether two phrases differ only in the case of their first letter, as when
+/// a sentence is recapitalized.
+fn is_capitalization_change(original: &str, replacement: &str) -> bool {
+    let mut a = original.chars();
+    let mut b = replacement.chars();
+    match (a.next(), b.next()) {
+        (Some(x), Some(y)) => x.to_lowercase().eq(y.to_lowercase()) && a.eq(b),
+        _ => false,
+    }
+}
+
+fn strip_punctuation(word: &str) -> &str {
+    word.trim_matches(|c: char| !c.is_alphanumeric())
+}
+
+/// Replaces whole-word occurrences of each `from` with its `to`, ignoring case
+pub fn apply_replacements<'a>(
+    text: &str,
+    replacements: impl IntoIterator<Item = (&'a str, &'a str)>,
+) -> String {
+    let mut result = text.to_string();
+    for (from, to) in replacements {
+        if from.trim().is_empty() {
+            continue;
+        }
+        // Only anchor on word boundaries where the phrase has word characters
+        let starts_with_word = from.starts_with(|c: char| c.is_alphanumeric());
+        let ends_with_word = from.ends_with(|c: char| c.is_alphanumeric());
+        let pattern = format!(
+            r"(?i){}{}{}",
+            if starts_with_word { r"\b" } else { "" },
+            regex::escape(from),
+            if ends_with_word { r"\b" } else { "" }
+        );
+        if let Ok(re) = Regex::new(&pattern) {
+            result = re.replace_all(&result, regex::NoExpand(to)).into_owned();
+        }
+    }
+    result
+}
+
 /// Returns filler words appropriate for the given language code.
 ///
 /// Some words like "um" and "ha" are real words in certain languages
@@ -324,6 +445,50 @@ pub fn filter_transcription_output(
 mod tests {
     use super::*;
 
+    fn substitution(original: &str, replacement: &str) -> WordSubstitution {
+        WordSubstitution {
+            original: original.to_string(),
+            replacement: replacement.to_string(),
+        }
+    }
+
+    #[test]
+    fn test_word_substitutions_finds_replaced_runs() {
+        let subs = word_substitutions(
+            "I asked chat gpt about the cube control config.",
+            "I asked ChatGPT about the kubectl config today.",
+        );
+        assert_eq!(
+            subs,
+            vec![
+                substitution("chat gpt", "ChatGPT"),
+                substitution("cube control", "kubectl"),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_word_substitutions_ignores_insertions_and_punctuation() {
+        assert!(word_substitutions("hello world", "hello, big world!").is_empty());
+        assert!(word_substitutions("one two three", "one three").is_empty());
+        assert!(word_substitutions("so it begins", "So it begins").is_empty());
+        // Rewriting a whole sentence is not a correction
+        assert!(word_substitutions("a b c d e", "v w x y z").is_empty());
+    }
+
+    #[test]
+    fn test_apply_replacements_matches_whole_words() {
+        let text = "Ask Chat GPT, not chat gpteam.";
+        assert_eq!(
+            apply_replacements(text, [("chat gpt", "ChatGPT")]),
+            "Ask ChatGPT, not chat gpteam."
+        );
+        assert_eq!(
+            apply_replacements("cost $5", [("$5", "five dollars")]),
+            "cost five dollars"
+        );
+    }
+
     #[test]
     fn test_apply_custom_words_exact_match() {
         let text = "hello world";
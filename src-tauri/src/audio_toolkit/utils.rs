@@ -1,6 +1,20 @@
+use std::sync::Mutex;
+
+/// Host chosen by the user, overriding the platform default.
+static PREFERRED_HOST: Mutex<Option<cpal::HostId>> = Mutex::new(None);
+
 /// Returns the appropriate CPAL host for the current platform.
-/// On Linux, uses ALSA host. On other platforms, uses the default host.
+/// Uses the host set with [`set_preferred_host`] when it is available.
+/// Otherwise, on Linux, uses ALSA host. On other platforms, uses the default host.
 pub fn get_cpal_host() -> cpal::Host {
+    let preferred = *PREFERRED_HOST.lock().unwrap();
+    if let Some(id) = preferred {
+        match cpal::host_from_id(id) {
+            Ok(host) => return host,
+            Err(e) => log::warn!("Audio host {} unavailable: {}", id.name(), e),
+        }
+    }
+
     #[cfg(target_os = "linux")]
     {
         cpal::host_from_id(cpal::HostId::Alsa).unwrap_or_else(|_| cpal::default_host())
@@ -10,3 +24,23 @@ pub fn get_cpal_host() -> cpal::Host {
         cpal::default_host()
     }
 }
+
+/// Makes [`get_cpal_host`] return `id`, or the platform default for `None`.
+pub fn set_preferred_host(id: Option<cpal::HostId>) {
+    *PREFERRED_HOST.lock().unwrap() = id;
+}
+
+/// Names of the hosts cpal reports as available on this machine.
+pub fn available_host_names() -> Vec<String> {
+    cpal::available_hosts()
+        .into_iter()
+        .map(|id| id.name().to_string())
+        .collect()
+}
+
+/// Looks up an available host by the name [`available_host_names`] returns.
+pub fn host_id_from_name(name: &str) -> Option<cpal::HostId> {
+    cpal::available_hosts()
+        .into_iter()
+        .find(|id| id.name().eq_ignore_ascii_case(name))
+}
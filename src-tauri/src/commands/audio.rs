@@ -1,7 +1,12 @@
 use crate::audio_feedback;
-use crate::audio_toolkit::audio::{list_input_devices, list_output_devices};
-use crate::managers::audio::{AudioRecordingManager, MicrophoneMode};
-use crate::settings::{get_settings, write_settings};
+use crate::audio_toolkit::audio::{input_channel_count, list_input_devices, list_output_devices};
+use crate::audio_toolkit::{
+    available_host_names, get_cpal_host, host_id_from_name, set_preferred_host,
+};
+use crate::managers::audio::{AudioRecordingManager, InputLevelEvent, MicrophoneMode};
+use crate::settings::{
+    get_settings, switch_audio_host, write_settings, CaptureSource, ChannelSelection,
+};
 use log::warn;
 use serde::{Deserialize, Serialize};
 use specta::Type;
@@ -87,15 +92,20 @@ pub fn get_available_microphones() -> Result<Vec<AudioDevice>, String> {
     Ok(result)
 }
 
+/// Makes `device_name` the most preferred microphone. Selecting "default"
+/// clears the priority list so the system default is used.
 #[tauri::command]
 #[specta::specta]
 pub fn set_selected_microphone(app: AppHandle, device_name: String) -> Result<(), String> {
     let mut settings = get_settings(&app);
-    settings.selected_microphone = if device_name == "default" {
-        None
+    if device_name == "default" {
+        settings.microphone_priority.clear();
     } else {
-        Some(device_name)
-    };
+        settings
+            .microphone_priority
+            .retain(|name| *name != device_name);
+        settings.microphone_priority.insert(0, device_name);
+    }
     write_settings(&app, settings);
 
     // Update the audio manager to use the new device
@@ -111,7 +121,9 @@ pub fn set_selected_microphone(app: AppHandle, device_name: String) -> Result<()
 pub fn get_selected_microphone(app: AppHandle) -> Result<String, String> {
     let settings = get_settings(&app);
     Ok(settings
-        .selected_microphone
+        .microphone_priority
+        .into_iter()
+        .next()
         .unwrap_or_else(|| "default".to_string()))
 }
 
@@ -161,37 +173,305 @@ pub fn get_selected_output_device(app: AppHandle) -> Result<String, String> {
 #[tauri::command]
 #[specta::specta]
 pub async fn play_test_sound(app: AppHandle, sound_type: String) {
-    let sound = match sound_type.as_str() {
-        "start" => audio_feedback::SoundType::Start,
-        "stop" => audio_feedback::SoundType::Stop,
-        _ => {
-            warn!("Unknown sound type: {}", sound_type);
-            return;
-        }
+    let Some(sound) = audio_feedback::SoundType::from_name(&sound_type) else {
+        warn!("Unknown sound type: {}", sound_type);
+        return;
     };
     audio_feedback::play_test_sound(&app, sound);
 }
 
 #[tauri::command]
 #[specta::specta]
-pub fn set_clamshell_microphone(app: AppHandle, device_name: String) -> Result<(), String> {
+pub fn get_available_sound_themes(app: AppHandle) -> Vec<audio_feedback::SoundThemeInfo> {
+    audio_feedback::list_sound_themes(&app)
+}
+
+/// Selects a theme folder to override the built-in sounds, or `None` to use
+/// the built-in theme alone.
+#[tauri::command]
+#[specta::specta]
+pub fn set_sound_theme_folder(app: AppHandle, name: Option<String>) -> Result<(), String> {
+    if let Some(name) = &name {
+        if !audio_feedback::sound_theme_exists(&app, name) {
+            return Err(format!("Sound theme '{}' not found", name));
+        }
+    }
     let mut settings = get_settings(&app);
-    settings.clamshell_microphone = if device_name == "default" {
-        None
+    settings.sound_theme_folder = name;
+    write_settings(&app, settings);
+    Ok(())
+}
+
+#[tauri::command]
+#[specta::specta]
+pub fn set_microphone_priority(app: AppHandle, device_names: Vec<String>) -> Result<(), String> {
+    let mut settings = get_settings(&app);
+    settings.microphone_priority = device_names
+        .into_iter()
+        .map(|name| name.trim().to_string())
+        .filter(|name| !name.is_empty())
+        .collect();
+    write_settings(&app, settings);
+
+    // Reopen in case a different device now wins
+    let rm = app.state::<Arc<AudioRecordingManager>>();
+    rm.update_selected_device()
+        .map_err(|e| format!("Failed to update selected device: {}", e))?;
+
+    Ok(())
+}
+
+#[tauri::command]
+#[specta::specta]
+pub fn get_microphone_priority(app: AppHandle) -> Result<Vec<String>, String> {
+    let settings = get_settings(&app);
+    Ok(settings.microphone_priority)
+}
+
+#[tauri::command]
+#[specta::specta]
+pub fn get_active_microphone(app: AppHandle) -> Option<String> {
+    let rm = app.state::<Arc<AudioRecordingManager>>();
+    rm.active_microphone()
+}
+
+#[tauri::command]
+#[specta::specta]
+pub fn get_microphone_channel_count(device_name: String) -> Result<u16, String> {
+    let devices =
+        list_input_devices().map_err(|e| format!("Failed to list audio devices: {}", e))?;
+    devices
+        .iter()
+        .find(|d| d.name == device_name)
+        .and_then(|d| input_channel_count(&d.device))
+        .ok_or_else(|| format!("Device '{}' not found", device_name))
+}
+
+#[tauri::command]
+#[specta::specta]
+pub fn set_microphone_channel(
+    app: AppHandle,
+    device_name: String,
+    selection: ChannelSelection,
+) -> Result<(), String> {
+    let mut settings = get_settings(&app);
+    if selection == ChannelSelection::Average {
+        settings.microphone_channels.remove(&device_name);
     } else {
-        Some(device_name)
-    };
+        settings.microphone_channels.insert(device_name, selection);
+    }
     write_settings(&app, settings);
+
+    // Reopen so the stream callback picks up the new selection
+    let rm = app.state::<Arc<AudioRecordingManager>>();
+    rm.update_selected_device()
+        .map_err(|e| format!("Failed to update selected device: {}", e))?;
+
     Ok(())
 }
 
 #[tauri::command]
 #[specta::specta]
-pub fn get_clamshell_microphone(app: AppHandle) -> Result<String, String> {
+pub fn get_microphone_channel(app: AppHandle, device_name: String) -> ChannelSelection {
     let settings = get_settings(&app);
-    Ok(settings
-        .clamshell_microphone
-        .unwrap_or_else(|| "default".to_string()))
+    settings
+        .microphone_channels
+        .get(&device_name)
+        .copied()
+        .unwrap_or_default()
+}
+
+#[tauri::command]
+#[specta::specta]
+pub fn get_available_audio_hosts() -> Vec<String> {
+    available_host_names()
+}
+
+#[tauri::command]
+#[specta::specta]
+pub fn get_audio_host() -> String {
+    get_cpal_host().id().name().to_string()
+}
+
+/// Switches the cpal host used for listing and opening devices. Device
+/// selections are remembered per host.
+#[tauri::command]
+#[specta::specta]
+pub fn set_audio_host(app: AppHandle, host: String) -> Result<(), String> {
+    let id = host_id_from_name(&host)
+        .ok_or_else(|| format!("Audio host '{}' is not available", host))?;
+    let current = get_cpal_host().id().name();
+
+    let mut settings = get_settings(&app);
+    switch_audio_host(&mut settings, current, id.name());
+    settings.audio_host = Some(id.name().to_string());
+    write_settings(&app, settings);
+
+    set_preferred_host(Some(id));
+    restart_microphone_stream(&app)
+}
+
+#[derive(Serialize, Type)]
+pub struct MonitorSourceInfo {
+    pub name: String,
+    pub description: String,
+}
+
+#[tauri::command]
+#[specta::specta]
+pub fn get_available_monitor_sources(app: AppHandle) -> Vec<MonitorSourceInfo> {
+    let rm = app.state::<Arc<AudioRecordingManager>>();
+    rm.monitor_sources()
+        .into_iter()
+        .map(|s| MonitorSourceInfo {
+            name: s.name,
+            description: s.description,
+        })
+        .collect()
+}
+
+/// Chooses between microphone, system audio, or both. `monitor_source` of
+/// `None` captures the default output device's monitor.
+#[tauri::command]
+#[specta::specta]
+pub fn set_audio_source(
+    app: AppHandle,
+    source: CaptureSource,
+    monitor_source: Option<String>,
+) -> Result<(), String> {
+    let mut settings = get_settings(&app);
+    settings.audio_source = source;
+    settings.monitor_source = monitor_source.filter(|name| !name.trim().is_empty());
+    write_settings(&app, settings);
+    restart_microphone_stream(&app)
+}
+
+#[tauri::command]
+#[specta::specta]
+pub fn get_audio_source(app: AppHandle) -> CaptureSource {
+    get_settings(&app).audio_source
+}
+
+/// Upper bound for the per-device software gain.
+const MAX_MICROPHONE_GAIN: f32 = 8.0;
+
+#[tauri::command]
+#[specta::specta]
+pub fn set_microphone_gain(app: AppHandle, device_name: String, gain: f32) -> Result<(), String> {
+    if !gain.is_finite() || gain < 0.0 {
+        return Err(format!("Invalid gain: {}", gain));
+    }
+    let gain = gain.min(MAX_MICROPHONE_GAIN);
+
+    let rm = app.state::<Arc<AudioRecordingManager>>();
+    rm.update_gain(&device_name, gain);
+
+    let mut settings = get_settings(&app);
+    if gain == 1.0 {
+        settings.microphone_gains.remove(&device_name);
+    } else {
+        settings.microphone_gains.insert(device_name, gain);
+    }
+    write_settings(&app, settings);
+    Ok(())
+}
+
+#[tauri::command]
+#[specta::specta]
+pub fn get_microphone_gain(app: AppHandle, device_name: String) -> f32 {
+    let settings = get_settings(&app);
+    settings
+        .microphone_gains
+        .get(&device_name)
+        .copied()
+        .unwrap_or(1.0)
+}
+
+#[tauri::command]
+#[specta::specta]
+pub fn get_input_level(app: AppHandle) -> Option<InputLevelEvent> {
+    let rm = app.state::<Arc<AudioRecordingManager>>();
+    rm.input_level().map(InputLevelEvent::from)
+}
+
+#[tauri::command]
+#[specta::specta]
+pub fn start_input_level_monitor(app: AppHandle) -> Result<(), String> {
+    let rm = app.state::<Arc<AudioRecordingManager>>();
+    rm.start_level_monitor()
+        .map_err(|e| format!("Failed to open microphone: {}", e))
+}
+
+#[tauri::command]
+#[specta::specta]
+pub fn stop_input_level_monitor(app: AppHandle) {
+    let rm = app.state::<Arc<AudioRecordingManager>>();
+    rm.stop_level_monitor();
+}
+
+/// Reopens an active stream so capture setting changes apply, or after the
+/// current recording when one is in progress.
+fn restart_microphone_stream(app: &AppHandle) -> Result<(), String> {
+    let rm = app.state::<Arc<AudioRecordingManager>>();
+    rm.update_selected_device()
+        .map_err(|e| format!("Failed to restart microphone stream: {}", e))
+}
+
+#[tauri::command]
+#[specta::specta]
+pub fn change_high_pass_filter_setting(app: AppHandle, enabled: bool) -> Result<(), String> {
+    let mut settings = get_settings(&app);
+    settings.high_pass_filter = enabled;
+    write_settings(&app, settings);
+    restart_microphone_stream(&app)
+}
+
+#[tauri::command]
+#[specta::specta]
+pub fn change_noise_reduction_setting(app: AppHandle, enabled: bool) -> Result<(), String> {
+    let mut settings = get_settings(&app);
+    settings.noise_reduction = enabled;
+    write_settings(&app, settings);
+    restart_microphone_stream(&app)
+}
+
+#[tauri::command]
+#[specta::specta]
+pub fn change_auto_gain_setting(app: AppHandle, enabled: bool) -> Result<(), String> {
+    let mut settings = get_settings(&app);
+    settings.auto_gain = enabled;
+    write_settings(&app, settings);
+    restart_microphone_stream(&app)
+}
+
+/// Caps recording length; `None` records until stopped.
+#[tauri::command]
+#[specta::specta]
+pub fn change_max_recording_duration_setting(
+    app: AppHandle,
+    seconds: Option<u32>,
+) -> Result<(), String> {
+    if seconds == Some(0) {
+        return Err("Maximum recording duration must be at least one second".to_string());
+    }
+    let mut settings = get_settings(&app);
+    settings.max_recording_duration_secs = seconds;
+    write_settings(&app, settings);
+    restart_microphone_stream(&app)
+}
+
+/// Sets how much of a recording is kept in memory before the rest is written
+/// to disk; `None` keeps all of it in memory.
+#[tauri::command]
+#[specta::specta]
+pub fn change_spill_after_setting(app: AppHandle, seconds: Option<u32>) -> Result<(), String> {
+    if seconds == Some(0) {
+        return Err("At least one second of audio must be kept in memory".to_string());
+    }
+    let mut settings = get_settings(&app);
+    settings.spill_after_secs = seconds;
+    write_settings(&app, settings);
+    restart_microphone_stream(&app)
 }
 
 #[tauri::command]
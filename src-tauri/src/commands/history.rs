@@ -1,6 +1,64 @@
-use crate::managers::history::{HistoryEntry, HistoryManager};
+use crate::encryption::EncryptionState;
+use crate::managers::audio::AudioRecordingManager;
+use crate::managers::backup::{ExportSummary, ImportReport};
+use crate::managers::history::{
+    AlternativeTranscript, HistoryCollection, HistoryEntry, HistoryManager, HistoryPage,
+    HistoryPageQuery, HistorySearchQuery, HistorySearchResults, HistoryTag, LearnedCorrection,
+    TranscriptionMetadata,
+};
+use crate::managers::retranscription::{RetranscriptionJob, RetranscriptionManager};
+use crate::managers::stats::{UsageStats, UsageStatsQuery};
+use crate::managers::transcription::TranscriptionManager;
+use crate::settings::{AppSettings, WordReplacement};
+use log::{error, info};
+use serde::Serialize;
+use specta::Type;
+use std::path::PathBuf;
 use std::sync::Arc;
-use tauri::{AppHandle, State};
+use tauri::{AppHandle, Emitter, Manager, State};
+
+/// How many times a correction is made by hand before it is applied to every
+/// transcription.
+const LEARN_CORRECTION_AFTER: u32 = 3;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Type)]
+#[serde(rename_all = "snake_case")]
+pub enum CorrectionKind {
+    /// A single word, best added to the custom words so near misses are
+    /// fixed too.
+    CustomWord,
+    /// A phrase replaced as a whole.
+    Replacement,
+}
+
+#[derive(Clone, Debug, Serialize, Type)]
+pub struct CorrectionSuggestion {
+    pub original: String,
+    pub replacement: String,
+    pub count: u32,
+    pub kind: CorrectionKind,
+    /// Already applied to every transcription as a word replacement.
+    pub applied: bool,
+}
+
+fn suggestion(settings: &AppSettings, correction: LearnedCorrection) -> CorrectionSuggestion {
+    let kind = if correction.replacement.contains(char::is_whitespace) {
+        CorrectionKind::Replacement
+    } else {
+        CorrectionKind::CustomWord
+    };
+    let applied = settings
+        .word_replacements
+        .iter()
+        .any(|r| r.from.eq_ignore_ascii_case(&correction.original));
+    CorrectionSuggestion {
+        original: correction.original,
+        replacement: correction.replacement,
+        count: correction.count,
+        kind,
+        applied,
+    }
+}
 
 #[tauri::command]
 #[specta::specta]
@@ -14,6 +72,38 @@ pub async fn get_history_entries(
         .map_err(|e| e.to_string())
 }
 
+#[tauri::command]
+#[specta::specta]
+pub async fn get_history_page(
+    history_manager: State<'_, Arc<HistoryManager>>,
+    query: HistoryPageQuery,
+) -> Result<HistoryPage, String> {
+    history_manager
+        .get_history_page(&query)
+        .map_err(|e| e.to_string())
+}
+
+/// Fetches the entries named in a `history-updated` event.
+#[tauri::command]
+#[specta::specta]
+pub async fn get_history_entries_by_ids(
+    history_manager: State<'_, Arc<HistoryManager>>,
+    ids: Vec<i64>,
+) -> Result<Vec<HistoryEntry>, String> {
+    history_manager
+        .get_entries_by_ids(&ids)
+        .map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+#[specta::specta]
+pub async fn search_history(
+    history_manager: State<'_, Arc<HistoryManager>>,
+    query: HistorySearchQuery,
+) -> Result<HistorySearchResults, String> {
+    history_manager.search(&query).map_err(|e| e.to_string())
+}
+
 #[tauri::command]
 #[specta::specta]
 pub async fn toggle_history_entry_saved(
@@ -27,6 +117,78 @@ pub async fn toggle_history_entry_saved(
         .map_err(|e| e.to_string())
 }
 
+/// Replaces an entry's transcript with a hand edit. Corrections made often
+/// enough are added to the word replacements; the corrections this edit made
+/// are returned as suggestions.
+#[tauri::command]
+#[specta::specta]
+pub async fn update_history_entry_text(
+    app: AppHandle,
+    history_manager: State<'_, Arc<HistoryManager>>,
+    id: i64,
+    text: String,
+) -> Result<Vec<CorrectionSuggestion>, String> {
+    let learned = history_manager
+        .update_entry_text(id, text)
+        .map_err(|e| e.to_string())?;
+
+    let mut settings = crate::settings::get_settings(&app);
+    let mut changed = false;
+    for correction in &learned {
+        let known = settings
+            .word_replacements
+            .iter()
+            .any(|r| r.from.eq_ignore_ascii_case(&correction.original));
+        if correction.count >= LEARN_CORRECTION_AFTER && !known {
+            info!(
+                "Learned correction '{}' -> '{}'",
+                correction.original, correction.replacement
+            );
+            settings.word_replacements.push(WordReplacement {
+                from: correction.original.clone(),
+                to: correction.replacement.clone(),
+            });
+            changed = true;
+        }
+    }
+    if changed {
+        crate::settings::write_settings(&app, settings.clone());
+    }
+
+    Ok(learned
+        .into_iter()
+        .map(|correction| suggestion(&settings, correction))
+        .collect())
+}
+
+/// Restores the model's transcript of an edited entry.
+#[tauri::command]
+#[specta::specta]
+pub async fn revert_history_entry_text(
+    history_manager: State<'_, Arc<HistoryManager>>,
+    id: i64,
+) -> Result<(), String> {
+    history_manager
+        .revert_entry_text(id)
+        .map_err(|e| e.to_string())
+}
+
+/// Every correction made by hand in history, most frequent first.
+#[tauri::command]
+#[specta::specta]
+pub async fn get_correction_suggestions(
+    app: AppHandle,
+    history_manager: State<'_, Arc<HistoryManager>>,
+) -> Result<Vec<CorrectionSuggestion>, String> {
+    let settings = crate::settings::get_settings(&app);
+    Ok(history_manager
+        .learned_corrections()
+        .map_err(|e| e.to_string())?
+        .into_iter()
+        .map(|correction| suggestion(&settings, correction))
+        .collect())
+}
+
 #[tauri::command]
 #[specta::specta]
 pub async fn get_audio_file_path(
@@ -86,6 +248,8 @@ pub async fn update_recording_retention_period(
         "days3" => RecordingRetentionPeriod::Days3,
         "weeks2" => RecordingRetentionPeriod::Weeks2,
         "months3" => RecordingRetentionPeriod::Months3,
+        "text_only" => RecordingRetentionPeriod::TextOnly,
+        "disk_quota" => RecordingRetentionPeriod::DiskQuota,
         _ => return Err(format!("Invalid retention period: {}", period)),
     };
 
@@ -99,3 +263,479 @@ pub async fn update_recording_retention_period(
 
     Ok(())
 }
+
+/// Sets how old audio may get under the text-only retention period.
+#[tauri::command]
+#[specta::specta]
+pub async fn update_audio_retention_days(
+    app: AppHandle,
+    history_manager: State<'_, Arc<HistoryManager>>,
+    days: u32,
+) -> Result<(), String> {
+    if days == 0 {
+        return Err("Audio must be kept for at least a day".to_string());
+    }
+
+    let mut settings = crate::settings::get_settings(&app);
+    settings.audio_retention_days = days;
+    crate::settings::write_settings(&app, settings);
+
+    history_manager
+        .cleanup_old_entries()
+        .map_err(|e| e.to_string())?;
+
+    Ok(())
+}
+
+/// Sets the disk space recordings may take under the disk quota retention
+/// period.
+#[tauri::command]
+#[specta::specta]
+pub async fn update_recordings_quota(
+    app: AppHandle,
+    history_manager: State<'_, Arc<HistoryManager>>,
+    quota_mb: u64,
+) -> Result<(), String> {
+    if quota_mb == 0 {
+        return Err("The recordings quota must be at least 1 MB".to_string());
+    }
+
+    let mut settings = crate::settings::get_settings(&app);
+    settings.recordings_quota_mb = quota_mb;
+    crate::settings::write_settings(&app, settings);
+
+    history_manager
+        .cleanup_old_entries()
+        .map_err(|e| e.to_string())?;
+
+    Ok(())
+}
+
+#[tauri::command]
+#[specta::specta]
+pub async fn update_recording_format(
+    app: AppHandle,
+    format: crate::settings::RecordingFormat,
+    opus_bitrate_kbps: Option<u32>,
+) -> Result<(), String> {
+    let mut settings = crate::settings::get_settings(&app);
+    settings.recording_format = format;
+    if let Some(bitrate) = opus_bitrate_kbps {
+        settings.opus_bitrate_kbps = bitrate.clamp(6, 128);
+    }
+    crate::settings::write_settings(&app, settings);
+
+    Ok(())
+}
+
+/// Converts existing recordings to the current recording format in the
+/// background, emitting `recording-conversion-progress` along the way and
+/// `recording-conversion-finished` with a summary at the end.
+#[tauri::command]
+#[specta::specta]
+pub async fn convert_recordings(
+    app: AppHandle,
+    history_manager: State<'_, Arc<HistoryManager>>,
+) -> Result<(), String> {
+    if history_manager.is_converting() {
+        return Err("A recording conversion is already running".to_string());
+    }
+
+    let format = crate::settings::get_recording_file_format(&app);
+    let history_manager = history_manager.inner().clone();
+
+    std::thread::spawn(move || match history_manager.convert_recordings(format) {
+        Ok(summary) => {
+            if let Err(e) = app.emit("recording-conversion-finished", summary) {
+                error!("Failed to emit recording-conversion-finished event: {}", e);
+            }
+        }
+        Err(e) => error!("Recording conversion failed: {}", e),
+    });
+
+    Ok(())
+}
+
+#[tauri::command]
+#[specta::specta]
+pub async fn update_crash_safe_recording(app: AppHandle, enabled: bool) -> Result<(), String> {
+    let mut settings = crate::settings::get_settings(&app);
+    settings.crash_safe_recording = enabled;
+    crate::settings::write_settings(&app, settings);
+
+    Ok(())
+}
+
+/// Transcribes an entry recovered from a crash-safe journal and stores the
+/// result in history.
+#[tauri::command]
+#[specta::specta]
+pub async fn transcribe_recovered_entry(
+    app: AppHandle,
+    history_manager: State<'_, Arc<HistoryManager>>,
+    transcription_manager: State<'_, Arc<TranscriptionManager>>,
+    id: i64,
+) -> Result<String, String> {
+    let entry = history_manager
+        .get_entry_by_id(id)
+        .await
+        .map_err(|e| e.to_string())?
+        .ok_or_else(|| format!("History entry {} not found", id))?;
+    if !entry.recovered {
+        return Err(format!("History entry {} is not a recovered recording", id));
+    }
+
+    let path = history_manager.get_audio_file_path(&entry.file_name);
+    let hm = history_manager.inner().clone();
+    let tm = transcription_manager.inner().clone();
+    tm.initiate_model_load();
+
+    let (output, samples_len) = tauri::async_runtime::spawn_blocking(move || {
+        let (samples, _) = hm.read_recording(&path)?;
+        let samples_len = samples.len();
+        tm.transcribe_detailed(samples)
+            .map(|output| (output, samples_len))
+    })
+    .await
+    .map_err(|e| e.to_string())?
+    .map_err(|e| e.to_string())?;
+
+    // Journals keep what passed voice activity detection
+    let settings = crate::settings::get_settings(&app);
+    let metadata = TranscriptionMetadata {
+        model_id: output.model_id,
+        engine: output.engine.map(str::to_string),
+        language: Some(settings.selected_language),
+        translate: Some(settings.translate_to_english),
+        speech_duration_ms: Some(
+            samples_len as u64 * 1000 / crate::audio_toolkit::constants::WHISPER_SAMPLE_RATE as u64,
+        ),
+        inference_ms: Some(output.inference.as_millis() as u64),
+        ..Default::default()
+    };
+
+    history_manager
+        .complete_recovered_entry(id, output.text.clone(), &metadata)
+        .map_err(|e| e.to_string())?;
+
+    Ok(output.text)
+}
+
+/// Queues entries to be transcribed again with another model or language.
+/// Either name the entries in `ids` or pass a `query` to take every entry it
+/// matches. Results are stored as alternative transcripts and progress is
+/// reported through `retranscription-progress` events. Returns how many
+/// entries were queued.
+#[tauri::command]
+#[specta::specta]
+pub async fn retranscribe_history_entries(
+    app: AppHandle,
+    history_manager: State<'_, Arc<HistoryManager>>,
+    retranscription_manager: State<'_, Arc<RetranscriptionManager>>,
+    ids: Option<Vec<i64>>,
+    query: Option<HistoryPageQuery>,
+    model_id: String,
+    language: Option<String>,
+) -> Result<usize, String> {
+    let ids = match (ids, query) {
+        (Some(ids), _) => ids,
+        (None, Some(query)) => history_manager
+            .entry_ids_matching(&query)
+            .map_err(|e| e.to_string())?,
+        (None, None) => return Err("Either ids or a query is required".to_string()),
+    };
+
+    let language =
+        language.unwrap_or_else(|| crate::settings::get_settings(&app).selected_language);
+    let jobs: Vec<RetranscriptionJob> = ids
+        .into_iter()
+        .map(|entry_id| RetranscriptionJob {
+            entry_id,
+            model_id: model_id.clone(),
+            language: language.clone(),
+        })
+        .collect();
+    let queued = jobs.len();
+    info!(
+        "Queued {} entries for re-transcription with {} ({})",
+        queued, model_id, language
+    );
+    retranscription_manager.enqueue(jobs);
+    Ok(queued)
+}
+
+#[tauri::command]
+#[specta::specta]
+pub fn cancel_retranscription(
+    retranscription_manager: State<'_, Arc<RetranscriptionManager>>,
+) -> Result<(), String> {
+    retranscription_manager.cancel();
+    Ok(())
+}
+
+#[tauri::command]
+#[specta::specta]
+pub async fn get_alternative_transcripts(
+    history_manager: State<'_, Arc<HistoryManager>>,
+    entry_id: i64,
+) -> Result<Vec<AlternativeTranscript>, String> {
+    history_manager
+        .get_alternative_transcripts(entry_id)
+        .map_err(|e| e.to_string())
+}
+
+/// Words dictated, speaking rate, audio time, most used models and languages,
+/// and an estimate of the typing time saved over a date range.
+#[tauri::command]
+#[specta::specta]
+pub async fn get_usage_stats(
+    history_manager: State<'_, Arc<HistoryManager>>,
+    query: UsageStatsQuery,
+) -> Result<UsageStats, String> {
+    history_manager
+        .usage_stats(&query)
+        .map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+#[specta::specta]
+pub async fn get_history_tags(
+    history_manager: State<'_, Arc<HistoryManager>>,
+) -> Result<Vec<HistoryTag>, String> {
+    history_manager.get_tags().map_err(|e| e.to_string())
+}
+
+/// Tags an entry by name, creating the tag if it is new.
+#[tauri::command]
+#[specta::specta]
+pub async fn add_history_entry_tag(
+    history_manager: State<'_, Arc<HistoryManager>>,
+    id: i64,
+    tag: String,
+) -> Result<HistoryTag, String> {
+    history_manager
+        .tag_entry(id, &tag)
+        .map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+#[specta::specta]
+pub async fn remove_history_entry_tag(
+    history_manager: State<'_, Arc<HistoryManager>>,
+    id: i64,
+    tag_id: i64,
+) -> Result<(), String> {
+    history_manager
+        .untag_entry(id, tag_id)
+        .map_err(|e| e.to_string())
+}
+
+/// Keeps entries with this tag for `retention_days`, or like saved entries
+/// when `None`.
+#[tauri::command]
+#[specta::specta]
+pub async fn update_history_tag_retention(
+    history_manager: State<'_, Arc<HistoryManager>>,
+    tag_id: i64,
+    retention_days: Option<u32>,
+) -> Result<(), String> {
+    history_manager
+        .set_tag_retention(tag_id, retention_days)
+        .map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+#[specta::specta]
+pub async fn delete_history_tag(
+    history_manager: State<'_, Arc<HistoryManager>>,
+    tag_id: i64,
+) -> Result<(), String> {
+    history_manager
+        .delete_tag(tag_id)
+        .map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+#[specta::specta]
+pub async fn update_history_entry_note(
+    history_manager: State<'_, Arc<HistoryManager>>,
+    id: i64,
+    note: Option<String>,
+) -> Result<(), String> {
+    history_manager
+        .set_entry_note(id, note)
+        .map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+#[specta::specta]
+pub async fn get_history_collections(
+    history_manager: State<'_, Arc<HistoryManager>>,
+) -> Result<Vec<HistoryCollection>, String> {
+    history_manager.get_collections().map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+#[specta::specta]
+pub async fn create_history_collection(
+    history_manager: State<'_, Arc<HistoryManager>>,
+    name: String,
+) -> Result<HistoryCollection, String> {
+    history_manager
+        .create_collection(&name)
+        .map_err(|e| e.to_string())
+}
+
+/// Deletes a collection; its entries stay in history.
+#[tauri::command]
+#[specta::specta]
+pub async fn delete_history_collection(
+    history_manager: State<'_, Arc<HistoryManager>>,
+    collection_id: i64,
+) -> Result<(), String> {
+    history_manager
+        .delete_collection(collection_id)
+        .map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+#[specta::specta]
+pub async fn add_to_history_collection(
+    history_manager: State<'_, Arc<HistoryManager>>,
+    collection_id: i64,
+    ids: Vec<i64>,
+) -> Result<(), String> {
+    history_manager
+        .add_to_collection(collection_id, &ids)
+        .map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+#[specta::specta]
+pub async fn remove_from_history_collection(
+    history_manager: State<'_, Arc<HistoryManager>>,
+    collection_id: i64,
+    ids: Vec<i64>,
+) -> Result<(), String> {
+    history_manager
+        .remove_from_collection(collection_id, &ids)
+        .map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+#[specta::specta]
+pub async fn get_history_encryption(
+    history_manager: State<'_, Arc<HistoryManager>>,
+) -> Result<EncryptionState, String> {
+    Ok(history_manager.encryption_state())
+}
+
+/// Runs a blocking history operation off the async runtime.
+async fn run_blocking<T: Send + 'static>(
+    history_manager: &Arc<HistoryManager>,
+    f: impl FnOnce(&HistoryManager) -> anyhow::Result<T> + Send + 'static,
+) -> Result<T, String> {
+    let history_manager = history_manager.clone();
+    tauri::async_runtime::spawn_blocking(move || f(&history_manager))
+        .await
+        .map_err(|e| e.to_string())?
+        .map_err(|e| e.to_string())
+}
+
+/// Encrypts the history database and recordings with a key derived from
+/// `passphrase`. With `remember_key` the key is kept in the OS keyring so the
+/// history unlocks at startup.
+#[tauri::command]
+#[specta::specta]
+pub async fn enable_history_encryption(
+    app: AppHandle,
+    history_manager: State<'_, Arc<HistoryManager>>,
+    passphrase: String,
+    remember_key: bool,
+) -> Result<(), String> {
+    run_blocking(history_manager.inner(), move |hm| {
+        hm.enable_encryption(&passphrase, remember_key)
+    })
+    .await?;
+    reopen_microphone_stream(&app);
+    Ok(())
+}
+
+/// Decrypts the history and turns encryption off.
+#[tauri::command]
+#[specta::specta]
+pub async fn disable_history_encryption(
+    app: AppHandle,
+    history_manager: State<'_, Arc<HistoryManager>>,
+    passphrase: String,
+) -> Result<(), String> {
+    run_blocking(history_manager.inner(), move |hm| {
+        hm.disable_encryption(&passphrase)
+    })
+    .await?;
+    reopen_microphone_stream(&app);
+    Ok(())
+}
+
+/// Spilling to disk is decided when the stream opens, and is off while the
+/// history is encrypted.
+fn reopen_microphone_stream(app: &AppHandle) {
+    let audio_manager = app.state::<Arc<AudioRecordingManager>>();
+    if let Err(e) = audio_manager.update_selected_device() {
+        error!("Failed to reopen the microphone stream: {}", e);
+    }
+}
+
+#[tauri::command]
+#[specta::specta]
+pub async fn unlock_history(
+    history_manager: State<'_, Arc<HistoryManager>>,
+    passphrase: String,
+) -> Result<(), String> {
+    run_blocking(history_manager.inner(), move |hm| hm.unlock(&passphrase)).await
+}
+
+#[tauri::command]
+#[specta::specta]
+pub async fn lock_history(history_manager: State<'_, Arc<HistoryManager>>) -> Result<(), String> {
+    history_manager.lock().map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+#[specta::specta]
+pub async fn update_history_key_remembered(
+    history_manager: State<'_, Arc<HistoryManager>>,
+    remember_key: bool,
+) -> Result<(), String> {
+    history_manager
+        .set_remember_key(remember_key)
+        .map_err(|e| e.to_string())
+}
+
+/// Exports the whole history, with its recordings, to a tar.gz archive at
+/// `path`.
+#[tauri::command]
+#[specta::specta]
+pub async fn export_history(
+    history_manager: State<'_, Arc<HistoryManager>>,
+    path: String,
+) -> Result<ExportSummary, String> {
+    run_blocking(history_manager.inner(), move |hm| {
+        hm.export_archive(&PathBuf::from(path))
+    })
+    .await
+}
+
+/// Merges an exported archive into the history, skipping entries that are
+/// already in it.
+#[tauri::command]
+#[specta::specta]
+pub async fn import_history(
+    history_manager: State<'_, Arc<HistoryManager>>,
+    path: String,
+) -> Result<ImportReport, String> {
+    run_blocking(history_manager.inner(), move |hm| {
+        hm.import_archive(&PathBuf::from(path))
+    })
+    .await
+}
@@ -4,7 +4,7 @@ pub mod models;
 pub mod transcription;
 
 use crate::settings::{get_settings, write_settings, AppSettings, LogLevel};
-use crate::utils::cancel_current_operation;
+use crate::utils::{cancel_current_operation, set_recording_paused};
 use tauri::{AppHandle, Manager};
 use tauri_plugin_opener::OpenerExt;
 
@@ -14,6 +14,13 @@ pub fn cancel_operation(app: AppHandle) {
     cancel_current_operation(&app);
 }
 
+/// Resumes a paused recording. Does nothing when not recording.
+#[tauri::command]
+#[specta::specta]
+pub fn resume_recording(app: AppHandle) {
+    set_recording_paused(&app, false);
+}
+
 #[tauri::command]
 #[specta::specta]
 pub fn get_app_dir_path(app: AppHandle) -> Result<String, String> {
@@ -0,0 +1,527 @@
+//! Opt-in encryption of the history at rest.
+//!
+//! A 256-bit key is derived from the user's passphrase with Argon2id. The
+//! history database is encrypted by SQLCipher with that key, and each
+//! recording is sealed as a whole with XChaCha20-Poly1305. The key can be
+//! kept in the OS keyring (Secret Service on Linux) so the history unlocks on
+//! its own at startup; otherwise it is only held in memory once unlocked.
+//!
+//! SQLCipher is only linked with the `encryption` cargo feature. Other builds
+//! read plaintext history as usual but refuse to turn encryption on or open an
+//! encrypted history.
+
+use anyhow::{anyhow, bail, Result};
+use argon2::{Algorithm, Argon2, Params, Version};
+use chacha20poly1305::aead::rand_core::RngCore;
+use chacha20poly1305::aead::{Aead, AeadCore, KeyInit, OsRng, Payload};
+use chacha20poly1305::{Key, XChaCha20Poly1305, XNonce};
+use log::{debug, info, warn};
+use serde::{Deserialize, Serialize};
+use specta::Type;
+use std::fs;
+use std::io::Read;
+use std::path::{Path, PathBuf};
+use std::sync::RwLock;
+use zeroize::Zeroizing;
+
+/// File in the app data directory describing how the key is derived.
+pub const ENCRYPTION_CONFIG_FILE: &str = "encryption.json";
+
+/// Starts every sealed file; the byte after it is the format version.
+const SEALED_MAGIC: &[u8; 8] = b"HANDYENC";
+const SEALED_VERSION: u8 = 1;
+const NONCE_LEN: usize = 24;
+const SEALED_HEADER_LEN: usize = SEALED_MAGIC.len() + 1;
+
+const SQLITE_HEADER: &[u8; 16] = b"SQLite format 3\0";
+
+/// Sealed with the key so a wrong passphrase is told apart from a right one.
+const CHECK_PLAINTEXT: &[u8] = b"handy history key";
+
+const KEYRING_USER: &str = "history-key";
+
+// Argon2id parameters recommended by OWASP
+const KDF_MEMORY_KIB: u32 = 19 * 1024;
+const KDF_ITERATIONS: u32 = 2;
+const KDF_PARALLELISM: u32 = 1;
+
+/// Whether SQLCipher is linked into this build.
+pub const SUPPORTED: bool = cfg!(feature = "encryption");
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Type)]
+#[serde(rename_all = "snake_case")]
+pub enum EncryptionStatus {
+    /// History is stored in plaintext.
+    Disabled,
+    /// History is encrypted and the passphrase hasn't been entered.
+    Locked,
+    Unlocked,
+}
+
+#[derive(Clone, Debug, Serialize, Type)]
+pub struct EncryptionState {
+    pub status: EncryptionStatus,
+    /// The key is kept in the OS keyring.
+    pub remember_key: bool,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+struct VaultConfig {
+    salt: String,
+    memory_kib: u32,
+    iterations: u32,
+    parallelism: u32,
+    check: String,
+    remember_key: bool,
+}
+
+#[derive(Clone)]
+pub struct EncryptionKey(Zeroizing<[u8; 32]>);
+
+impl EncryptionKey {
+    fn derive(passphrase: &str, config: &VaultConfig) -> Result<Self> {
+        let salt = from_hex(&config.salt)?;
+        let params = Params::new(
+            config.memory_kib,
+            config.iterations,
+            config.parallelism,
+            Some(32),
+        )
+        .map_err(|e| anyhow!("Invalid key derivation parameters: {}", e))?;
+
+        let mut key = Zeroizing::new([0u8; 32]);
+        Argon2::new(Algorithm::Argon2id, Version::V0x13, params)
+            .hash_password_into(passphrase.as_bytes(), &salt, &mut key[..])
+            .map_err(|e| anyhow!("Failed to derive the encryption key: {}", e))?;
+        Ok(Self(key))
+    }
+
+    fn from_bytes(bytes: &[u8]) -> Result<Self> {
+        let bytes: [u8; 32] = bytes
+            .try_into()
+            .map_err(|_| anyhow!("Stored encryption key has the wrong length"))?;
+        Ok(Self(Zeroizing::new(bytes)))
+    }
+
+    /// Statement that opens a SQLCipher database with this key.
+    pub fn sqlcipher_pragma(&self) -> Zeroizing<String> {
+        Zeroizing::new(format!("PRAGMA key = \"x'{}'\";", to_hex(&self.0[..])))
+    }
+
+    /// Clause that attaches a database encrypted with this key.
+    pub fn sqlcipher_attach_key(&self) -> Zeroizing<String> {
+        Zeroizing::new(format!("KEY \"x'{}'\"", to_hex(&self.0[..])))
+    }
+
+    pub fn seal(&self, data: &[u8]) -> Result<Vec<u8>> {
+        let cipher = XChaCha20Poly1305::new(Key::from_slice(&self.0[..]));
+        let nonce = XChaCha20Poly1305::generate_nonce(&mut OsRng);
+        let header = sealed_header();
+        let ciphertext = cipher
+            .encrypt(
+                &nonce,
+                Payload {
+                    msg: data,
+                    aad: &header,
+                },
+            )
+            .map_err(|_| anyhow!("Failed to encrypt data"))?;
+
+        let mut sealed = Vec::with_capacity(SEALED_HEADER_LEN + NONCE_LEN + ciphertext.len());
+        sealed.extend_from_slice(&header);
+        sealed.extend_from_slice(&nonce);
+        sealed.extend_from_slice(&ciphertext);
+        Ok(sealed)
+    }
+
+    pub fn unseal(&self, sealed: &[u8]) -> Result<Vec<u8>> {
+        if !is_sealed(sealed) || sealed.len() < SEALED_HEADER_LEN + NONCE_LEN {
+            bail!("Data is not encrypted by Handy");
+        }
+        let (header, rest) = sealed.split_at(SEALED_HEADER_LEN);
+        let (nonce, ciphertext) = rest.split_at(NONCE_LEN);
+
+        let cipher = XChaCha20Poly1305::new(Key::from_slice(&self.0[..]));
+        cipher
+            .decrypt(
+                XNonce::from_slice(nonce),
+                Payload {
+                    msg: ciphertext,
+                    aad: header,
+                },
+            )
+            .map_err(|_| anyhow!("Failed to decrypt data: wrong key or corrupted file"))
+    }
+}
+
+fn sealed_header() -> [u8; SEALED_HEADER_LEN] {
+    let mut header = [0u8; SEALED_HEADER_LEN];
+    header[..SEALED_MAGIC.len()].copy_from_slice(SEALED_MAGIC);
+    header[SEALED_MAGIC.len()] = SEALED_VERSION;
+    header
+}
+
+/// Whether data was written by [`EncryptionKey::seal`].
+pub fn is_sealed(data: &[u8]) -> bool {
+    data.starts_with(SEALED_MAGIC)
+}
+
+/// Whether a file starts like a sealed one, reading only its header.
+pub fn is_sealed_file(path: &Path) -> Result<bool> {
+    let mut header = [0u8; SEALED_MAGIC.len()];
+    let mut file = fs::File::open(path)?;
+    match file.read_exact(&mut header) {
+        Ok(()) => Ok(is_sealed(&header)),
+        Err(e) if e.kind() == std::io::ErrorKind::UnexpectedEof => Ok(false),
+        Err(e) => Err(e.into()),
+    }
+}
+
+/// Whether a database file is plain SQLite. Missing and empty files aren't,
+/// so new databases are created encrypted when a key is set.
+pub fn is_plaintext_database(path: &Path) -> Result<bool> {
+    let mut header = [0u8; SQLITE_HEADER.len()];
+    let mut file = match fs::File::open(path) {
+        Ok(file) => file,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(false),
+        Err(e) => return Err(e.into()),
+    };
+    match file.read_exact(&mut header) {
+        Ok(()) => Ok(&header == SQLITE_HEADER),
+        Err(e) if e.kind() == std::io::ErrorKind::UnexpectedEof => Ok(false),
+        Err(e) => Err(e.into()),
+    }
+}
+
+/// Whether the history in `app_data_dir` is encrypted, whether or not it's
+/// unlocked.
+pub fn is_enabled(app_data_dir: &Path) -> bool {
+    app_data_dir.join(ENCRYPTION_CONFIG_FILE).exists()
+}
+
+/// Holds the history key, if encryption is on and the history unlocked.
+pub struct Vault {
+    config_path: PathBuf,
+    keyring_service: String,
+    config: RwLock<Option<VaultConfig>>,
+    key: RwLock<Option<EncryptionKey>>,
+}
+
+impl Vault {
+    /// Reads the encryption settings and, when the key is remembered, takes
+    /// it from the keyring.
+    pub fn open(app_data_dir: &Path, keyring_service: &str) -> Result<Self> {
+        let config_path = app_data_dir.join(ENCRYPTION_CONFIG_FILE);
+        let config: Option<VaultConfig> = match fs::read_to_string(&config_path) {
+            Ok(contents) => Some(serde_json::from_str(&contents)?),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => None,
+            Err(e) => return Err(e.into()),
+        };
+
+        let vault = Self {
+            config_path,
+            keyring_service: keyring_service.to_string(),
+            config: RwLock::new(None),
+            key: RwLock::new(None),
+        };
+
+        if let Some(config) = config {
+            if config.remember_key {
+                match vault.load_remembered_key(&config) {
+                    Ok(Some(key)) => *vault.key.write().unwrap() = Some(key),
+                    Ok(None) => warn!("History key is not in the keyring, history stays locked"),
+                    Err(e) => warn!("Failed to read the history key from the keyring: {}", e),
+                }
+            }
+            *vault.config.write().unwrap() = Some(config);
+        }
+
+        Ok(vault)
+    }
+
+    pub fn status(&self) -> EncryptionStatus {
+        if self.config.read().unwrap().is_none() {
+            EncryptionStatus::Disabled
+        } else if self.key.read().unwrap().is_none() {
+            EncryptionStatus::Locked
+        } else {
+            EncryptionStatus::Unlocked
+        }
+    }
+
+    pub fn state(&self) -> EncryptionState {
+        EncryptionState {
+            status: self.status(),
+            remember_key: self
+                .config
+                .read()
+                .unwrap()
+                .as_ref()
+                .is_some_and(|config| config.remember_key),
+        }
+    }
+
+    pub fn key(&self) -> Option<EncryptionKey> {
+        self.key.read().unwrap().clone()
+    }
+
+    /// Turns encryption on with a key derived from `passphrase`. Encrypting
+    /// the data already on disk is up to the caller.
+    pub fn enable(&self, passphrase: &str, remember_key: bool) -> Result<EncryptionKey> {
+        ensure_supported()?;
+        if self.status() != EncryptionStatus::Disabled {
+            bail!("History encryption is already enabled");
+        }
+        if passphrase.is_empty() {
+            bail!("Passphrase is empty");
+        }
+
+        let mut salt = [0u8; 16];
+        OsRng.fill_bytes(&mut salt);
+        let mut config = VaultConfig {
+            salt: to_hex(&salt),
+            memory_kib: KDF_MEMORY_KIB,
+            iterations: KDF_ITERATIONS,
+            parallelism: KDF_PARALLELISM,
+            check: String::new(),
+            remember_key,
+        };
+        let key = EncryptionKey::derive(passphrase, &config)?;
+        config.check = to_hex(&key.seal(CHECK_PLAINTEXT)?);
+
+        if remember_key {
+            self.remember(&key)?;
+        }
+        self.write_config(&config)?;
+        *self.config.write().unwrap() = Some(config);
+        *self.key.write().unwrap() = Some(key.clone());
+
+        info!("History encryption enabled");
+        Ok(key)
+    }
+
+    pub fn unlock(&self, passphrase: &str) -> Result<EncryptionKey> {
+        ensure_supported()?;
+        let config = self
+            .config
+            .read()
+            .unwrap()
+            .clone()
+            .ok_or_else(|| anyhow!("History encryption is not enabled"))?;
+        let key = EncryptionKey::derive(passphrase, &config)?;
+        verify_key(&key, &config)?;
+
+        *self.key.write().unwrap() = Some(key.clone());
+        debug!("History unlocked");
+        Ok(key)
+    }
+
+    /// Forgets the key until the passphrase is entered again. A key kept in
+    /// the keyring stays there.
+    pub fn lock(&self) {
+        *self.key.write().unwrap() = None;
+        debug!("History locked");
+    }
+
+    /// Turns encryption off once the data on disk has been decrypted.
+    pub fn disable(&self) -> Result<()> {
+        if let Err(e) = self.forget() {
+            warn!("Failed to remove the history key from the keyring: {}", e);
+        }
+        match fs::remove_file(&self.config_path) {
+            Ok(()) => {}
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => {}
+            Err(e) => return Err(e.into()),
+        }
+        *self.config.write().unwrap() = None;
+        *self.key.write().unwrap() = None;
+
+        info!("History encryption disabled");
+        Ok(())
+    }
+
+    /// Keeps the key in the OS keyring, or removes it from there.
+    pub fn set_remember_key(&self, remember_key: bool) -> Result<()> {
+        let mut config = self
+            .config
+            .read()
+            .unwrap()
+            .clone()
+            .ok_or_else(|| anyhow!("History encryption is not enabled"))?;
+
+        if remember_key {
+            let key = self
+                .key()
+                .ok_or_else(|| anyhow!("Unlock the history before remembering its key"))?;
+            self.remember(&key)?;
+        } else {
+            self.forget()?;
+        }
+
+        config.remember_key = remember_key;
+        self.write_config(&config)?;
+        *self.config.write().unwrap() = Some(config);
+        Ok(())
+    }
+
+    /// Encrypts data with the key, or leaves it as is when encryption is off.
+    /// Fails while the history is locked, so nothing is written in plaintext.
+    pub fn seal(&self, data: Vec<u8>) -> Result<Vec<u8>> {
+        match self.key() {
+            Some(key) => key.seal(&data),
+            None if self.status() == EncryptionStatus::Disabled => Ok(data),
+            None => bail!("History is locked"),
+        }
+    }
+
+    /// Decrypts sealed data; anything else is returned as is.
+    pub fn unseal(&self, data: Vec<u8>) -> Result<Vec<u8>> {
+        if !is_sealed(&data) {
+            return Ok(data);
+        }
+        self.key()
+            .ok_or_else(|| anyhow!("History is locked"))?
+            .unseal(&data)
+    }
+
+    fn write_config(&self, config: &VaultConfig) -> Result<()> {
+        let temp_path = self.config_path.with_extension("json.tmp");
+        fs::write(&temp_path, serde_json::to_string_pretty(config)?)?;
+        fs::rename(&temp_path, &self.config_path)?;
+        Ok(())
+    }
+
+    fn keyring_entry(&self) -> Result<keyring::Entry> {
+        Ok(keyring::Entry::new(&self.keyring_service, KEYRING_USER)?)
+    }
+
+    fn remember(&self, key: &EncryptionKey) -> Result<()> {
+        self.keyring_entry()?.set_secret(&key.0[..])?;
+        Ok(())
+    }
+
+    fn forget(&self) -> Result<()> {
+        match self.keyring_entry()?.delete_credential() {
+            Ok(()) | Err(keyring::Error::NoEntry) => Ok(()),
+            Err(e) => Err(e.into()),
+        }
+    }
+
+    fn load_remembered_key(&self, config: &VaultConfig) -> Result<Option<EncryptionKey>> {
+        let secret = match self.keyring_entry()?.get_secret() {
+            Ok(secret) => Zeroizing::new(secret),
+            Err(keyring::Error::NoEntry) => return Ok(None),
+            Err(e) => return Err(e.into()),
+        };
+        let key = EncryptionKey::from_bytes(&secret)?;
+        verify_key(&key, config)?;
+        Ok(Some(key))
+    }
+}
+
+fn ensure_supported() -> Result<()> {
+    if !SUPPORTED {
+        bail!("This build does not support history encryption");
+    }
+    Ok(())
+}
+
+fn verify_key(key: &EncryptionKey, config: &VaultConfig) -> Result<()> {
+    match key.unseal(&from_hex(&config.check)?) {
+        Ok(check) if check == CHECK_PLAINTEXT => Ok(()),
+        _ => bail!("Wrong passphrase"),
+    }
+}
+
+fn to_hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|byte| format!("{byte:02x}")).collect()
+}
+
+fn from_hex(hex: &str) -> Result<Vec<u8>> {
+    if hex.len() % 2 != 0 || !hex.is_ascii() {
+        bail!("Invalid hex string");
+    }
+    (0..hex.len())
+        .step_by(2)
+        .map(|i| u8::from_str_radix(&hex[i..i + 2], 16).map_err(|_| anyhow!("Invalid hex string")))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn vault(dir: &Path) -> Vault {
+        Vault::open(dir, "com.pais.chatterfox.tests").unwrap()
+    }
+
+    #[test]
+    fn sealed_data_round_trips_only_with_the_key() {
+        let key = EncryptionKey::from_bytes(&[7; 32]).unwrap();
+        let sealed = key.seal(b"patient notes").unwrap();
+        assert!(is_sealed(&sealed));
+        assert_eq!(key.unseal(&sealed).unwrap(), b"patient notes");
+
+        let other = EncryptionKey::from_bytes(&[8; 32]).unwrap();
+        assert!(other.unseal(&sealed).is_err());
+
+        let mut tampered = sealed.clone();
+        *tampered.last_mut().unwrap() ^= 1;
+        assert!(key.unseal(&tampered).is_err());
+    }
+
+    #[cfg(feature = "encryption")]
+    #[test]
+    fn passphrase_unlocks_after_a_restart() {
+        let dir = tempfile::tempdir().unwrap();
+        let first = vault(dir.path());
+        assert_eq!(first.status(), EncryptionStatus::Disabled);
+        assert_eq!(first.seal(b"plain".to_vec()).unwrap(), b"plain");
+
+        first.enable("correct horse", false).unwrap();
+        let sealed = first.seal(b"secret".to_vec()).unwrap();
+        assert!(is_sealed(&sealed));
+
+        let restarted = vault(dir.path());
+        assert_eq!(restarted.status(), EncryptionStatus::Locked);
+        assert!(restarted.seal(b"secret".to_vec()).is_err());
+        assert!(restarted.unseal(sealed.clone()).is_err());
+        assert!(restarted.unlock("wrong horse").is_err());
+
+        restarted.unlock("correct horse").unwrap();
+        assert_eq!(restarted.status(), EncryptionStatus::Unlocked);
+        assert_eq!(restarted.unseal(sealed).unwrap(), b"secret");
+        // Plaintext left from before encryption still reads
+        assert_eq!(restarted.unseal(b"plain".to_vec()).unwrap(), b"plain");
+    }
+
+    #[cfg(not(feature = "encryption"))]
+    #[test]
+    fn builds_without_sqlcipher_refuse_encryption() {
+        let dir = tempfile::tempdir().unwrap();
+        let vault = vault(dir.path());
+        assert!(vault.enable("correct horse", false).is_err());
+        assert_eq!(vault.status(), EncryptionStatus::Disabled);
+    }
+
+    #[test]
+    fn database_headers_are_told_apart() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("history.db");
+        assert!(!is_plaintext_database(&path).unwrap());
+
+        fs::write(&path, b"SQLite format 3\0rest of the page").unwrap();
+        assert!(is_plaintext_database(&path).unwrap());
+
+        fs::write(&path, [0x5a; 64]).unwrap();
+        assert!(!is_plaintext_database(&path).unwrap());
+    }
+
+    #[test]
+    fn hex_round_trips() {
+        let bytes = [0x00, 0x0f, 0xa5, 0xff];
+        assert_eq!(to_hex(&bytes), "000fa5ff");
+        assert_eq!(from_hex("000fa5ff").unwrap(), bytes);
+        assert!(from_hex("abc").is_err());
+    }
+}
@@ -1,30 +1,6 @@
 #[cfg(target_os = "macos")]
 use std::process::Command;
 
-/// Checks if the MacBook is in clamshell mode (lid closed with external display)
-///
-/// This queries the macOS IORegistry for the AppleClamshellState key.
-/// Returns true if the lid is closed, false if open.
-#[cfg(target_os = "macos")]
-pub fn is_clamshell() -> Result<bool, String> {
-    let output = Command::new("ioreg")
-        .args(["-r", "-k", "AppleClamshellState", "-d", "4"])
-        .output()
-        .map_err(|e| format!("Failed to execute ioreg: {}", e))?;
-
-    if !output.status.success() {
-        return Err(format!(
-            "ioreg command failed with status: {}",
-            output.status
-        ));
-    }
-
-    let stdout = String::from_utf8_lossy(&output.stdout);
-
-    // Look for "AppleClamshellState" = Yes in the output
-    Ok(stdout.contains("\"AppleClamshellState\" = Yes"))
-}
-
 /// Checks if the Mac is a laptop by detecting battery presence
 ///
 /// This uses pmset to check for battery information.
@@ -45,13 +21,6 @@ pub fn is_laptop() -> Result<bool, String> {
     Ok(stdout.contains("InternalBattery"))
 }
 
-/// Stub implementation for non-macOS platforms
-/// Always returns false since clamshell mode is macOS-specific
-#[cfg(not(target_os = "macos"))]
-pub fn is_clamshell() -> Result<bool, String> {
-    Ok(false)
-}
-
 /// Stub implementation for non-macOS platforms
 /// Always returns false since laptop detection is macOS-specific
 #[cfg(not(target_os = "macos"))]
@@ -65,15 +34,6 @@ pub fn is_laptop() -> Result<bool, String> {
 mod tests {
     use super::*;
 
-    #[test]
-    #[cfg(target_os = "macos")]
-    fn test_clamshell_check() {
-        // This will run on macOS and should not panic
-        let result = is_clamshell();
-        assert!(result.is_ok());
-        let _ = result.unwrap();
-    }
-
     #[test]
     #[cfg(target_os = "macos")]
     fn test_is_laptop() {
@@ -5,10 +5,13 @@ mod audio_feedback;
 pub mod audio_toolkit;
 mod clipboard;
 mod commands;
+mod encryption;
 mod helpers;
 mod input;
 mod managers;
+mod output_control;
 mod overlay;
+mod recording_protocol;
 mod settings;
 mod shortcut;
 mod signal_handle;
@@ -22,6 +25,7 @@ use env_filter::Builder as EnvFilterBuilder;
 use managers::audio::AudioRecordingManager;
 use managers::history::HistoryManager;
 use managers::model::ModelManager;
+use managers::retranscription::RetranscriptionManager;
 use managers::transcription::TranscriptionManager;
 #[cfg(unix)]
 use signal_hook::consts::SIGUSR2;
@@ -114,6 +118,9 @@ fn initialize_core_logic(app_handle: &AppHandle) {
     // after onboarding completes. This avoids triggering permission dialogs
     // on macOS before the user is ready.
 
+    // Undo muting, ducking or paused media left behind by a crash
+    output_control::restore_output(app_handle);
+
     // Initialize the managers
     let recording_manager = Arc::new(
         AudioRecordingManager::new(app_handle).expect("Failed to initialize recording manager"),
@@ -126,12 +133,18 @@ fn initialize_core_logic(app_handle: &AppHandle) {
     );
     let history_manager =
         Arc::new(HistoryManager::new(app_handle).expect("Failed to initialize history manager"));
+    let retranscription_manager = Arc::new(RetranscriptionManager::new(
+        app_handle,
+        history_manager.clone(),
+        transcription_manager.clone(),
+    ));
 
     // Add managers to Tauri's managed state
     app_handle.manage(recording_manager.clone());
     app_handle.manage(model_manager.clone());
     app_handle.manage(transcription_manager.clone());
     app_handle.manage(history_manager.clone());
+    app_handle.manage(retranscription_manager.clone());
 
     // Note: Shortcuts are NOT initialized here.
     // The frontend is responsible for calling the `initialize_shortcuts` command
@@ -184,6 +197,12 @@ fn initialize_core_logic(app_handle: &AppHandle) {
             "copy_last_transcript" => {
                 tray::copy_last_transcript(app);
             }
+            "pause" => {
+                utils::set_recording_paused(app, true);
+            }
+            "resume" => {
+                utils::set_recording_paused(app, false);
+            }
             "cancel" => {
                 use crate::utils::cancel_current_operation;
 
@@ -253,9 +272,30 @@ pub fn run() {
         shortcut::change_paste_method_setting,
         shortcut::change_clipboard_handling_setting,
         shortcut::update_custom_words,
+        shortcut::update_word_replacements,
         shortcut::suspend_binding,
         shortcut::resume_binding,
         shortcut::change_mute_while_recording_setting,
+        shortcut::change_recording_output_action_setting,
+        shortcut::change_duck_volume_setting,
+        commands::audio::change_high_pass_filter_setting,
+        commands::audio::change_noise_reduction_setting,
+        commands::audio::change_auto_gain_setting,
+        commands::audio::get_available_sound_themes,
+        commands::audio::set_sound_theme_folder,
+        commands::audio::change_max_recording_duration_setting,
+        commands::audio::change_spill_after_setting,
+        commands::audio::set_microphone_gain,
+        commands::audio::get_microphone_gain,
+        commands::audio::get_input_level,
+        commands::audio::start_input_level_monitor,
+        commands::audio::stop_input_level_monitor,
+        commands::audio::get_available_monitor_sources,
+        commands::audio::set_audio_source,
+        commands::audio::get_audio_source,
+        commands::audio::get_available_audio_hosts,
+        commands::audio::get_audio_host,
+        commands::audio::set_audio_host,
         shortcut::change_append_trailing_space_setting,
         shortcut::change_app_language_setting,
         shortcut::change_update_checks_setting,
@@ -263,6 +303,7 @@ pub fn run() {
         shortcut::handy_keys::stop_handy_keys_recording,
         trigger_update_check,
         commands::cancel_operation,
+        commands::resume_recording,
         commands::get_app_dir_path,
         commands::get_app_settings,
         commands::get_default_settings,
@@ -295,18 +336,57 @@ pub fn run() {
         commands::audio::get_selected_output_device,
         commands::audio::play_test_sound,
         commands::audio::check_custom_sounds,
-        commands::audio::set_clamshell_microphone,
-        commands::audio::get_clamshell_microphone,
+        commands::audio::set_microphone_priority,
+        commands::audio::get_microphone_priority,
+        commands::audio::get_active_microphone,
+        commands::audio::get_microphone_channel_count,
+        commands::audio::set_microphone_channel,
+        commands::audio::get_microphone_channel,
         commands::audio::is_recording,
         commands::transcription::set_model_unload_timeout,
         commands::transcription::get_model_load_status,
         commands::transcription::unload_model_manually,
         commands::history::get_history_entries,
+        commands::history::get_history_page,
+        commands::history::get_history_entries_by_ids,
+        commands::history::search_history,
         commands::history::toggle_history_entry_saved,
+        commands::history::update_history_entry_text,
+        commands::history::revert_history_entry_text,
+        commands::history::get_correction_suggestions,
         commands::history::get_audio_file_path,
         commands::history::delete_history_entry,
         commands::history::update_history_limit,
         commands::history::update_recording_retention_period,
+        commands::history::update_audio_retention_days,
+        commands::history::update_recordings_quota,
+        commands::history::update_recording_format,
+        commands::history::convert_recordings,
+        commands::history::update_crash_safe_recording,
+        commands::history::transcribe_recovered_entry,
+        commands::history::retranscribe_history_entries,
+        commands::history::cancel_retranscription,
+        commands::history::get_alternative_transcripts,
+        commands::history::get_usage_stats,
+        commands::history::get_history_tags,
+        commands::history::add_history_entry_tag,
+        commands::history::remove_history_entry_tag,
+        commands::history::update_history_tag_retention,
+        commands::history::delete_history_tag,
+        commands::history::update_history_entry_note,
+        commands::history::get_history_collections,
+        commands::history::create_history_collection,
+        commands::history::delete_history_collection,
+        commands::history::add_to_history_collection,
+        commands::history::remove_from_history_collection,
+        commands::history::get_history_encryption,
+        commands::history::enable_history_encryption,
+        commands::history::disable_history_encryption,
+        commands::history::unlock_history,
+        commands::history::lock_history,
+        commands::history::update_history_key_remembered,
+        commands::history::export_history,
+        commands::history::import_history,
         helpers::clamshell::is_laptop,
     ]);
 
@@ -371,6 +451,10 @@ pub fn run() {
             Some(vec![]),
         ))
         .manage(Mutex::new(ShortcutToggleStates::default()))
+        .register_asynchronous_uri_scheme_protocol(
+            recording_protocol::RECORDING_SCHEME,
+            recording_protocol::handle_request,
+        )
         .setup(move |app| {
             let settings = get_settings(&app.handle());
             let tauri_log_level: tauri_plugin_log::LogLevel = settings.log_level.into();
@@ -1,102 +1,44 @@
-use crate::audio_toolkit::{list_input_devices, vad::SmoothedVad, AudioRecorder, SileroVad};
-use crate::helpers::clamshell;
-use crate::settings::{get_settings, AppSettings};
+use crate::audio_toolkit::audio::{
+    device_name_matches, list_monitor_sources, MonitorSource, DEFAULT_MONITOR_SOURCE,
+};
+use crate::audio_toolkit::{
+    host_id_from_name, list_input_devices, set_preferred_host, vad::SmoothedVad, AudioRecorder,
+    InputLevel, PreprocessConfig, RecordingLevels, RecordingLimitEvent, SileroVad,
+};
+use crate::encryption;
+use crate::managers::history::{discard_journal, journal_dir};
+use crate::output_control;
+use crate::settings::{get_settings, AppSettings, CaptureSource};
 use crate::utils;
-use log::{debug, error, info};
+use log::{debug, error, info, warn};
+use serde::Serialize;
+use specta::Type;
+use std::path::PathBuf;
 use std::sync::{Arc, Mutex};
-use std::time::Instant;
-use tauri::Manager;
-
-fn set_mute(mute: bool) {
-    // Expected behavior:
-    // - Windows: works on most systems using standard audio drivers.
-    // - Linux: works on many systems (PipeWire, PulseAudio, ALSA),
-    //   but some distros may lack the tools used.
-    // - macOS: works on most standard setups via AppleScript.
-    // If unsupported, fails silently.
-
-    #[cfg(target_os = "windows")]
-    {
-        unsafe {
-            use windows::Win32::{
-                Media::Audio::{
-                    eMultimedia, eRender, Endpoints::IAudioEndpointVolume, IMMDeviceEnumerator,
-                    MMDeviceEnumerator,
-                },
-                System::Com::{CoCreateInstance, CoInitializeEx, CLSCTX_ALL, COINIT_MULTITHREADED},
-            };
-
-            macro_rules! unwrap_or_return {
-                ($expr:expr) => {
-                    match $expr {
-                        Ok(val) => val,
-                        Err(_) => return,
-                    }
-                };
-            }
-
-            // Initialize the COM library for this thread.
-            // If already initialized (e.g., by another library like Tauri), this does nothing.
-            let _ = CoInitializeEx(None, COINIT_MULTITHREADED);
-
-            let all_devices: IMMDeviceEnumerator =
-                unwrap_or_return!(CoCreateInstance(&MMDeviceEnumerator, None, CLSCTX_ALL));
-            let default_device =
-                unwrap_or_return!(all_devices.GetDefaultAudioEndpoint(eRender, eMultimedia));
-            let volume_interface = unwrap_or_return!(
-                default_device.Activate::<IAudioEndpointVolume>(CLSCTX_ALL, None)
-            );
-
-            let _ = volume_interface.SetMute(mute, std::ptr::null());
-        }
-    }
-
-    #[cfg(target_os = "linux")]
-    {
-        use std::process::Command;
-
-        let mute_val = if mute { "1" } else { "0" };
-        let amixer_state = if mute { "mute" } else { "unmute" };
+use std::thread;
+use std::time::{Duration, Instant};
+use tauri::{Emitter, Manager};
 
-        // Try multiple backends to increase compatibility
-        // 1. PipeWire (wpctl)
-        if Command::new("wpctl")
-            .args(["set-mute", "@DEFAULT_AUDIO_SINK@", mute_val])
-            .output()
-            .map(|o| o.status.success())
-            .unwrap_or(false)
-        {
-            return;
-        }
+const WHISPER_SAMPLE_RATE: usize = 16000;
 
-        // 2. PulseAudio (pactl)
-        if Command::new("pactl")
-            .args(["set-sink-mute", "@DEFAULT_SINK@", mute_val])
-            .output()
-            .map(|o| o.status.success())
-            .unwrap_or(false)
-        {
-            return;
-        }
+/// Directory in app data that long recordings spill to.
+const SPILL_DIR: &str = "spill";
 
-        // 3. ALSA (amixer)
-        let _ = Command::new("amixer")
-            .args(["set", "Master", amixer_state])
-            .output();
-    }
+/// How often the device watcher checks whether a preferred microphone is back.
+const DEVICE_WATCH_INTERVAL: Duration = Duration::from_secs(3);
 
-    #[cfg(target_os = "macos")]
-    {
-        use std::process::Command;
-        let script = format!(
-            "set volume output muted {}",
-            if mute { "true" } else { "false" }
-        );
-        let _ = Command::new("osascript").args(["-e", &script]).output();
-    }
-}
+/// Stream errors in a row after which the stream is no longer reopened.
+const MAX_STREAM_REOPENS: u32 = 5;
+/// Wait before the first reopen, doubled for each further error in a row.
+const STREAM_REOPEN_BACKOFF: Duration = Duration::from_millis(250);
+/// A stream error this long after the previous one starts a new count.
+const STREAM_ERROR_RESET: Duration = Duration::from_secs(30);
 
-const WHISPER_SAMPLE_RATE: usize = 16000;
+/// Recordings whose speech frames average below this RMS level get a "too
+/// quiet" warning. Pauses are left out, so they don't drag the level down.
+const TOO_QUIET_DBFS: f32 = -40.0;
+/// Fraction of clipped samples above which a recording gets a clipping warning.
+const CLIPPING_RATIO: f32 = 0.001;
 
 /* ──────────────────────────────────────────────────────────────── */
 
@@ -112,11 +54,101 @@ pub enum MicrophoneMode {
     OnDemand,
 }
 
+/// Payload of the `microphone-changed` event.
+#[derive(Clone, Debug, Serialize)]
+pub struct MicrophoneChangedEvent {
+    /// Name of the device now in use, `None` for the system default.
+    pub device_name: Option<String>,
+    /// True when the most preferred microphone is unavailable.
+    pub is_fallback: bool,
+}
+
+/// Payload of the `input-level` event and `get_input_level` command.
+#[derive(Clone, Debug, Serialize, Type)]
+pub struct InputLevelEvent {
+    pub rms_dbfs: f32,
+    pub peak_dbfs: f32,
+    pub clipped_samples: usize,
+}
+
+impl From<InputLevel> for InputLevelEvent {
+    fn from(level: InputLevel) -> Self {
+        Self {
+            rms_dbfs: level.rms_dbfs,
+            peak_dbfs: level.peak_dbfs,
+            clipped_samples: level.clipped_samples,
+        }
+    }
+}
+
+#[derive(Clone, Debug, Serialize, Type)]
+#[serde(rename_all = "snake_case")]
+pub enum LevelWarningKind {
+    TooQuiet,
+    Clipping,
+}
+
+/// Payload of the `recording-level-warning` event, sent after a recording.
+#[derive(Clone, Debug, Serialize, Type)]
+pub struct RecordingLevelWarning {
+    pub kind: LevelWarningKind,
+    /// RMS level of the speech, or of all input when no speech was detected.
+    pub rms_dbfs: f32,
+    pub peak_dbfs: f32,
+    pub clipped_samples: usize,
+}
+
+/// Lengths of the last finished recording.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct RecordingDurations {
+    /// Audio captured while recording, excluding paused time.
+    pub audio: Duration,
+    /// What was left after voice activity detection.
+    pub speech: Duration,
+}
+
+/// Recent stream errors, to stop reopening a stream that keeps failing.
+#[derive(Default)]
+struct StreamErrors {
+    in_a_row: u32,
+    last: Option<Instant>,
+}
+
+/// The device picked for the next stream open.
+struct MicrophoneChoice {
+    device: Option<cpal::Device>,
+    name: Option<String>,
+    is_fallback: bool,
+}
+
 /* ──────────────────────────────────────────────────────────────── */
 
+fn default_input_device_name() -> Option<String> {
+    list_input_devices()
+        .ok()?
+        .into_iter()
+        .find(|d| d.is_default)
+        .map(|d| d.name)
+}
+
+fn spill_dir(app: &tauri::AppHandle) -> Result<PathBuf, anyhow::Error> {
+    Ok(app.path().app_data_dir()?.join(SPILL_DIR))
+}
+
+/// Journals and spill files hold raw audio, so neither is written while the
+/// history is encrypted.
+fn history_encrypted(app: &tauri::AppHandle) -> bool {
+    app.path()
+        .app_data_dir()
+        .is_ok_and(|dir| encryption::is_enabled(&dir))
+}
+
 fn create_audio_recorder(
     vad_path: &str,
     app_handle: &tauri::AppHandle,
+    input_level: Arc<Mutex<Option<InputLevel>>>,
+    is_recording: Arc<Mutex<bool>>,
+    monitoring: Arc<Mutex<bool>>,
 ) -> Result<AudioRecorder, anyhow::Error> {
     let silero = SileroVad::new(vad_path, 0.3)
         .map_err(|e| anyhow::anyhow!("Failed to create SileroVad: {}", e))?;
@@ -132,6 +164,48 @@ fn create_audio_recorder(
             move |levels| {
                 utils::emit_levels(&app_handle, &levels);
             }
+        })
+        .with_meter_callback({
+            let app_handle = app_handle.clone();
+            move |level| {
+                *input_level.lock().unwrap() = Some(level);
+                // An always-on microphone meters while idle too; only a
+                // recording or the settings meter needs the events
+                if *is_recording.lock().unwrap() || *monitoring.lock().unwrap() {
+                    let _ = app_handle.emit("input-level", InputLevelEvent::from(level));
+                }
+            }
+        })
+        .with_limit_callback({
+            let app_handle = app_handle.clone();
+            move |event| {
+                // Runs on the audio thread, and stopping waits on that thread
+                let app_handle = app_handle.clone();
+                thread::spawn(move || match event {
+                    RecordingLimitEvent::Approaching { remaining } => {
+                        info!("Recording stops in {:?}", remaining);
+                        utils::emit_recording_limit_approaching(&app_handle, remaining.as_secs());
+                        utils::show_recording_limit_warning(&app_handle);
+                    }
+                    RecordingLimitEvent::Reached => {
+                        info!("Maximum recording duration reached, stopping");
+                        let _ = app_handle.emit("recording-limit-reached", ());
+                        utils::stop_current_recording(&app_handle);
+                    }
+                });
+            }
+        })
+        .with_error_callback({
+            let app_handle = app_handle.clone();
+            move |err| {
+                // Runs on the audio thread, so recover from a separate thread.
+                let app_handle = app_handle.clone();
+                thread::spawn(move || {
+                    if let Some(rm) = app_handle.try_state::<Arc<AudioRecordingManager>>() {
+                        rm.handle_stream_error(err);
+                    }
+                });
+            }
         });
 
     Ok(recorder)
@@ -148,7 +222,22 @@ pub struct AudioRecordingManager {
     recorder: Arc<Mutex<Option<AudioRecorder>>>,
     is_open: Arc<Mutex<bool>>,
     is_recording: Arc<Mutex<bool>>,
+    is_paused: Arc<Mutex<bool>>,
     did_mute: Arc<Mutex<bool>>,
+
+    active_microphone: Arc<Mutex<Option<String>>>,
+    /// Device the open stream's per-device settings were looked up under.
+    settings_device: Arc<Mutex<Option<String>>>,
+    is_fallback: Arc<Mutex<bool>>,
+    recovering: Arc<Mutex<bool>>,
+    stream_errors: Arc<Mutex<StreamErrors>>,
+    restart_pending: Arc<Mutex<bool>>,
+    salvaged_samples: Arc<Mutex<Vec<f32>>>,
+    durations: Arc<Mutex<RecordingDurations>>,
+    journal_path: Arc<Mutex<Option<PathBuf>>>,
+
+    input_level: Arc<Mutex<Option<InputLevel>>>,
+    monitoring: Arc<Mutex<bool>>,
 }
 
 impl AudioRecordingManager {
@@ -156,6 +245,12 @@ impl AudioRecordingManager {
 
     pub fn new(app: &tauri::AppHandle) -> Result<Self, anyhow::Error> {
         let settings = get_settings(app);
+        if let Some(name) = &settings.audio_host {
+            match host_id_from_name(name) {
+                Some(id) => set_preferred_host(Some(id)),
+                None => warn!("Audio host {} is not available, using default", name),
+            }
+        }
         let mode = if settings.always_on_microphone {
             MicrophoneMode::AlwaysOn
         } else {
@@ -170,65 +265,251 @@ impl AudioRecordingManager {
             recorder: Arc::new(Mutex::new(None)),
             is_open: Arc::new(Mutex::new(false)),
             is_recording: Arc::new(Mutex::new(false)),
+            is_paused: Arc::new(Mutex::new(false)),
             did_mute: Arc::new(Mutex::new(false)),
+
+            active_microphone: Arc::new(Mutex::new(None)),
+            settings_device: Arc::new(Mutex::new(None)),
+            is_fallback: Arc::new(Mutex::new(false)),
+            recovering: Arc::new(Mutex::new(false)),
+            stream_errors: Arc::new(Mutex::new(StreamErrors::default())),
+            restart_pending: Arc::new(Mutex::new(false)),
+            salvaged_samples: Arc::new(Mutex::new(Vec::new())),
+            durations: Arc::new(Mutex::new(RecordingDurations::default())),
+            journal_path: Arc::new(Mutex::new(None)),
+
+            input_level: Arc::new(Mutex::new(None)),
+            monitoring: Arc::new(Mutex::new(false)),
         };
 
+        // Spill files only outlive their recording when the app crashed
+        if let Ok(dir) = spill_dir(app) {
+            if dir.exists() {
+                if let Err(e) = std::fs::remove_dir_all(&dir) {
+                    warn!("Failed to clear stale spill files in {:?}: {e}", dir);
+                }
+            }
+        }
+
         // Always-on?  Open immediately.
         if matches!(mode, MicrophoneMode::AlwaysOn) {
             manager.start_microphone_stream()?;
         }
 
+        // Watch for preferred devices coming back while we run on a fallback
+        {
+            let manager_cloned = manager.clone();
+            thread::spawn(move || loop {
+                thread::sleep(DEVICE_WATCH_INTERVAL);
+                manager_cloned.check_preferred_microphone();
+            });
+        }
+
         Ok(manager)
     }
 
     /* ---------- helper methods --------------------------------------------- */
 
-    fn get_effective_microphone_device(&self, settings: &AppSettings) -> Option<cpal::Device> {
-        // Check if we're in clamshell mode and have a clamshell microphone configured
-        let use_clamshell_mic = if let Ok(is_clamshell) = clamshell::is_clamshell() {
-            is_clamshell && settings.clamshell_microphone.is_some()
-        } else {
-            false
+    /// Picks the first entry of `microphone_priority` that matches a present
+    /// device, falling back to the system default.
+    fn get_effective_microphone_device(&self, settings: &AppSettings) -> MicrophoneChoice {
+        let default_choice = |is_fallback| MicrophoneChoice {
+            device: None,
+            name: None,
+            is_fallback,
         };
 
-        let device_name = if use_clamshell_mic {
-            settings.clamshell_microphone.as_ref().unwrap()
-        } else {
-            settings.selected_microphone.as_ref()?
-        };
+        if settings.microphone_priority.is_empty() {
+            return default_choice(false);
+        }
 
-        // Find the device by name
         match list_input_devices() {
-            Ok(devices) => devices
-                .into_iter()
-                .find(|d| d.name == *device_name)
-                .map(|d| d.device),
+            Ok(devices) => {
+                for (position, pattern) in settings.microphone_priority.iter().enumerate() {
+                    if let Some(found) = devices
+                        .iter()
+                        .find(|d| device_name_matches(pattern, &d.name))
+                    {
+                        return MicrophoneChoice {
+                            device: Some(found.device.clone()),
+                            name: Some(found.name.clone()),
+                            is_fallback: position > 0,
+                        };
+                    }
+                }
+                debug!("No preferred microphone present, using default");
+                default_choice(true)
+            }
             Err(e) => {
                 debug!("Failed to list devices, using default: {}", e);
-                None
+                default_choice(true)
             }
         }
     }
 
+    /// Monitor sources that can be captured instead of, or mixed into, the
+    /// microphone.
+    pub fn monitor_sources(&self) -> Vec<MonitorSource> {
+        list_monitor_sources()
+    }
+
+    /// Name of the microphone in use, or the one the next stream would open
+    /// when the microphone is currently closed.
+    pub fn active_microphone(&self) -> Option<String> {
+        let name = if *self.is_open.lock().unwrap() {
+            self.active_microphone.lock().unwrap().clone()
+        } else {
+            let settings = get_settings(&self.app_handle);
+            self.get_effective_microphone_device(&settings).name
+        };
+
+        // Resolve the system default to a concrete device name
+        name.or_else(default_input_device_name)
+    }
+
+    /// Periodic check run by the device watcher. Switches back to a preferred
+    /// microphone once it reappears, and reopens an always-on stream that was
+    /// lost entirely. Never interrupts a recording in progress.
+    fn check_preferred_microphone(&self) {
+        if *self.recovering.lock().unwrap() || self.is_recording() {
+            return;
+        }
+
+        let is_open = *self.is_open.lock().unwrap();
+        if !is_open {
+            if matches!(*self.mode.lock().unwrap(), MicrophoneMode::AlwaysOn) {
+                if let Err(e) = self.start_microphone_stream() {
+                    debug!("Microphone still unavailable: {}", e);
+                }
+            }
+            return;
+        }
+
+        if !*self.is_fallback.lock().unwrap() {
+            return;
+        }
+
+        let settings = get_settings(&self.app_handle);
+        let choice = self.get_effective_microphone_device(&settings);
+        if choice.name != *self.active_microphone.lock().unwrap() {
+            info!("Preferred microphone available again, switching");
+            if let Err(e) = self.update_selected_device() {
+                warn!("Failed to switch back to preferred microphone: {}", e);
+            }
+        }
+    }
+
+    /// Counts a stream error, returning how many there have been in a row.
+    fn count_stream_error(&self) -> u32 {
+        let mut errors = self.stream_errors.lock().unwrap();
+        if errors
+            .last
+            .is_some_and(|last| last.elapsed() > STREAM_ERROR_RESET)
+        {
+            errors.in_a_row = 0;
+        }
+        errors.in_a_row += 1;
+        errors.last = Some(Instant::now());
+        errors.in_a_row
+    }
+
+    /// Called when the input stream reports an error, typically because the
+    /// device was unplugged. Reopens the stream on the next available device
+    /// and continues an active recording there; if no device can be opened
+    /// the recording is cancelled so the app always returns to idle. Reopens
+    /// back off and stop after `MAX_STREAM_REOPENS` errors in a row, so a
+    /// source that fails as soon as it opens doesn't spin.
+    fn handle_stream_error(&self, err: String) {
+        {
+            let mut recovering = self.recovering.lock().unwrap();
+            if *recovering {
+                return;
+            }
+            *recovering = true;
+        }
+
+        if *self.is_open.lock().unwrap() {
+            let in_a_row = self.count_stream_error();
+            warn!("Microphone stream error #{in_a_row}, reopening: {}", err);
+
+            let was_recording = *self.is_recording.lock().unwrap();
+            let was_muted = *self.did_mute.lock().unwrap();
+
+            // Keep whatever was captured before the device went away
+            if was_recording {
+                if let Some(rec) = self.recorder.lock().unwrap().as_ref() {
+                    match rec.stop() {
+                        Ok(buf) => self.salvaged_samples.lock().unwrap().extend(buf),
+                        Err(e) => error!("Failed to salvage samples: {e}"),
+                    }
+                    self.durations.lock().unwrap().audio += rec.captured_duration();
+                }
+                *self.is_recording.lock().unwrap() = false;
+            }
+
+            self.stop_microphone_stream();
+
+            let reopened = if in_a_row > MAX_STREAM_REOPENS {
+                Err(anyhow::anyhow!("stream failed {in_a_row} times in a row"))
+            } else {
+                thread::sleep(STREAM_REOPEN_BACKOFF * 2u32.pow(in_a_row - 1));
+                self.start_microphone_stream()
+            };
+
+            match reopened {
+                Ok(()) => {
+                    // The user may have stopped or cancelled in the meantime
+                    let state = self.state.lock().unwrap();
+                    if was_recording && matches!(*state, RecordingState::Recording { .. }) {
+                        if let Some(rec) = self.recorder.lock().unwrap().as_ref() {
+                            if self.start_recorder(rec).is_ok() {
+                                *self.is_recording.lock().unwrap() = true;
+                                if *self.is_paused.lock().unwrap() {
+                                    let _ = rec.pause();
+                                }
+                            }
+                        }
+                        drop(state);
+                        if was_muted {
+                            self.apply_mute();
+                        }
+                    }
+                }
+                Err(e) => {
+                    error!("Failed to reopen microphone after stream error: {e}");
+                    if was_recording {
+                        utils::fail_current_operation(
+                            &self.app_handle,
+                            format!("Microphone lost: {e}"),
+                        );
+                    }
+                }
+            }
+        }
+
+        *self.recovering.lock().unwrap() = false;
+    }
+
     /* ---------- microphone life-cycle -------------------------------------- */
 
-    /// Applies mute if mute_while_recording is enabled and stream is open
+    /// Quiets other audio, as configured by `recording_output_action`, if
+    /// mute_while_recording is enabled and stream is open
     pub fn apply_mute(&self) {
         let settings = get_settings(&self.app_handle);
         let mut did_mute_guard = self.did_mute.lock().unwrap();
 
         if settings.mute_while_recording && *self.is_open.lock().unwrap() {
-            set_mute(true);
+            output_control::quiet_output(&self.app_handle, settings.recording_output_action);
             *did_mute_guard = true;
             debug!("Mute applied");
         }
     }
 
-    /// Removes mute if it was applied
+    /// Restores other audio if it was quieted
     pub fn remove_mute(&self) {
         let mut did_mute_guard = self.did_mute.lock().unwrap();
         if *did_mute_guard {
-            set_mute(false);
+            output_control::restore_output(&self.app_handle);
             *did_mute_guard = false;
             debug!("Mute removed");
         }
@@ -261,19 +542,96 @@ impl AudioRecordingManager {
             *recorder_opt = Some(create_audio_recorder(
                 vad_path.to_str().unwrap(),
                 &self.app_handle,
+                self.input_level.clone(),
+                self.is_recording.clone(),
+                self.monitoring.clone(),
             )?);
         }
 
-        // Get the selected device from settings, considering clamshell mode
+        // Get the first available device from the priority list
         let settings = get_settings(&self.app_handle);
-        let selected_device = self.get_effective_microphone_device(&settings);
+        let choice = self.get_effective_microphone_device(&settings);
 
         if let Some(rec) = recorder_opt.as_mut() {
-            rec.open(selected_device)
-                .map_err(|e| anyhow::anyhow!("Failed to open recorder: {}", e))?;
+            let monitor_name = settings
+                .monitor_source
+                .clone()
+                .unwrap_or_else(|| DEFAULT_MONITOR_SOURCE.to_string());
+
+            // Per-device settings follow whatever is captured as the main input
+            let device_name = match settings.audio_source {
+                CaptureSource::SystemAudio => Some(monitor_name.clone()),
+                _ => choice.name.clone().or_else(default_input_device_name),
+            };
+            let channel_selection = device_name
+                .as_ref()
+                .and_then(|name| settings.microphone_channels.get(name).copied())
+                .unwrap_or_default();
+            rec.set_channel_mix(channel_selection.into());
+            rec.set_gain(
+                device_name
+                    .as_ref()
+                    .and_then(|name| settings.microphone_gains.get(name).copied())
+                    .unwrap_or(1.0),
+            );
+            rec.set_preprocess_config(PreprocessConfig {
+                high_pass: settings.high_pass_filter,
+                noise_reduction: settings.noise_reduction,
+                auto_gain: settings.auto_gain,
+            });
+            rec.set_max_duration(
+                settings
+                    .max_recording_duration_secs
+                    .map(|secs| Duration::from_secs(secs as u64)),
+            );
+            rec.set_spill_after(if history_encrypted(&self.app_handle) {
+                None
+            } else {
+                settings
+                    .spill_after_secs
+                    .map(|secs| Duration::from_secs(secs as u64))
+            });
+            match spill_dir(&self.app_handle) {
+                Ok(dir) => rec.set_spill_dir(dir),
+                Err(e) => warn!("Spilling to the temp dir, app data unavailable: {e}"),
+            }
+            let opened = match settings.audio_source {
+                CaptureSource::Microphone => {
+                    rec.set_monitor_source(None);
+                    rec.open(choice.device)
+                }
+                CaptureSource::MicrophoneAndSystemAudio => {
+                    rec.set_monitor_source(Some(monitor_name));
+                    rec.open(choice.device)
+                }
+                CaptureSource::SystemAudio => {
+                    rec.set_monitor_source(None);
+                    rec.open_monitor(&monitor_name)
+                }
+            };
+            opened.map_err(|e| anyhow::anyhow!("Failed to open recorder: {}", e))?;
+            *self.settings_device.lock().unwrap() = device_name;
         }
 
         *open_flag = true;
+        *self.is_fallback.lock().unwrap() = choice.is_fallback;
+
+        let mut active = self.active_microphone.lock().unwrap();
+        if *active != choice.name {
+            info!(
+                "Using microphone: {}",
+                choice.name.as_deref().unwrap_or("default")
+            );
+            *active = choice.name.clone();
+            let _ = self.app_handle.emit(
+                "microphone-changed",
+                MicrophoneChangedEvent {
+                    device_name: choice.name,
+                    is_fallback: choice.is_fallback,
+                },
+            );
+        }
+
         info!(
             "Microphone stream initialized in {:?}",
             start_time.elapsed()
@@ -289,7 +647,7 @@ impl AudioRecordingManager {
 
         let mut did_mute_guard = self.did_mute.lock().unwrap();
         if *did_mute_guard {
-            set_mute(false);
+            output_control::restore_output(&self.app_handle);
         }
         *did_mute_guard = false;
 
@@ -303,6 +661,7 @@ impl AudioRecordingManager {
         }
 
         *open_flag = false;
+        *self.input_level.lock().unwrap() = None;
         debug!("Microphone stream stopped");
     }
 
@@ -345,8 +704,13 @@ impl AudioRecordingManager {
                 }
             }
 
+            self.salvaged_samples.lock().unwrap().clear();
+            *self.durations.lock().unwrap() = RecordingDurations::default();
+            *self.journal_path.lock().unwrap() = self.new_journal_path();
+            *self.is_paused.lock().unwrap() = false;
+
             if let Some(rec) = self.recorder.lock().unwrap().as_ref() {
-                if rec.start().is_ok() {
+                if self.start_recorder(rec).is_ok() {
                     *self.is_recording.lock().unwrap() = true;
                     *state = RecordingState::Recording {
                         binding_id: binding_id.to_string(),
@@ -361,7 +725,54 @@ impl AudioRecordingManager {
         }
     }
 
+    /// Starts the recorder, journaling to the current recording's journal when
+    /// crash-safe recording is enabled.
+    fn start_recorder(&self, rec: &AudioRecorder) -> Result<(), Box<dyn std::error::Error>> {
+        match self.journal_path.lock().unwrap().clone() {
+            Some(path) => rec.start_journaled(path),
+            None => rec.start(),
+        }
+    }
+
+    fn new_journal_path(&self) -> Option<PathBuf> {
+        if !get_settings(&self.app_handle).crash_safe_recording {
+            return None;
+        }
+        if history_encrypted(&self.app_handle) {
+            debug!("Crash-safe recording is off while the history is encrypted");
+            return None;
+        }
+        match journal_dir(&self.app_handle) {
+            Ok(dir) => Some(dir.join(format!(
+                "journal-{}.pcm",
+                chrono::Utc::now().timestamp_millis()
+            ))),
+            Err(e) => {
+                warn!("Crash-safe recording unavailable: {e}");
+                None
+            }
+        }
+    }
+
+    /// Hands over the journal of the last stopped recording. The caller
+    /// removes it once the recording has been saved to history.
+    pub fn take_journal(&self) -> Option<PathBuf> {
+        self.journal_path.lock().unwrap().take()
+    }
+
+    /// Reopens an open stream so changed device or capture settings apply.
+    /// Reopening would drop the audio of a recording in progress, so during a
+    /// recording the restart waits until the recording ends.
     pub fn update_selected_device(&self) -> Result<(), anyhow::Error> {
+        // Held throughout, so a recording can't start halfway through a restart
+        let state = self.state.lock().unwrap();
+        if matches!(*state, RecordingState::Recording { .. }) {
+            debug!("Recording in progress, restarting the microphone stream after it");
+            *self.restart_pending.lock().unwrap() = true;
+            return Ok(());
+        }
+        *self.restart_pending.lock().unwrap() = false;
+
         // If currently open, restart the microphone stream to use the new device
         if *self.is_open.lock().unwrap() {
             self.stop_microphone_stream();
@@ -370,6 +781,17 @@ impl AudioRecordingManager {
         Ok(())
     }
 
+    /// Runs a restart deferred by `update_selected_device` once the recording
+    /// has ended.
+    fn apply_pending_restart(&self) {
+        let pending = std::mem::take(&mut *self.restart_pending.lock().unwrap());
+        if pending {
+            if let Err(e) = self.update_selected_device() {
+                warn!("Failed to restart microphone stream: {}", e);
+            }
+        }
+    }
+
     pub fn stop_recording(&self, binding_id: &str) -> Option<Vec<f32>> {
         let mut state = self.state.lock().unwrap();
 
@@ -380,25 +802,39 @@ impl AudioRecordingManager {
                 *state = RecordingState::Idle;
                 drop(state);
 
-                let samples = if let Some(rec) = self.recorder.lock().unwrap().as_ref() {
-                    match rec.stop() {
+                let captured = if let Some(rec) = self.recorder.lock().unwrap().as_ref() {
+                    let buf = match rec.stop() {
                         Ok(buf) => buf,
                         Err(e) => {
                             error!("stop() failed: {e}");
                             Vec::new()
                         }
+                    };
+                    self.durations.lock().unwrap().audio += rec.captured_duration();
+                    if let Some(levels) = rec.recorded_levels() {
+                        self.check_recording_level(levels);
                     }
+                    buf
                 } else {
                     error!("Recorder not available");
                     Vec::new()
                 };
 
+                // Prepend audio captured before a device switch, if any
+                let mut samples = std::mem::take(&mut *self.salvaged_samples.lock().unwrap());
+                samples.extend(captured);
+
                 *self.is_recording.lock().unwrap() = false;
+                *self.is_paused.lock().unwrap() = false;
 
                 // In on-demand mode turn the mic off again
-                if matches!(*self.mode.lock().unwrap(), MicrophoneMode::OnDemand) {
+                if self.should_close_after_recording() {
                     self.stop_microphone_stream();
                 }
+                self.apply_pending_restart();
+
+                self.durations.lock().unwrap().speech =
+                    Duration::from_secs_f64(samples.len() as f64 / WHISPER_SAMPLE_RATE as f64);
 
                 // Pad if very short
                 let s_len = samples.len();
@@ -414,6 +850,69 @@ impl AudioRecordingManager {
             _ => None,
         }
     }
+    /// Pauses the current recording; nothing is captured until it is resumed.
+    pub fn pause_recording(&self) -> Result<(), String> {
+        let state = self.state.lock().unwrap();
+        if !matches!(*state, RecordingState::Recording { .. }) {
+            return Err("Not recording".to_string());
+        }
+        let mut paused = self.is_paused.lock().unwrap();
+        if *paused {
+            return Ok(());
+        }
+        if let Some(rec) = self.recorder.lock().unwrap().as_ref() {
+            rec.pause()
+                .map_err(|e| format!("Failed to pause recording: {e}"))?;
+        }
+        *paused = true;
+        drop(paused);
+        drop(state);
+
+        // Let the user hear whatever interrupted them
+        self.remove_mute();
+        debug!("Recording paused");
+        Ok(())
+    }
+
+    /// Resumes a paused recording, continuing the same buffer.
+    pub fn resume_recording(&self) -> Result<(), String> {
+        let state = self.state.lock().unwrap();
+        if !matches!(*state, RecordingState::Recording { .. }) {
+            return Err("Not recording".to_string());
+        }
+        let mut paused = self.is_paused.lock().unwrap();
+        if !*paused {
+            return Ok(());
+        }
+        if let Some(rec) = self.recorder.lock().unwrap().as_ref() {
+            rec.resume()
+                .map_err(|e| format!("Failed to resume recording: {e}"))?;
+        }
+        *paused = false;
+        drop(paused);
+        drop(state);
+
+        self.apply_mute();
+        debug!("Recording resumed");
+        Ok(())
+    }
+
+    pub fn last_recording_durations(&self) -> RecordingDurations {
+        *self.durations.lock().unwrap()
+    }
+
+    pub fn is_paused(&self) -> bool {
+        *self.is_paused.lock().unwrap()
+    }
+
+    /// The binding that started the current recording, if any.
+    pub fn active_binding(&self) -> Option<String> {
+        match &*self.state.lock().unwrap() {
+            RecordingState::Recording { binding_id } => Some(binding_id.clone()),
+            RecordingState::Idle => None,
+        }
+    }
+
     pub fn is_recording(&self) -> bool {
         matches!(
             *self.state.lock().unwrap(),
@@ -432,13 +931,80 @@ impl AudioRecordingManager {
             if let Some(rec) = self.recorder.lock().unwrap().as_ref() {
                 let _ = rec.stop(); // Discard the result
             }
+            self.salvaged_samples.lock().unwrap().clear();
+            discard_journal(self.take_journal());
 
             *self.is_recording.lock().unwrap() = false;
+            *self.is_paused.lock().unwrap() = false;
 
             // In on-demand mode turn the mic off again
-            if matches!(*self.mode.lock().unwrap(), MicrophoneMode::OnDemand) {
+            if self.should_close_after_recording() {
                 self.stop_microphone_stream();
             }
+            self.apply_pending_restart();
+        }
+    }
+
+    fn should_close_after_recording(&self) -> bool {
+        matches!(*self.mode.lock().unwrap(), MicrophoneMode::OnDemand)
+            && !*self.monitoring.lock().unwrap()
+    }
+
+    /// Warns the frontend when a finished recording was too quiet or clipped.
+    /// `levels` are measured on the input before preprocessing, so gain
+    /// control doesn't hide a quiet microphone. Clipping is counted over all
+    /// input, loudness over speech only; when no speech was detected at all,
+    /// the recording is too quiet if even its peak stays below the threshold.
+    fn check_recording_level(&self, levels: RecordingLevels) {
+        let input = levels.input;
+        let rms_dbfs = levels
+            .speech
+            .map_or(input.rms_dbfs, |speech| speech.rms_dbfs);
+        let speech_dbfs = levels
+            .speech
+            .map_or(input.peak_dbfs, |speech| speech.rms_dbfs);
+
+        let kind = if input.clipped_samples as f32 > input.sample_count as f32 * CLIPPING_RATIO {
+            LevelWarningKind::Clipping
+        } else if speech_dbfs < TOO_QUIET_DBFS {
+            LevelWarningKind::TooQuiet
+        } else {
+            return;
+        };
+
+        warn!(
+            "Recording level warning {:?}: rms {:.1} dBFS, peak {:.1} dBFS, {} clipped samples",
+            kind, rms_dbfs, input.peak_dbfs, input.clipped_samples
+        );
+        let _ = self.app_handle.emit(
+            "recording-level-warning",
+            RecordingLevelWarning {
+                kind,
+                rms_dbfs,
+                peak_dbfs: input.peak_dbfs,
+                clipped_samples: input.clipped_samples,
+            },
+        );
+    }
+
+    /* ---------- level metering ---------------------------------------------- */
+
+    /// Latest metering window of the open stream, if any.
+    pub fn input_level(&self) -> Option<InputLevel> {
+        *self.input_level.lock().unwrap()
+    }
+
+    /// Keeps the microphone open so the settings page can show a live meter.
+    pub fn start_level_monitor(&self) -> Result<(), anyhow::Error> {
+        *self.monitoring.lock().unwrap() = true;
+        self.start_microphone_stream()
+    }
+
+    /// Ends level monitoring, closing the microphone again in on-demand mode.
+    pub fn stop_level_monitor(&self) {
+        *self.monitoring.lock().unwrap() = false;
+        if !self.is_recording() && self.should_close_after_recording() {
+            self.stop_microphone_stream();
         }
     }
 }
@@ -0,0 +1,244 @@
+//! The history archive: a tar.gz holding `history.json` with every entry,
+//! followed by the recordings under `recordings/`.
+
+use crate::managers::history::TranscriptionMetadata;
+use anyhow::Result;
+use flate2::read::GzDecoder;
+use flate2::write::GzEncoder;
+use flate2::Compression;
+use log::warn;
+use serde::{Deserialize, Serialize};
+use specta::Type;
+use std::fs::{self, File};
+use std::io::{BufWriter, Read};
+use std::path::{Path, PathBuf};
+use tar::{Archive, Builder, Header};
+
+pub const ARCHIVE_VERSION: u32 = 1;
+
+const MANIFEST_NAME: &str = "history.json";
+const RECORDINGS_PREFIX: &str = "recordings/";
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct HistoryArchive {
+    pub version: u32,
+    pub exported_at: i64,
+    pub entries: Vec<ArchivedEntry>,
+}
+
+/// An entry as stored in an archive. Tags and collections are kept by name,
+/// since ids don't carry over between databases.
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+pub struct ArchivedEntry {
+    pub file_name: String,
+    pub timestamp: i64,
+    pub saved: bool,
+    pub title: String,
+    pub transcription_text: String,
+    #[serde(default)]
+    pub post_processed_text: Option<String>,
+    #[serde(default)]
+    pub post_process_prompt: Option<String>,
+    #[serde(default)]
+    pub recovered: bool,
+    #[serde(default)]
+    pub metadata: TranscriptionMetadata,
+    #[serde(default)]
+    pub original_text: Option<String>,
+    #[serde(default)]
+    pub note: Option<String>,
+    /// The recording was deleted by retention, so the archive has none.
+    #[serde(default)]
+    pub audio_pruned: bool,
+    #[serde(default)]
+    pub tags: Vec<String>,
+    #[serde(default)]
+    pub collections: Vec<String>,
+}
+
+#[derive(Clone, Debug, Default, Serialize, Type)]
+pub struct ExportSummary {
+    pub entries: usize,
+    pub recordings: usize,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Type)]
+#[serde(rename_all = "snake_case")]
+pub enum ImportConflictKind {
+    /// The entry was already in history with different details; the existing
+    /// entry was kept and given the archive's tags and collections.
+    ExistingDiffers,
+    /// Another recording already had the entry's file name, so it was
+    /// imported under a new one.
+    RecordingRenamed,
+    /// The archive had no recording for the entry, so only its text was
+    /// imported.
+    RecordingMissing,
+}
+
+#[derive(Clone, Debug, Serialize, Type)]
+pub struct ImportConflict {
+    pub timestamp: i64,
+    pub title: String,
+    pub kind: ImportConflictKind,
+}
+
+#[derive(Clone, Debug, Default, Serialize, Type)]
+pub struct ImportReport {
+    pub imported: usize,
+    /// Entries already in history, matched by timestamp and text.
+    pub duplicates: usize,
+    pub conflicts: Vec<ImportConflict>,
+}
+
+/// Writes an archive to a temporary file next to `path`, renamed into place
+/// once complete.
+pub struct ArchiveWriter {
+    path: PathBuf,
+    temp_path: PathBuf,
+    builder: Builder<GzEncoder<BufWriter<File>>>,
+    mtime: u64,
+}
+
+impl ArchiveWriter {
+    /// Starts an archive with its manifest, which readers expect first.
+    pub fn create(path: &Path, archive: &HistoryArchive) -> Result<Self> {
+        let mut temp_path = path.to_path_buf().into_os_string();
+        temp_path.push(".partial");
+        let temp_path = PathBuf::from(temp_path);
+
+        let encoder = GzEncoder::new(
+            BufWriter::new(File::create(&temp_path)?),
+            Compression::default(),
+        );
+        let mut writer = Self {
+            path: path.to_path_buf(),
+            temp_path,
+            builder: Builder::new(encoder),
+            mtime: archive.exported_at.max(0) as u64,
+        };
+        writer.append(MANIFEST_NAME, &serde_json::to_vec_pretty(archive)?)?;
+        Ok(writer)
+    }
+
+    pub fn add_recording(&mut self, file_name: &str, data: &[u8]) -> Result<()> {
+        self.append(&format!("{RECORDINGS_PREFIX}{file_name}"), data)
+    }
+
+    fn append(&mut self, name: &str, data: &[u8]) -> Result<()> {
+        let mut header = Header::new_gnu();
+        header.set_size(data.len() as u64);
+        header.set_mode(0o644);
+        header.set_mtime(self.mtime);
+        header.set_cksum();
+        self.builder.append_data(&mut header, name, data)?;
+        Ok(())
+    }
+
+    pub fn finish(self) -> Result<()> {
+        let encoder = self.builder.into_inner()?;
+        encoder
+            .finish()?
+            .into_inner()
+            .map_err(|e| e.into_error())?
+            .sync_all()?;
+        fs::rename(&self.temp_path, &self.path)?;
+        Ok(())
+    }
+}
+
+/// Reads an archive's manifest and unpacks its recordings into
+/// `recordings_dir`, which should be a fresh staging directory. Each
+/// recording is passed through `seal` before it is written, so an encrypted
+/// history never stages one in plaintext.
+pub fn read_archive(
+    path: &Path,
+    recordings_dir: &Path,
+    seal: impl Fn(Vec<u8>) -> Result<Vec<u8>>,
+) -> Result<HistoryArchive> {
+    fs::create_dir_all(recordings_dir)?;
+    let mut archive = Archive::new(GzDecoder::new(File::open(path)?));
+    let mut manifest = None;
+
+    for entry in archive.entries()? {
+        let mut entry = entry?;
+        let name = entry.path()?.to_string_lossy().into_owned();
+
+        if name == MANIFEST_NAME {
+            let mut contents = Vec::new();
+            entry.read_to_end(&mut contents)?;
+            let parsed: HistoryArchive = serde_json::from_slice(&contents)?;
+            if parsed.version > ARCHIVE_VERSION {
+                anyhow::bail!(
+                    "The archive was made by a newer version (format {})",
+                    parsed.version
+                );
+            }
+            manifest = Some(parsed);
+        } else if let Some(file_name) = name.strip_prefix(RECORDINGS_PREFIX) {
+            if !is_plain_file_name(file_name) {
+                warn!("Skipping archived recording with an unsafe name: {}", name);
+                continue;
+            }
+            let mut contents = Vec::new();
+            entry.read_to_end(&mut contents)?;
+            fs::write(recordings_dir.join(file_name), seal(contents)?)?;
+        }
+    }
+
+    manifest.ok_or_else(|| anyhow::anyhow!("Not a history archive: {} is missing", MANIFEST_NAME))
+}
+
+/// Whether `name` names a file directly inside a directory, so it can't be
+/// used to write elsewhere.
+pub fn is_plain_file_name(name: &str) -> bool {
+    !name.is_empty()
+        && Path::new(name).file_name().and_then(|n| n.to_str()) == Some(name)
+        && name != ".."
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn archives_round_trip() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("history.tar.gz");
+        let archive = HistoryArchive {
+            version: ARCHIVE_VERSION,
+            exported_at: 1_700_000_000,
+            entries: vec![ArchivedEntry {
+                file_name: "handy-1.wav".to_string(),
+                timestamp: 1,
+                title: "Recording".to_string(),
+                transcription_text: "hello".to_string(),
+                tags: vec!["work".to_string()],
+                ..Default::default()
+            }],
+        };
+
+        let mut writer = ArchiveWriter::create(&path, &archive).unwrap();
+        writer.add_recording("handy-1.wav", b"RIFF audio").unwrap();
+        writer.finish().unwrap();
+
+        let staging = dir.path().join("staging");
+        let read = read_archive(&path, &staging, Ok).unwrap();
+        assert_eq!(read.entries.len(), 1);
+        assert_eq!(read.entries[0].transcription_text, "hello");
+        assert_eq!(read.entries[0].tags, vec!["work"]);
+        assert_eq!(
+            fs::read(staging.join("handy-1.wav")).unwrap(),
+            b"RIFF audio"
+        );
+    }
+
+    #[test]
+    fn only_plain_file_names_are_accepted() {
+        assert!(is_plain_file_name("handy-1.wav"));
+        assert!(!is_plain_file_name("../handy-1.wav"));
+        assert!(!is_plain_file_name("nested/handy-1.wav"));
+        assert!(!is_plain_file_name(".."));
+        assert!(!is_plain_file_name(""));
+    }
+}
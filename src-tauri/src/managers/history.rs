@@ -6,10 +6,22 @@ use rusqlite_migration::{Migrations, M};
 use serde::{Deserialize, Serialize};
 use specta::Type;
 use std::fs;
-use std::path::PathBuf;
+use std::io::Cursor;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Condvar, Mutex};
 use tauri::{AppHandle, Emitter, Manager};
 
-use crate::audio_toolkit::save_wav_file;
+use crate::audio_toolkit::audio::read_journal;
+use crate::audio_toolkit::{
+    constants, decode_audio, encode_audio, word_substitutions, AudioFileFormat,
+};
+use crate::encryption::{self, EncryptionState, EncryptionStatus, Vault};
+use crate::managers::backup::{
+    self, ArchiveWriter, ArchivedEntry, ExportSummary, HistoryArchive, ImportConflict,
+    ImportConflictKind, ImportReport,
+};
+use crate::managers::stats::{self, UsageRow, UsageStats, UsageStatsQuery};
 
 /// Database migrations for transcription history.
 /// Each migration is applied in order. The library tracks which migrations
@@ -31,8 +43,121 @@ static MIGRATIONS: &[M] = &[
     ),
     M::up("ALTER TABLE transcription_history ADD COLUMN post_processed_text TEXT;"),
     M::up("ALTER TABLE transcription_history ADD COLUMN post_process_prompt TEXT;"),
+    M::up("ALTER TABLE transcription_history ADD COLUMN recovered BOOLEAN NOT NULL DEFAULT 0;"),
+    // Full-text index over the history, kept in sync by triggers
+    M::up(
+        "CREATE VIRTUAL TABLE transcription_history_fts USING fts5(
+            title,
+            transcription_text,
+            post_processed_text,
+            content='transcription_history',
+            content_rowid='id'
+        );
+        INSERT INTO transcription_history_fts(transcription_history_fts) VALUES ('rebuild');
+        CREATE TRIGGER transcription_history_fts_insert AFTER INSERT ON transcription_history BEGIN
+            INSERT INTO transcription_history_fts(rowid, title, transcription_text, post_processed_text)
+            VALUES (new.id, new.title, new.transcription_text, new.post_processed_text);
+        END;
+        CREATE TRIGGER transcription_history_fts_delete AFTER DELETE ON transcription_history BEGIN
+            INSERT INTO transcription_history_fts(transcription_history_fts, rowid, title, transcription_text, post_processed_text)
+            VALUES ('delete', old.id, old.title, old.transcription_text, old.post_processed_text);
+        END;
+        CREATE TRIGGER transcription_history_fts_update
+        AFTER UPDATE OF title, transcription_text, post_processed_text ON transcription_history BEGIN
+            INSERT INTO transcription_history_fts(transcription_history_fts, rowid, title, transcription_text, post_processed_text)
+            VALUES ('delete', old.id, old.title, old.transcription_text, old.post_processed_text);
+            INSERT INTO transcription_history_fts(rowid, title, transcription_text, post_processed_text)
+            VALUES (new.id, new.title, new.transcription_text, new.post_processed_text);
+        END;",
+    ),
+    M::up(
+        "ALTER TABLE transcription_history ADD COLUMN model_id TEXT;
+        ALTER TABLE transcription_history ADD COLUMN language TEXT;
+        CREATE INDEX transcription_history_timestamp_id
+            ON transcription_history (timestamp DESC, id DESC);",
+    ),
+    M::up(
+        "ALTER TABLE transcription_history ADD COLUMN engine TEXT;
+        ALTER TABLE transcription_history ADD COLUMN translate BOOLEAN;
+        ALTER TABLE transcription_history ADD COLUMN audio_duration_ms INTEGER;
+        ALTER TABLE transcription_history ADD COLUMN speech_duration_ms INTEGER;
+        ALTER TABLE transcription_history ADD COLUMN inference_ms INTEGER;
+        ALTER TABLE transcription_history ADD COLUMN paste_method TEXT;
+        ALTER TABLE transcription_history ADD COLUMN binding_id TEXT;",
+    ),
+    M::up(
+        "ALTER TABLE transcription_history ADD COLUMN original_text TEXT;
+        CREATE TABLE learned_corrections (
+            original TEXT NOT NULL,
+            replacement TEXT NOT NULL,
+            count INTEGER NOT NULL DEFAULT 0,
+            PRIMARY KEY (original, replacement)
+        );",
+    ),
+    M::up(
+        "CREATE TABLE alternative_transcripts (
+            id INTEGER PRIMARY KEY AUTOINCREMENT,
+            entry_id INTEGER NOT NULL,
+            timestamp INTEGER NOT NULL,
+            model_id TEXT NOT NULL,
+            engine TEXT,
+            language TEXT NOT NULL,
+            transcription_text TEXT NOT NULL,
+            inference_ms INTEGER
+        );
+        CREATE INDEX alternative_transcripts_entry ON alternative_transcripts (entry_id);
+        CREATE TRIGGER alternative_transcripts_cleanup AFTER DELETE ON transcription_history BEGIN
+            DELETE FROM alternative_transcripts WHERE entry_id = old.id;
+        END;",
+    ),
+    M::up(
+        "ALTER TABLE transcription_history ADD COLUMN note TEXT;
+        CREATE TABLE tags (
+            id INTEGER PRIMARY KEY AUTOINCREMENT,
+            name TEXT NOT NULL UNIQUE COLLATE NOCASE,
+            retention_days INTEGER
+        );
+        CREATE TABLE entry_tags (
+            entry_id INTEGER NOT NULL,
+            tag_id INTEGER NOT NULL,
+            PRIMARY KEY (entry_id, tag_id)
+        );
+        CREATE INDEX entry_tags_tag ON entry_tags (tag_id);
+        CREATE TABLE collections (
+            id INTEGER PRIMARY KEY AUTOINCREMENT,
+            name TEXT NOT NULL UNIQUE COLLATE NOCASE,
+            created_at INTEGER NOT NULL
+        );
+        CREATE TABLE collection_entries (
+            collection_id INTEGER NOT NULL,
+            entry_id INTEGER NOT NULL,
+            PRIMARY KEY (collection_id, entry_id)
+        );
+        CREATE INDEX collection_entries_entry ON collection_entries (entry_id);
+        CREATE TRIGGER entry_organization_cleanup AFTER DELETE ON transcription_history BEGIN
+            DELETE FROM entry_tags WHERE entry_id = old.id;
+            DELETE FROM collection_entries WHERE entry_id = old.id;
+        END;
+        CREATE TRIGGER tags_cleanup AFTER DELETE ON tags BEGIN
+            DELETE FROM entry_tags WHERE tag_id = old.id;
+        END;
+        CREATE TRIGGER collections_cleanup AFTER DELETE ON collections BEGIN
+            DELETE FROM collection_entries WHERE collection_id = old.id;
+        END;",
+    ),
+    M::up("ALTER TABLE transcription_history ADD COLUMN audio_pruned BOOLEAN NOT NULL DEFAULT 0;"),
 ];
 
+/// Columns read by [`entry_from_row`].
+const ENTRY_COLUMNS: &str = "id, file_name, timestamp, saved, title, transcription_text, recovered,
+     model_id, engine, language, translate, audio_duration_ms,
+     speech_duration_ms, inference_ms, paste_method, binding_id, original_text, note, audio_pruned,
+     (SELECT group_concat(tag_id) FROM entry_tags WHERE entry_id = transcription_history.id) AS tag_ids";
+
+/// Entries the retention settings may delete: neither saved nor tagged.
+const UNPROTECTED_ENTRIES: &str = "saved = 0 AND NOT EXISTS
+     (SELECT 1 FROM entry_tags WHERE entry_id = transcription_history.id)";
+
 #[derive(Clone, Debug, Serialize, Deserialize, Type)]
 pub struct HistoryEntry {
     pub id: i64,
@@ -41,12 +166,196 @@ pub struct HistoryEntry {
     pub saved: bool,
     pub title: String,
     pub transcription_text: String,
+    /// Audio recovered from a crash-safe journal that hasn't been transcribed.
+    pub recovered: bool,
+    pub metadata: TranscriptionMetadata,
+    /// The model's transcript, when it has been edited by hand.
+    pub original_text: Option<String>,
+    pub note: Option<String>,
+    /// Ids of the entry's tags.
+    pub tags: Vec<i64>,
+    /// The recording was deleted by retention; only the text is kept.
+    pub audio_pruned: bool,
+}
+
+/// A user tag. The retention cleanup keeps tagged entries like saved ones,
+/// or for `retention_days` when every tag of the entry sets it.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Type)]
+pub struct HistoryTag {
+    pub id: i64,
+    pub name: String,
+    pub retention_days: Option<u32>,
+    pub entry_count: u32,
+}
+
+/// A named group of history entries.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Type)]
+pub struct HistoryCollection {
+    pub id: i64,
+    pub name: String,
+    pub created_at: i64,
+    pub entry_count: u32,
+}
+
+/// Another model's or language's transcript of an entry's recording.
+#[derive(Clone, Debug, Serialize, Type)]
+pub struct AlternativeTranscript {
+    pub id: i64,
+    pub entry_id: i64,
+    pub timestamp: i64,
+    pub model_id: String,
+    pub engine: Option<String>,
+    pub language: String,
+    pub transcription_text: String,
+    pub inference_ms: Option<u64>,
+}
+
+/// A word-level correction made by hand in history, with how often it was
+/// made across entries.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Type)]
+pub struct LearnedCorrection {
+    pub original: String,
+    pub replacement: String,
+    pub count: u32,
+}
+
+/// How an entry was transcribed. Entries from before this was recorded, and
+/// recovered ones that haven't been transcribed, leave it unset.
+#[derive(Clone, Debug, Default, Serialize, Deserialize, Type)]
+pub struct TranscriptionMetadata {
+    pub model_id: Option<String>,
+    pub engine: Option<String>,
+    /// Language selected in settings, `auto` for detection. The engines don't
+    /// report which language they detected, so that isn't kept.
+    pub language: Option<String>,
+    pub translate: Option<bool>,
+    /// Recorded audio, excluding paused time.
+    pub audio_duration_ms: Option<u64>,
+    /// Audio left after voice activity detection.
+    pub speech_duration_ms: Option<u64>,
+    pub inference_ms: Option<u64>,
+    pub paste_method: Option<String>,
+    /// Shortcut binding that started the recording.
+    pub binding_id: Option<String>,
+}
+
+/// Position in the history, ordered newest first.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize, Type)]
+pub struct HistoryCursor {
+    pub timestamp: i64,
+    pub id: i64,
+}
+
+/// A page of history, optionally filtered. Dates are unix timestamps in
+/// seconds, `from` inclusive and `to` exclusive.
+#[derive(Clone, Debug, Deserialize, Type)]
+pub struct HistoryPageQuery {
+    /// Only entries older than this; the first page when unset.
+    #[serde(default)]
+    pub before: Option<HistoryCursor>,
+    #[serde(default = "default_page_size")]
+    pub limit: u32,
+    #[serde(default)]
+    pub saved_only: bool,
+    #[serde(default)]
+    pub model_id: Option<String>,
+    #[serde(default)]
+    pub language: Option<String>,
+    #[serde(default)]
+    pub from: Option<i64>,
+    #[serde(default)]
+    pub to: Option<i64>,
+    #[serde(default)]
+    pub tag_id: Option<i64>,
+    #[serde(default)]
+    pub collection_id: Option<i64>,
+}
+
+#[derive(Clone, Debug, Serialize, Type)]
+pub struct HistoryPage {
+    pub entries: Vec<HistoryEntry>,
+    /// Cursor for the next page, or `None` on the last one.
+    pub next: Option<HistoryCursor>,
+}
+
+/// Payload of `history-updated`, so listeners can patch what they show.
+#[derive(Clone, Debug, Default, Serialize, Type)]
+pub struct HistoryUpdate {
+    pub inserted: Vec<i64>,
+    pub updated: Vec<i64>,
+    pub deleted: Vec<i64>,
+}
+
+/// A full-text search over history. Dates are unix timestamps in seconds,
+/// `from` inclusive and `to` exclusive.
+#[derive(Clone, Debug, Deserialize, Type)]
+pub struct HistorySearchQuery {
+    pub query: String,
+    #[serde(default)]
+    pub from: Option<i64>,
+    #[serde(default)]
+    pub to: Option<i64>,
+    #[serde(default)]
+    pub saved_only: bool,
+    #[serde(default)]
+    pub tag_id: Option<i64>,
+    #[serde(default)]
+    pub offset: u32,
+    #[serde(default = "default_page_size")]
+    pub limit: u32,
+}
+
+fn default_page_size() -> u32 {
+    50
+}
+
+/// A piece of a search snippet; highlighted parts matched the query.
+#[derive(Clone, Debug, PartialEq, Serialize, Type)]
+pub struct SnippetPart {
+    pub text: String,
+    pub highlighted: bool,
+}
+
+#[derive(Clone, Debug, Serialize, Type)]
+pub struct HistorySearchHit {
+    pub entry: HistoryEntry,
+    pub snippet: Vec<SnippetPart>,
+}
+
+#[derive(Clone, Debug, Serialize, Type)]
+pub struct HistorySearchResults {
+    /// Best matches first.
+    pub hits: Vec<HistorySearchHit>,
+    /// Number of matches across all pages.
+    pub total: u32,
+}
+
+#[derive(Clone, Debug, Serialize, Type)]
+pub struct RecordingConversionProgress {
+    pub processed: usize,
+    pub total: usize,
+}
+
+#[derive(Clone, Debug, Default, Serialize, Type)]
+pub struct RecordingConversionSummary {
+    pub converted: usize,
+    pub skipped: usize,
+    pub failed: usize,
 }
 
 pub struct HistoryManager {
     app_handle: AppHandle,
     recordings_dir: PathBuf,
     db_path: PathBuf,
+    /// Held by every open connection, so the database file can be swapped.
+    db_gate: Arc<DatabaseGate>,
+    vault: Vault,
+    /// Dictations made while the history is locked, saved once it's unlocked.
+    /// They're only in memory, as nothing is written unencrypted.
+    pending: Mutex<Vec<PendingDictation>>,
+    /// Recordings are being rewritten, by a conversion or by turning
+    /// encryption on or off.
+    converting: AtomicBool,
 }
 
 impl HistoryManager {
@@ -66,18 +375,53 @@ impl HistoryManager {
             app_handle: app_handle.clone(),
             recordings_dir,
             db_path,
+            db_gate: Arc::new(DatabaseGate::default()),
+            vault: Vault::open(&app_data_dir, &app_handle.config().identifier)?,
+            pending: Mutex::new(Vec::new()),
+            converting: AtomicBool::new(false),
         };
 
-        // Initialize database and run migrations synchronously
-        manager.init_database()?;
+        manager.clear_import_staging();
+
+        if manager.vault.status() == EncryptionStatus::Locked {
+            info!("History is encrypted and stays locked until its passphrase is entered");
+        } else {
+            manager.open_history()?;
+        }
 
         Ok(manager)
     }
 
+    /// Brings the database up to date and recovers crashed recordings, once
+    /// the history can be read.
+    fn open_history(&self) -> Result<()> {
+        // Initialize database and run migrations synchronously
+        self.init_database()?;
+
+        // Finish encrypting anything an interrupted migration left behind
+        if self.vault.status() == EncryptionStatus::Unlocked {
+            if let Err(e) = self.encrypt_existing_data() {
+                error!("Failed to encrypt existing history: {}", e);
+            }
+        }
+
+        // Journals left behind by a crash become recovered entries
+        if let Err(e) = self.recover_journals() {
+            error!("Failed to recover recording journals: {}", e);
+        }
+
+        // Retention otherwise only runs when something is saved
+        if let Err(e) = self.cleanup_old_entries() {
+            error!("Failed to apply history retention: {}", e);
+        }
+
+        Ok(())
+    }
+
     fn init_database(&self) -> Result<()> {
         info!("Initializing database at {:?}", self.db_path);
 
-        let mut conn = Connection::open(&self.db_path)?;
+        let mut conn = self.get_connection()?;
 
         // Handle migration from tauri-plugin-sql to rusqlite_migration
         // tauri-plugin-sql used _sqlx_migrations table, rusqlite_migration uses user_version pragma
@@ -170,35 +514,247 @@ impl HistoryManager {
         Ok(())
     }
 
-    fn get_connection(&self) -> Result<Connection> {
-        Ok(Connection::open(&self.db_path)?)
+    fn get_connection(&self) -> Result<HistoryConnection> {
+        let lease = self.db_gate.enter();
+        Ok(HistoryConnection {
+            conn: self.open_connection()?,
+            _lease: lease,
+        })
+    }
+
+    /// Opens the database outside the gate; only for swapping it.
+    fn open_connection(&self) -> Result<Connection> {
+        let key = self.vault.key();
+        if key.is_none() && self.vault.status() == EncryptionStatus::Locked {
+            anyhow::bail!("History is locked");
+        }
+
+        let conn = Connection::open(&self.db_path)?;
+        if let Some(key) = key {
+            // Left in plaintext until the migration to encryption gets to it
+            if !encryption::is_plaintext_database(&self.db_path)? {
+                conn.execute_batch(&key.sqlcipher_pragma())?;
+            }
+        }
+        Ok(conn)
+    }
+
+    /// Encodes a recording and writes it, encrypted when the history is.
+    fn write_recording(&self, path: &Path, samples: &[f32], format: AudioFileFormat) -> Result<()> {
+        let data = self.vault.seal(encode_audio(samples, format)?)?;
+        fs::write(path, data)?;
+        debug!("Saved {:?} recording: {:?}", format, path);
+        Ok(())
+    }
+
+    /// Reads a recording's samples and sample rate, decrypting it if needed.
+    pub fn read_recording(&self, path: &Path) -> Result<(Vec<f32>, u32)> {
+        let data = self.vault.unseal(fs::read(path)?)?;
+        let extension = path
+            .extension()
+            .and_then(|ext| ext.to_str())
+            .unwrap_or_default();
+        decode_audio(Cursor::new(data), extension)
     }
 
-    /// Save a transcription to history (both database and WAV file)
+    /// Save a transcription to history (both database and audio file)
     pub async fn save_transcription(
         &self,
         audio_samples: Vec<f32>,
         transcription_text: String,
+        metadata: TranscriptionMetadata,
     ) -> Result<()> {
         let timestamp = Utc::now().timestamp();
-        let file_name = format!("handy-{}.wav", timestamp);
+        if self.vault.status() == EncryptionStatus::Locked {
+            self.hold_until_unlocked(PendingDictation {
+                timestamp,
+                audio_samples,
+                transcription_text,
+                metadata,
+                append: false,
+            });
+            return Ok(());
+        }
+        self.save_new_entry(timestamp, &audio_samples, transcription_text, &metadata)
+    }
+
+    fn save_new_entry(
+        &self,
+        timestamp: i64,
+        audio_samples: &[f32],
+        transcription_text: String,
+        metadata: &TranscriptionMetadata,
+    ) -> Result<()> {
+        let format = crate::settings::get_recording_file_format(&self.app_handle);
+        let file_name = format!("handy-{}.{}", timestamp, format.extension());
         let title = self.format_timestamp_title(timestamp);
 
-        // Save WAV file
+        // Save audio file
         let file_path = self.recordings_dir.join(&file_name);
-        save_wav_file(file_path, &audio_samples).await?;
+        self.write_recording(&file_path, audio_samples, format)?;
 
         // Save to database
-        self.save_to_database(file_name, timestamp, title, transcription_text)?;
+        let id =
+            self.save_to_database(file_name, timestamp, title, transcription_text, metadata)?;
 
         // Clean up old entries
-        self.cleanup_old_entries()?;
+        let mut update = self.apply_retention()?;
+        update.inserted.push(id);
+        self.emit_history_updated(update);
+
+        Ok(())
+    }
+
+    fn hold_until_unlocked(&self, dictation: PendingDictation) {
+        info!("History is locked, keeping the dictation in memory until it's unlocked");
+        self.pending.lock().unwrap().push(dictation);
+    }
+
+    /// Saves the dictations made while the history was locked, in order.
+    fn save_pending_dictations(&self) {
+        let pending = std::mem::take(&mut *self.pending.lock().unwrap());
+        for dictation in pending {
+            let result = if dictation.append {
+                tauri::async_runtime::block_on(self.append_to_latest(
+                    dictation.audio_samples,
+                    dictation.transcription_text,
+                    dictation.metadata,
+                ))
+            } else {
+                self.save_new_entry(
+                    dictation.timestamp,
+                    &dictation.audio_samples,
+                    dictation.transcription_text,
+                    &dictation.metadata,
+                )
+            };
+            if let Err(e) = result {
+                error!("Failed to save a dictation made while locked: {}", e);
+            }
+        }
+    }
 
-        // Emit history updated event
-        if let Err(e) = self.app_handle.emit("history-updated", ()) {
+    fn emit_history_updated(&self, update: HistoryUpdate) {
+        if let Err(e) = self.app_handle.emit("history-updated", update) {
             error!("Failed to emit history-updated event: {}", e);
         }
+    }
+
+    /// Adds a follow-up dictation to the latest entry: its text is appended to
+    /// the entry's transcript and its audio to the entry's recording, after a
+    /// short silence. Durations add up; the rest of the metadata is kept from
+    /// the first dictation. Starts a new entry when there is nothing to append
+    /// to.
+    pub async fn append_to_latest(
+        &self,
+        audio_samples: Vec<f32>,
+        transcription_text: String,
+        metadata: TranscriptionMetadata,
+    ) -> Result<()> {
+        if self.vault.status() == EncryptionStatus::Locked {
+            self.hold_until_unlocked(PendingDictation {
+                timestamp: Utc::now().timestamp(),
+                audio_samples,
+                transcription_text,
+                metadata,
+                append: true,
+            });
+            return Ok(());
+        }
+
+        let latest = match self.get_latest_entry()? {
+            // Appending to a pruned entry would leave it with partial audio
+            Some(entry) if !entry.recovered && !entry.audio_pruned => entry,
+            _ => {
+                return self
+                    .save_transcription(audio_samples, transcription_text, metadata)
+                    .await
+            }
+        };
+
+        let old_path = self.recordings_dir.join(&latest.file_name);
+        let previous = if old_path.exists() {
+            let (samples, sample_rate) = self.read_recording(&old_path)?;
+            if sample_rate != constants::WHISPER_SAMPLE_RATE {
+                anyhow::bail!(
+                    "Cannot append to {} recorded at {} Hz",
+                    latest.file_name,
+                    sample_rate
+                );
+            }
+            samples
+        } else {
+            Vec::new()
+        };
+        let combined = append_recording(previous, &audio_samples);
+
+        // Written next to the original first, so a failure leaves it intact
+        let format = crate::settings::get_recording_file_format(&self.app_handle);
+        let file_name = Path::new(&latest.file_name)
+            .with_extension(format.extension())
+            .to_string_lossy()
+            .into_owned();
+        let new_path = self.recordings_dir.join(&file_name);
+        let temp_path = new_path.with_extension("tmp");
+        self.write_recording(&temp_path, &combined, format)?;
+        fs::rename(&temp_path, &new_path)?;
+        if new_path != old_path && old_path.exists() {
+            if let Err(e) = fs::remove_file(&old_path) {
+                error!("Failed to delete {}: {}", latest.file_name, e);
+            }
+        }
+
+        let conn = self.get_connection()?;
+        Self::append_to_entry_with_conn(
+            &conn,
+            &latest,
+            &file_name,
+            &transcription_text,
+            &metadata,
+        )?;
+        debug!("Appended dictation to history entry {}", latest.id);
+
+        self.emit_history_updated(HistoryUpdate {
+            updated: vec![latest.id],
+            ..Default::default()
+        });
+
+        Ok(())
+    }
 
+    /// Adds a dictation's text and timings to an existing entry. An edited
+    /// entry gets the text on its model transcript too, so reverting the edit
+    /// keeps the appended dictation.
+    fn append_to_entry_with_conn(
+        conn: &Connection,
+        entry: &HistoryEntry,
+        file_name: &str,
+        text: &str,
+        metadata: &TranscriptionMetadata,
+    ) -> Result<()> {
+        let original_text = entry
+            .original_text
+            .as_deref()
+            .map(|original| join_transcripts(original, text));
+        conn.execute(
+            "UPDATE transcription_history SET
+                file_name = ?1,
+                transcription_text = ?2,
+                original_text = ?3,
+                audio_duration_ms = COALESCE(audio_duration_ms, 0) + ?4,
+                speech_duration_ms = COALESCE(speech_duration_ms, 0) + ?5,
+                inference_ms = COALESCE(inference_ms, 0) + ?6
+             WHERE id = ?7",
+            params![
+                file_name,
+                join_transcripts(&entry.transcription_text, text),
+                original_text,
+                metadata.audio_duration_ms,
+                metadata.speech_duration_ms,
+                metadata.inference_ms,
+                entry.id
+            ],
+        )?;
         Ok(())
     }
 
@@ -208,35 +764,173 @@ impl HistoryManager {
         timestamp: i64,
         title: String,
         transcription_text: String,
-    ) -> Result<()> {
+        metadata: &TranscriptionMetadata,
+    ) -> Result<i64> {
         let conn = self.get_connection()?;
         conn.execute(
-            "INSERT INTO transcription_history (file_name, timestamp, saved, title, transcription_text) VALUES (?1, ?2, ?3, ?4, ?5)",
-            params![file_name, timestamp, false, title, transcription_text],
+            "INSERT INTO transcription_history (
+                file_name, timestamp, saved, title, transcription_text, model_id, engine,
+                language, translate, audio_duration_ms, speech_duration_ms, inference_ms,
+                paste_method, binding_id
+             ) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12, ?13, ?14)",
+            params![
+                file_name,
+                timestamp,
+                false,
+                title,
+                transcription_text,
+                metadata.model_id,
+                metadata.engine,
+                metadata.language,
+                metadata.translate,
+                metadata.audio_duration_ms,
+                metadata.speech_duration_ms,
+                metadata.inference_ms,
+                metadata.paste_method,
+                metadata.binding_id
+            ],
         )?;
 
         debug!("Saved transcription to database");
-        Ok(())
+        Ok(conn.last_insert_rowid())
     }
 
     pub fn cleanup_old_entries(&self) -> Result<()> {
+        let update = self.apply_retention()?;
+        if !update.deleted.is_empty() || !update.updated.is_empty() {
+            self.emit_history_updated(update);
+        }
+        Ok(())
+    }
+
+    /// Applies the retention settings: deleted entries are reported as
+    /// deleted, entries that only lost their audio as updated.
+    fn apply_retention(&self) -> Result<HistoryUpdate> {
         let retention_period = crate::settings::get_recording_retention_period(&self.app_handle);
+        let mut update = HistoryUpdate::default();
 
         match retention_period {
             crate::settings::RecordingRetentionPeriod::Never => {
                 // Don't delete anything
-                return Ok(());
             }
             crate::settings::RecordingRetentionPeriod::PreserveLimit => {
                 // Use the old count-based logic with history_limit
                 let limit = crate::settings::get_history_limit(&self.app_handle);
-                return self.cleanup_by_count(limit);
+                update.deleted = self.cleanup_by_count(limit)?;
+            }
+            crate::settings::RecordingRetentionPeriod::TextOnly => {
+                let days = crate::settings::get_settings(&self.app_handle).audio_retention_days;
+                update.updated = self.prune_audio_by_age(days)?;
+            }
+            crate::settings::RecordingRetentionPeriod::DiskQuota => {
+                let quota_mb = crate::settings::get_settings(&self.app_handle).recordings_quota_mb;
+                update.updated = self.prune_audio_by_quota(quota_mb)?;
             }
             _ => {
                 // Use time-based logic
-                return self.cleanup_by_time(retention_period);
+                update.deleted = self.cleanup_by_time(retention_period)?;
+            }
+        }
+
+        // Tags with their own retention apply whatever the general setting
+        update.deleted.extend(self.cleanup_by_tag_retention()?);
+        Ok(update)
+    }
+
+    /// Deletes the audio of entries older than `days`, keeping their text.
+    fn prune_audio_by_age(&self, days: u32) -> Result<Vec<i64>> {
+        let conn = self.get_connection()?;
+        let cutoff = Utc::now().timestamp() - i64::from(days) * 24 * 60 * 60;
+        let candidates = Self::prunable_audio_with_conn(&conn, Some(cutoff))?;
+
+        let pruned = self.prune_audio(&conn, &candidates)?;
+        if !pruned.is_empty() {
+            debug!(
+                "Deleted the audio of {} entries older than {} days",
+                pruned.len(),
+                days
+            );
+        }
+        Ok(pruned)
+    }
+
+    /// Deletes the oldest audio until the recordings fit in `quota_mb`,
+    /// keeping the entries' text. Saved and tagged entries keep their audio,
+    /// so the recordings can stay over the quota.
+    fn prune_audio_by_quota(&self, quota_mb: u64) -> Result<Vec<i64>> {
+        let quota = quota_mb.saturating_mul(1024 * 1024);
+        let mut used = recordings_size(&self.recordings_dir)?;
+        if used <= quota {
+            return Ok(Vec::new());
+        }
+
+        let conn = self.get_connection()?;
+        let mut evicted = Vec::new();
+        for (id, file_name) in Self::prunable_audio_with_conn(&conn, None)? {
+            if used <= quota {
+                break;
             }
+            let size = fs::metadata(self.recordings_dir.join(&file_name))
+                .map(|metadata| metadata.len())
+                .unwrap_or(0);
+            used = used.saturating_sub(size);
+            evicted.push((id, file_name));
         }
+
+        let pruned = self.prune_audio(&conn, &evicted)?;
+        if used > quota {
+            info!(
+                "Recordings take {} MB, over the {} MB quota, after deleting all unsaved audio",
+                used / (1024 * 1024),
+                quota_mb
+            );
+        }
+        if !pruned.is_empty() {
+            debug!(
+                "Deleted the audio of {} entries to fit the disk quota",
+                pruned.len()
+            );
+        }
+        Ok(pruned)
+    }
+
+    /// Entries whose audio retention may delete, oldest first: unprotected,
+    /// transcribed, still with audio, and older than `before` when set.
+    fn prunable_audio_with_conn(
+        conn: &Connection,
+        before: Option<i64>,
+    ) -> Result<Vec<(i64, String)>> {
+        let mut stmt = conn.prepare(&format!(
+            "SELECT id, file_name FROM transcription_history
+             WHERE {UNPROTECTED_ENTRIES} AND recovered = 0 AND audio_pruned = 0
+               AND (?1 IS NULL OR timestamp < ?1)
+             ORDER BY timestamp, id"
+        ))?;
+        let rows = stmt
+            .query_map(params![before], |row| Ok((row.get(0)?, row.get(1)?)))?
+            .collect::<Result<Vec<_>, _>>()?;
+        Ok(rows)
+    }
+
+    /// Deletes the recordings of `entries` and flags them as pruned,
+    /// returning the ids of the entries flagged.
+    fn prune_audio(&self, conn: &Connection, entries: &[(i64, String)]) -> Result<Vec<i64>> {
+        let mut pruned = Vec::new();
+        for (id, file_name) in entries {
+            let file_path = self.recordings_dir.join(file_name);
+            if let Err(e) = fs::remove_file(&file_path) {
+                if e.kind() != std::io::ErrorKind::NotFound {
+                    error!("Failed to delete audio file {}: {}", file_name, e);
+                    continue;
+                }
+            }
+            conn.execute(
+                "UPDATE transcription_history SET audio_pruned = 1 WHERE id = ?1",
+                params![id],
+            )?;
+            pruned.push(*id);
+        }
+        Ok(pruned)
     }
 
     fn delete_entries_and_files(&self, entries: &[(i64, String)]) -> Result<usize> {
@@ -269,13 +963,13 @@ impl HistoryManager {
         Ok(deleted_count)
     }
 
-    fn cleanup_by_count(&self, limit: usize) -> Result<()> {
+    fn cleanup_by_count(&self, limit: usize) -> Result<Vec<i64>> {
         let conn = self.get_connection()?;
 
-        // Get all entries that are not saved, ordered by timestamp desc
-        let mut stmt = conn.prepare(
-            "SELECT id, file_name FROM transcription_history WHERE saved = 0 ORDER BY timestamp DESC"
-        )?;
+        // Get all entries that are not saved or tagged, ordered by timestamp desc
+        let mut stmt = conn.prepare(&format!(
+            "SELECT id, file_name FROM transcription_history WHERE {UNPROTECTED_ENTRIES} ORDER BY timestamp DESC"
+        ))?;
 
         let rows = stmt.query_map([], |row| {
             Ok((row.get::<_, i64>("id")?, row.get::<_, String>("file_name")?))
@@ -286,22 +980,24 @@ impl HistoryManager {
             entries.push(row?);
         }
 
-        if entries.len() > limit {
-            let entries_to_delete = &entries[limit..];
-            let deleted_count = self.delete_entries_and_files(entries_to_delete)?;
+        if entries.len() <= limit {
+            return Ok(Vec::new());
+        }
+
+        let entries_to_delete = &entries[limit..];
+        let deleted_count = self.delete_entries_and_files(entries_to_delete)?;
 
-            if deleted_count > 0 {
-                debug!("Cleaned up {} old history entries by count", deleted_count);
-            }
+        if deleted_count > 0 {
+            debug!("Cleaned up {} old history entries by count", deleted_count);
         }
 
-        Ok(())
+        Ok(entries_to_delete.iter().map(|(id, _)| *id).collect())
     }
 
     fn cleanup_by_time(
         &self,
         retention_period: crate::settings::RecordingRetentionPeriod,
-    ) -> Result<()> {
+    ) -> Result<Vec<i64>> {
         let conn = self.get_connection()?;
 
         // Calculate cutoff timestamp (current time minus retention period)
@@ -313,10 +1009,10 @@ impl HistoryManager {
             _ => unreachable!("Should not reach here"),
         };
 
-        // Get all unsaved entries older than the cutoff timestamp
-        let mut stmt = conn.prepare(
-            "SELECT id, file_name FROM transcription_history WHERE saved = 0 AND timestamp < ?1",
-        )?;
+        // Get all unsaved, untagged entries older than the cutoff timestamp
+        let mut stmt = conn.prepare(&format!(
+            "SELECT id, file_name FROM transcription_history WHERE {UNPROTECTED_ENTRIES} AND timestamp < ?1"
+        ))?;
 
         let rows = stmt.query_map(params![cutoff_timestamp], |row| {
             Ok((row.get::<_, i64>("id")?, row.get::<_, String>("file_name")?))
@@ -336,25 +1032,51 @@ impl HistoryManager {
             );
         }
 
-        Ok(())
+        Ok(entries_to_delete.into_iter().map(|(id, _)| id).collect())
     }
 
-    pub async fn get_history_entries(&self) -> Result<Vec<HistoryEntry>> {
+    fn cleanup_by_tag_retention(&self) -> Result<Vec<i64>> {
         let conn = self.get_connection()?;
+        let entries_to_delete =
+            Self::expired_tagged_entries_with_conn(&conn, Utc::now().timestamp())?;
+
+        let deleted_count = self.delete_entries_and_files(&entries_to_delete)?;
+        if deleted_count > 0 {
+            debug!(
+                "Cleaned up {} tagged history entries past their tag retention",
+                deleted_count
+            );
+        }
+
+        Ok(entries_to_delete.into_iter().map(|(id, _)| id).collect())
+    }
+
+    /// Unsaved entries whose tags all set a retention, older than the longest
+    /// of them.
+    fn expired_tagged_entries_with_conn(conn: &Connection, now: i64) -> Result<Vec<(i64, String)>> {
         let mut stmt = conn.prepare(
-            "SELECT id, file_name, timestamp, saved, title, transcription_text FROM transcription_history ORDER BY timestamp DESC"
+            "SELECT h.id, h.file_name FROM transcription_history h
+             JOIN entry_tags et ON et.entry_id = h.id
+             JOIN tags t ON t.id = et.tag_id
+             WHERE h.saved = 0
+             GROUP BY h.id
+             HAVING COUNT(t.retention_days) = COUNT(*)
+                AND h.timestamp < ?1 - MAX(t.retention_days) * 86400
+             ORDER BY h.id",
         )?;
+        let rows = stmt
+            .query_map(params![now], |row| Ok((row.get(0)?, row.get(1)?)))?
+            .collect::<Result<Vec<_>, _>>()?;
+        Ok(rows)
+    }
 
-        let rows = stmt.query_map([], |row| {
-            Ok(HistoryEntry {
-                id: row.get("id")?,
-                file_name: row.get("file_name")?,
-                timestamp: row.get("timestamp")?,
-                saved: row.get("saved")?,
-                title: row.get("title")?,
-                transcription_text: row.get("transcription_text")?,
-            })
-        })?;
+    pub async fn get_history_entries(&self) -> Result<Vec<HistoryEntry>> {
+        let conn = self.get_connection()?;
+        let mut stmt = conn.prepare(&format!(
+            "SELECT {ENTRY_COLUMNS} FROM transcription_history ORDER BY timestamp DESC"
+        ))?;
+
+        let rows = stmt.query_map([], entry_from_row)?;
 
         let mut entries = Vec::new();
         for row in rows {
@@ -364,85 +1086,1372 @@ impl HistoryManager {
         Ok(entries)
     }
 
-    pub fn get_latest_entry(&self) -> Result<Option<HistoryEntry>> {
+    /// Newest entries first, a page at a time.
+    pub fn get_history_page(&self, query: &HistoryPageQuery) -> Result<HistoryPage> {
         let conn = self.get_connection()?;
-        Self::get_latest_entry_with_conn(&conn)
+        Self::get_history_page_with_conn(&conn, query)
     }
 
-    fn get_latest_entry_with_conn(conn: &Connection) -> Result<Option<HistoryEntry>> {
-        let mut stmt = conn.prepare(
-            "SELECT id, file_name, timestamp, saved, title, transcription_text
-             FROM transcription_history
-             ORDER BY timestamp DESC
-             LIMIT 1",
+    fn get_history_page_with_conn(
+        conn: &Connection,
+        query: &HistoryPageQuery,
+    ) -> Result<HistoryPage> {
+        let mut stmt = conn.prepare(&format!(
+            "SELECT {ENTRY_COLUMNS} FROM transcription_history
+             WHERE (?1 IS NULL OR timestamp < ?1 OR (timestamp = ?1 AND id < ?2))
+               AND (?3 = 0 OR saved = 1)
+               AND (?4 IS NULL OR model_id = ?4)
+               AND (?5 IS NULL OR language = ?5)
+               AND (?6 IS NULL OR timestamp >= ?6)
+               AND (?7 IS NULL OR timestamp < ?7)
+               AND (?8 IS NULL OR EXISTS (SELECT 1 FROM entry_tags
+                    WHERE entry_id = transcription_history.id AND tag_id = ?8))
+               AND (?9 IS NULL OR EXISTS (SELECT 1 FROM collection_entries
+                    WHERE entry_id = transcription_history.id AND collection_id = ?9))
+             ORDER BY timestamp DESC, id DESC
+             LIMIT ?10"
+        ))?;
+
+        // One extra row tells whether there is another page
+        let rows = stmt.query_map(
+            params![
+                query.before.map(|cursor| cursor.timestamp),
+                query.before.map(|cursor| cursor.id),
+                query.saved_only,
+                query.model_id,
+                query.language,
+                query.from,
+                query.to,
+                query.tag_id,
+                query.collection_id,
+                query.limit.saturating_add(1)
+            ],
+            entry_from_row,
         )?;
 
-        let entry = stmt
-            .query_row([], |row| {
-                Ok(HistoryEntry {
-                    id: row.get("id")?,
-                    file_name: row.get("file_name")?,
-                    timestamp: row.get("timestamp")?,
-                    saved: row.get("saved")?,
-                    title: row.get("title")?,
-                    transcription_text: row.get("transcription_text")?,
-                })
+        let mut entries = Vec::new();
+        for row in rows {
+            entries.push(row?);
+        }
+
+        let next = if entries.len() > query.limit as usize {
+            entries.truncate(query.limit as usize);
+            entries.last().map(|entry| HistoryCursor {
+                timestamp: entry.timestamp,
+                id: entry.id,
             })
-            .optional()?;
+        } else {
+            None
+        };
 
-        Ok(entry)
+        Ok(HistoryPage { entries, next })
     }
 
-    pub async fn toggle_saved_status(&self, id: i64) -> Result<()> {
+    /// Looks up entries named in a `history-updated` event; missing ids are
+    /// skipped.
+    pub fn get_entries_by_ids(&self, ids: &[i64]) -> Result<Vec<HistoryEntry>> {
         let conn = self.get_connection()?;
+        let mut stmt = conn.prepare(&format!(
+            "SELECT {ENTRY_COLUMNS} FROM transcription_history WHERE id = ?1"
+        ))?;
 
-        // Get current saved status
-        let current_saved: bool = conn.query_row(
-            "SELECT saved FROM transcription_history WHERE id = ?1",
-            params![id],
-            |row| row.get("saved"),
-        )?;
-
-        let new_saved = !current_saved;
-
-        conn.execute(
-            "UPDATE transcription_history SET saved = ?1 WHERE id = ?2",
-            params![new_saved, id],
-        )?;
-
-        debug!("Toggled saved status for entry {}: {}", id, new_saved);
+        let mut entries = Vec::new();
+        for id in ids {
+            if let Some(entry) = stmt.query_row([id], entry_from_row).optional()? {
+                entries.push(entry);
+            }
+        }
+        Ok(entries)
+    }
 
-        // Emit history updated event
-        if let Err(e) = self.app_handle.emit("history-updated", ()) {
-            error!("Failed to emit history-updated event: {}", e);
+    /// Ids of every entry matching the filters of `query`, newest first. Its
+    /// cursor and limit are ignored.
+    pub fn entry_ids_matching(&self, query: &HistoryPageQuery) -> Result<Vec<i64>> {
+        let conn = self.get_connection()?;
+        let mut page_query = query.clone();
+        page_query.before = None;
+        page_query.limit = 500;
+
+        let mut ids = Vec::new();
+        loop {
+            let page = Self::get_history_page_with_conn(&conn, &page_query)?;
+            // Entries without audio have nothing to re-transcribe
+            ids.extend(
+                page.entries
+                    .iter()
+                    .filter(|entry| !entry.audio_pruned)
+                    .map(|entry| entry.id),
+            );
+            match page.next {
+                Some(cursor) => page_query.before = Some(cursor),
+                None => return Ok(ids),
+            }
         }
+    }
 
-        Ok(())
+    /// Words dictated, speaking rate and time saved over a date range.
+    pub fn usage_stats(&self, query: &UsageStatsQuery) -> Result<UsageStats> {
+        let conn = self.get_connection()?;
+        let rows = Self::usage_rows_with_conn(&conn, query)?;
+        Ok(stats::aggregate(
+            &rows,
+            query.typing_wpm.unwrap_or(stats::DEFAULT_TYPING_WPM),
+        ))
+    }
+
+    fn usage_rows_with_conn(conn: &Connection, query: &UsageStatsQuery) -> Result<Vec<UsageRow>> {
+        // Recovered entries that haven't been transcribed have nothing to count
+        let mut stmt = conn.prepare(
+            "SELECT timestamp, COALESCE(post_processed_text, transcription_text), model_id,
+                    language, audio_duration_ms, speech_duration_ms
+             FROM transcription_history
+             WHERE recovered = 0
+               AND (?1 IS NULL OR timestamp >= ?1)
+               AND (?2 IS NULL OR timestamp < ?2)
+             ORDER BY timestamp",
+        )?;
+        let rows = stmt
+            .query_map(params![query.from, query.to], |row| {
+                Ok(UsageRow {
+                    timestamp: row.get(0)?,
+                    text: row.get(1)?,
+                    model_id: row.get(2)?,
+                    language: row.get(3)?,
+                    audio_duration_ms: row.get(4)?,
+                    speech_duration_ms: row.get(5)?,
+                })
+            })?
+            .collect::<Result<Vec<_>, _>>()?;
+        Ok(rows)
+    }
+
+    /// Ranked full-text search over titles and transcripts.
+    pub fn search(&self, query: &HistorySearchQuery) -> Result<HistorySearchResults> {
+        let conn = self.get_connection()?;
+        Self::search_with_conn(&conn, query)
+    }
+
+    fn search_with_conn(
+        conn: &Connection,
+        query: &HistorySearchQuery,
+    ) -> Result<HistorySearchResults> {
+        let Some(match_expr) = fts_query(&query.query) else {
+            return Ok(HistorySearchResults {
+                hits: Vec::new(),
+                total: 0,
+            });
+        };
+
+        let filters = "transcription_history_fts MATCH ?1
+             AND (?2 IS NULL OR h.timestamp >= ?2)
+             AND (?3 IS NULL OR h.timestamp < ?3)
+             AND (?4 = 0 OR h.saved = 1)
+             AND (?5 IS NULL OR EXISTS (SELECT 1 FROM entry_tags
+                  WHERE entry_id = h.id AND tag_id = ?5))";
+
+        let total: u32 = conn.query_row(
+            &format!(
+                "SELECT COUNT(*) FROM transcription_history_fts
+                 JOIN transcription_history h ON h.id = transcription_history_fts.rowid
+                 WHERE {filters}"
+            ),
+            params![
+                match_expr,
+                query.from,
+                query.to,
+                query.saved_only,
+                query.tag_id
+            ],
+            |row| row.get(0),
+        )?;
+
+        // Titles are generated from the date, so they count for less
+        let mut stmt = conn.prepare(&format!(
+            "SELECT h.*,
+                    (SELECT group_concat(tag_id) FROM entry_tags WHERE entry_id = h.id) AS tag_ids,
+                    snippet(transcription_history_fts, -1, char(2), char(3), '…', 16) AS snippet
+             FROM transcription_history_fts
+             JOIN transcription_history h ON h.id = transcription_history_fts.rowid
+             WHERE {filters}
+             ORDER BY bm25(transcription_history_fts, 0.2, 1.0, 1.0), h.timestamp DESC
+             LIMIT ?6 OFFSET ?7"
+        ))?;
+
+        let rows = stmt.query_map(
+            params![
+                match_expr,
+                query.from,
+                query.to,
+                query.saved_only,
+                query.tag_id,
+                query.limit,
+                query.offset
+            ],
+            |row| {
+                Ok(HistorySearchHit {
+                    entry: entry_from_row(row)?,
+                    snippet: parse_snippet(&row.get::<_, String>("snippet")?),
+                })
+            },
+        )?;
+
+        let mut hits = Vec::new();
+        for row in rows {
+            hits.push(row?);
+        }
+
+        Ok(HistorySearchResults { hits, total })
+    }
+
+    pub fn get_latest_entry(&self) -> Result<Option<HistoryEntry>> {
+        let conn = self.get_connection()?;
+        Self::get_latest_entry_with_conn(&conn)
+    }
+
+    fn get_latest_entry_with_conn(conn: &Connection) -> Result<Option<HistoryEntry>> {
+        let mut stmt = conn.prepare(&format!(
+            "SELECT {ENTRY_COLUMNS} FROM transcription_history ORDER BY timestamp DESC LIMIT 1"
+        ))?;
+
+        let entry = stmt.query_row([], entry_from_row).optional()?;
+
+        Ok(entry)
+    }
+
+    pub async fn toggle_saved_status(&self, id: i64) -> Result<()> {
+        let conn = self.get_connection()?;
+
+        // Get current saved status
+        let current_saved: bool = conn.query_row(
+            "SELECT saved FROM transcription_history WHERE id = ?1",
+            params![id],
+            |row| row.get("saved"),
+        )?;
+
+        let new_saved = !current_saved;
+
+        conn.execute(
+            "UPDATE transcription_history SET saved = ?1 WHERE id = ?2",
+            params![new_saved, id],
+        )?;
+
+        debug!("Toggled saved status for entry {}: {}", id, new_saved);
+
+        self.emit_history_updated(HistoryUpdate {
+            updated: vec![id],
+            ..Default::default()
+        });
+
+        Ok(())
+    }
+
+    /// Replaces an entry's transcript with a hand edit, keeping the model's
+    /// output so the edit can be reverted. Returns the corrections the edit
+    /// newly makes, counted across all entries.
+    pub fn update_entry_text(&self, id: i64, text: String) -> Result<Vec<LearnedCorrection>> {
+        let mut conn = self.get_connection()?;
+        let learned = Self::update_entry_text_with_conn(&mut conn, id, &text)?;
+
+        self.emit_history_updated(HistoryUpdate {
+            updated: vec![id],
+            ..Default::default()
+        });
+
+        Ok(learned)
+    }
+
+    fn update_entry_text_with_conn(
+        conn: &mut Connection,
+        id: i64,
+        text: &str,
+    ) -> Result<Vec<LearnedCorrection>> {
+        let tx = conn.transaction()?;
+        let (current, original): (String, Option<String>) = tx
+            .query_row(
+                "SELECT transcription_text, original_text FROM transcription_history WHERE id = ?1",
+                params![id],
+                |row| Ok((row.get(0)?, row.get(1)?)),
+            )
+            .optional()?
+            .ok_or_else(|| anyhow::anyhow!("History entry {} not found", id))?;
+        let original = original.unwrap_or_else(|| current.clone());
+
+        tx.execute(
+            "UPDATE transcription_history SET transcription_text = ?1, original_text = ?2 WHERE id = ?3",
+            params![text, (text != original).then_some(&original), id],
+        )?;
+
+        // Each entry counts a correction once, however often it is re-edited
+        let before = word_substitutions(&original, &current);
+        let after = word_substitutions(&original, text);
+        for undone in before.iter().filter(|sub| !after.contains(sub)) {
+            tx.execute(
+                "UPDATE learned_corrections SET count = count - 1 WHERE original = ?1 AND replacement = ?2",
+                params![undone.original, undone.replacement],
+            )?;
+        }
+        let mut learned = Vec::new();
+        for made in after.iter().filter(|sub| !before.contains(sub)) {
+            let count = tx.query_row(
+                "INSERT INTO learned_corrections (original, replacement, count) VALUES (?1, ?2, 1)
+                 ON CONFLICT (original, replacement) DO UPDATE SET count = count + 1
+                 RETURNING count",
+                params![made.original, made.replacement],
+                |row| row.get(0),
+            )?;
+            learned.push(LearnedCorrection {
+                original: made.original.clone(),
+                replacement: made.replacement.clone(),
+                count,
+            });
+        }
+        tx.execute("DELETE FROM learned_corrections WHERE count <= 0", [])?;
+        tx.commit()?;
+
+        Ok(learned)
+    }
+
+    /// Restores the model's transcript of an edited entry.
+    pub fn revert_entry_text(&self, id: i64) -> Result<()> {
+        let original: Option<String> = self
+            .get_connection()?
+            .query_row(
+                "SELECT original_text FROM transcription_history WHERE id = ?1",
+                params![id],
+                |row| row.get(0),
+            )
+            .optional()?
+            .flatten();
+        if let Some(original) = original {
+            self.update_entry_text(id, original)?;
+        }
+        Ok(())
+    }
+
+    /// Stores another transcript of an entry's recording next to its own.
+    pub fn add_alternative_transcript(
+        &self,
+        entry_id: i64,
+        model_id: &str,
+        language: &str,
+        output: &crate::managers::transcription::TranscriptionOutput,
+    ) -> Result<i64> {
+        let conn = self.get_connection()?;
+        conn.execute(
+            "INSERT INTO alternative_transcripts (entry_id, timestamp, model_id, engine, language, transcription_text, inference_ms)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7)",
+            params![
+                entry_id,
+                Utc::now().timestamp(),
+                model_id,
+                output.engine,
+                language,
+                output.text,
+                output.inference.as_millis() as u64
+            ],
+        )?;
+        let id = conn.last_insert_rowid();
+
+        self.emit_history_updated(HistoryUpdate {
+            updated: vec![entry_id],
+            ..Default::default()
+        });
+
+        Ok(id)
+    }
+
+    /// An entry's alternative transcripts, newest first.
+    pub fn get_alternative_transcripts(&self, entry_id: i64) -> Result<Vec<AlternativeTranscript>> {
+        let conn = self.get_connection()?;
+        let mut stmt = conn.prepare(
+            "SELECT id, entry_id, timestamp, model_id, engine, language, transcription_text, inference_ms
+             FROM alternative_transcripts WHERE entry_id = ?1 ORDER BY timestamp DESC, id DESC",
+        )?;
+        let rows = stmt.query_map([entry_id], |row| {
+            Ok(AlternativeTranscript {
+                id: row.get("id")?,
+                entry_id: row.get("entry_id")?,
+                timestamp: row.get("timestamp")?,
+                model_id: row.get("model_id")?,
+                engine: row.get("engine")?,
+                language: row.get("language")?,
+                transcription_text: row.get("transcription_text")?,
+                inference_ms: row.get("inference_ms")?,
+            })
+        })?;
+
+        let mut alternatives = Vec::new();
+        for row in rows {
+            alternatives.push(row?);
+        }
+        Ok(alternatives)
+    }
+
+    pub fn get_tags(&self) -> Result<Vec<HistoryTag>> {
+        let conn = self.get_connection()?;
+        Self::get_tags_with_conn(&conn)
+    }
+
+    fn get_tags_with_conn(conn: &Connection) -> Result<Vec<HistoryTag>> {
+        let mut stmt = conn.prepare(
+            "SELECT t.id, t.name, t.retention_days, COUNT(et.entry_id)
+             FROM tags t LEFT JOIN entry_tags et ON et.tag_id = t.id
+             GROUP BY t.id ORDER BY t.name COLLATE NOCASE",
+        )?;
+        let rows = stmt
+            .query_map([], tag_from_row)?
+            .collect::<Result<Vec<_>, _>>()?;
+        Ok(rows)
+    }
+
+    /// Tags an entry, creating the tag when no tag has that name yet.
+    pub fn tag_entry(&self, entry_id: i64, name: &str) -> Result<HistoryTag> {
+        let conn = self.get_connection()?;
+        let tag = Self::tag_entry_with_conn(&conn, entry_id, name)?;
+
+        self.emit_history_updated(HistoryUpdate {
+            updated: vec![entry_id],
+            ..Default::default()
+        });
+
+        Ok(tag)
+    }
+
+    fn tag_entry_with_conn(conn: &Connection, entry_id: i64, name: &str) -> Result<HistoryTag> {
+        let name = name.trim();
+        if name.is_empty() {
+            anyhow::bail!("Tag name is empty");
+        }
+        ensure_entry_exists(conn, entry_id)?;
+        let tag_id = attach_tag(conn, entry_id, name)?;
+
+        Ok(conn.query_row(
+            "SELECT t.id, t.name, t.retention_days, COUNT(et.entry_id)
+             FROM tags t LEFT JOIN entry_tags et ON et.tag_id = t.id
+             WHERE t.id = ?1 GROUP BY t.id",
+            params![tag_id],
+            tag_from_row,
+        )?)
+    }
+
+    pub fn untag_entry(&self, entry_id: i64, tag_id: i64) -> Result<()> {
+        let conn = self.get_connection()?;
+        let removed = conn.execute(
+            "DELETE FROM entry_tags WHERE entry_id = ?1 AND tag_id = ?2",
+            params![entry_id, tag_id],
+        )?;
+
+        if removed > 0 {
+            self.emit_history_updated(HistoryUpdate {
+                updated: vec![entry_id],
+                ..Default::default()
+            });
+        }
+        Ok(())
+    }
+
+    /// Sets how many days entries with this tag are kept, or keeps them like
+    /// saved entries when `None`.
+    pub fn set_tag_retention(&self, tag_id: i64, retention_days: Option<u32>) -> Result<()> {
+        let conn = self.get_connection()?;
+        let updated = conn.execute(
+            "UPDATE tags SET retention_days = ?1 WHERE id = ?2",
+            params![retention_days, tag_id],
+        )?;
+        if updated == 0 {
+            anyhow::bail!("Tag {} not found", tag_id);
+        }
+        Ok(())
+    }
+
+    /// Deletes a tag and takes it off every entry.
+    pub fn delete_tag(&self, tag_id: i64) -> Result<()> {
+        let conn = self.get_connection()?;
+        let mut stmt = conn.prepare("SELECT entry_id FROM entry_tags WHERE tag_id = ?1")?;
+        let entry_ids = stmt
+            .query_map(params![tag_id], |row| row.get(0))?
+            .collect::<Result<Vec<i64>, _>>()?;
+
+        conn.execute("DELETE FROM tags WHERE id = ?1", params![tag_id])?;
+
+        if !entry_ids.is_empty() {
+            self.emit_history_updated(HistoryUpdate {
+                updated: entry_ids,
+                ..Default::default()
+            });
+        }
+        Ok(())
+    }
+
+    /// Sets an entry's note; a blank note removes it.
+    pub fn set_entry_note(&self, entry_id: i64, note: Option<String>) -> Result<()> {
+        let note = note
+            .map(|note| note.trim().to_string())
+            .filter(|note| !note.is_empty());
+
+        let conn = self.get_connection()?;
+        let updated = conn.execute(
+            "UPDATE transcription_history SET note = ?1 WHERE id = ?2",
+            params![note, entry_id],
+        )?;
+        if updated == 0 {
+            anyhow::bail!("History entry {} not found", entry_id);
+        }
+
+        self.emit_history_updated(HistoryUpdate {
+            updated: vec![entry_id],
+            ..Default::default()
+        });
+        Ok(())
+    }
+
+    pub fn get_collections(&self) -> Result<Vec<HistoryCollection>> {
+        let conn = self.get_connection()?;
+        let mut stmt = conn.prepare(
+            "SELECT c.id, c.name, c.created_at, COUNT(ce.entry_id)
+             FROM collections c LEFT JOIN collection_entries ce ON ce.collection_id = c.id
+             GROUP BY c.id ORDER BY c.name COLLATE NOCASE",
+        )?;
+        let rows = stmt
+            .query_map([], |row| {
+                Ok(HistoryCollection {
+                    id: row.get(0)?,
+                    name: row.get(1)?,
+                    created_at: row.get(2)?,
+                    entry_count: row.get(3)?,
+                })
+            })?
+            .collect::<Result<Vec<_>, _>>()?;
+        Ok(rows)
+    }
+
+    pub fn create_collection(&self, name: &str) -> Result<HistoryCollection> {
+        let name = name.trim();
+        if name.is_empty() {
+            anyhow::bail!("Collection name is empty");
+        }
+
+        let conn = self.get_connection()?;
+        let created_at = Utc::now().timestamp();
+        conn.execute(
+            "INSERT INTO collections (name, created_at) VALUES (?1, ?2)",
+            params![name, created_at],
+        )
+        .map_err(|e| match e {
+            rusqlite::Error::SqliteFailure(err, _)
+                if err.code == rusqlite::ErrorCode::ConstraintViolation =>
+            {
+                anyhow::anyhow!("A collection named {} already exists", name)
+            }
+            e => e.into(),
+        })?;
+
+        Ok(HistoryCollection {
+            id: conn.last_insert_rowid(),
+            name: name.to_string(),
+            created_at,
+            entry_count: 0,
+        })
+    }
+
+    pub fn delete_collection(&self, collection_id: i64) -> Result<()> {
+        let conn = self.get_connection()?;
+        conn.execute(
+            "DELETE FROM collections WHERE id = ?1",
+            params![collection_id],
+        )?;
+        Ok(())
+    }
+
+    pub fn add_to_collection(&self, collection_id: i64, entry_ids: &[i64]) -> Result<()> {
+        let mut conn = self.get_connection()?;
+        let tx = conn.transaction()?;
+        let exists = tx
+            .query_row(
+                "SELECT 1 FROM collections WHERE id = ?1",
+                params![collection_id],
+                |_| Ok(()),
+            )
+            .optional()?;
+        if exists.is_none() {
+            anyhow::bail!("Collection {} not found", collection_id);
+        }
+        for entry_id in entry_ids {
+            ensure_entry_exists(&tx, *entry_id)?;
+            tx.execute(
+                "INSERT OR IGNORE INTO collection_entries (collection_id, entry_id) VALUES (?1, ?2)",
+                params![collection_id, entry_id],
+            )?;
+        }
+        tx.commit()?;
+        Ok(())
+    }
+
+    pub fn remove_from_collection(&self, collection_id: i64, entry_ids: &[i64]) -> Result<()> {
+        let conn = self.get_connection()?;
+        for entry_id in entry_ids {
+            conn.execute(
+                "DELETE FROM collection_entries WHERE collection_id = ?1 AND entry_id = ?2",
+                params![collection_id, entry_id],
+            )?;
+        }
+        Ok(())
+    }
+
+    /// Writes the whole history, with its recordings, to a tar.gz archive.
+    /// Encrypted recordings are decrypted first; the archive itself is not
+    /// encrypted.
+    pub fn export_archive(&self, path: &Path) -> Result<ExportSummary> {
+        let conn = self.get_connection()?;
+        let entries = Self::archived_entries_with_conn(&conn)?;
+        let archive = HistoryArchive {
+            version: backup::ARCHIVE_VERSION,
+            exported_at: Utc::now().timestamp(),
+            entries,
+        };
+
+        let mut writer = ArchiveWriter::create(path, &archive)?;
+        let mut recordings = 0;
+        for entry in &archive.entries {
+            let recording_path = self.get_audio_file_path(&entry.file_name);
+            if !recording_path.exists() {
+                continue;
+            }
+            let data = self.vault.unseal(fs::read(&recording_path)?)?;
+            writer.add_recording(&entry.file_name, &data)?;
+            recordings += 1;
+        }
+        writer.finish()?;
+
+        info!(
+            "Exported {} history entries and {} recordings to {:?}",
+            archive.entries.len(),
+            recordings,
+            path
+        );
+        Ok(ExportSummary {
+            entries: archive.entries.len(),
+            recordings,
+        })
+    }
+
+    fn archived_entries_with_conn(conn: &Connection) -> Result<Vec<ArchivedEntry>> {
+        let mut stmt = conn.prepare(&format!(
+            "SELECT {ENTRY_COLUMNS}, post_processed_text, post_process_prompt,
+                 (SELECT group_concat(t.name, char(31)) FROM entry_tags et
+                  JOIN tags t ON t.id = et.tag_id WHERE et.entry_id = transcription_history.id) AS tag_names,
+                 (SELECT group_concat(c.name, char(31)) FROM collection_entries ce
+                  JOIN collections c ON c.id = ce.collection_id
+                  WHERE ce.entry_id = transcription_history.id) AS collection_names
+             FROM transcription_history ORDER BY timestamp, id"
+        ))?;
+        let rows = stmt
+            .query_map([], |row| {
+                let entry = entry_from_row(row)?;
+                Ok(ArchivedEntry {
+                    file_name: entry.file_name,
+                    timestamp: entry.timestamp,
+                    saved: entry.saved,
+                    title: entry.title,
+                    transcription_text: entry.transcription_text,
+                    post_processed_text: row.get("post_processed_text")?,
+                    post_process_prompt: row.get("post_process_prompt")?,
+                    recovered: entry.recovered,
+                    metadata: entry.metadata,
+                    original_text: entry.original_text,
+                    note: entry.note,
+                    audio_pruned: entry.audio_pruned,
+                    tags: split_names(row.get("tag_names")?),
+                    collections: split_names(row.get("collection_names")?),
+                })
+            })?
+            .collect::<Result<Vec<_>, _>>()?;
+        Ok(rows)
+    }
+
+    /// Merges a history archive into this history. Entries already here,
+    /// matched by timestamp and text, are skipped but pick up the archive's
+    /// tags and collections.
+    pub fn import_archive(&self, path: &Path) -> Result<ImportReport> {
+        let staging_dir = self.recordings_dir.join(format!(
+            "{IMPORT_STAGING_PREFIX}{}",
+            Utc::now().timestamp_millis()
+        ));
+        let result = self.import_archive_from(path, &staging_dir);
+        if let Err(e) = fs::remove_dir_all(&staging_dir) {
+            if e.kind() != std::io::ErrorKind::NotFound {
+                error!("Failed to delete {:?}: {}", staging_dir, e);
+            }
+        }
+        result
+    }
+
+    fn import_archive_from(&self, path: &Path, staging_dir: &Path) -> Result<ImportReport> {
+        let archive = backup::read_archive(path, staging_dir, |data| self.vault.seal(data))?;
+        let mut conn = self.get_connection()?;
+        let mut report = ImportReport::default();
+        let mut update = HistoryUpdate::default();
+
+        for mut entry in archive.entries {
+            let staged = backup::is_plain_file_name(&entry.file_name)
+                .then(|| staging_dir.join(&entry.file_name))
+                .filter(|staged| staged.exists());
+            let archived_name = entry.file_name.clone();
+            // Without a recording there is nothing to play back
+            let archived_pruned = entry.audio_pruned;
+            entry.audio_pruned |= staged.is_none();
+            let mut conflict = None;
+
+            // Recordings go in under a name no other entry uses
+            if staged.is_some() {
+                let name = self.unused_file_name(&conn, &archived_name)?;
+                if name != archived_name {
+                    conflict = Some(ImportConflictKind::RecordingRenamed);
+                }
+                entry.file_name = name;
+            }
+
+            let tx = conn.transaction()?;
+            let outcome = Self::merge_entry_with_conn(&tx, &entry)?;
+            if let MergeOutcome::Inserted(_) = outcome {
+                if let Some(staged) = &staged {
+                    // Already sealed while staging
+                    fs::rename(staged, self.recordings_dir.join(&entry.file_name))?;
+                } else if !archived_pruned {
+                    conflict = Some(ImportConflictKind::RecordingMissing);
+                }
+            }
+            tx.commit()?;
+
+            match outcome {
+                MergeOutcome::Inserted(id) => {
+                    report.imported += 1;
+                    update.inserted.push(id);
+                }
+                MergeOutcome::Duplicate { id, differs } => {
+                    report.duplicates += 1;
+                    update.updated.push(id);
+                    conflict = differs.then_some(ImportConflictKind::ExistingDiffers);
+                }
+            }
+            if let Some(kind) = conflict {
+                report.conflicts.push(ImportConflict {
+                    timestamp: entry.timestamp,
+                    title: entry.title.clone(),
+                    kind,
+                });
+            }
+        }
+
+        info!(
+            "Imported {} history entries from {:?}; {} duplicates, {} conflicts",
+            report.imported,
+            path,
+            report.duplicates,
+            report.conflicts.len()
+        );
+        if !update.inserted.is_empty() || !update.updated.is_empty() {
+            self.emit_history_updated(update);
+        }
+        Ok(report)
+    }
+
+    /// Removes staging directories left behind by an import that crashed.
+    fn clear_import_staging(&self) {
+        let Ok(entries) = fs::read_dir(&self.recordings_dir) else {
+            return;
+        };
+        for entry in entries.flatten() {
+            let path = entry.path();
+            let is_staging = path
+                .file_name()
+                .and_then(|name| name.to_str())
+                .is_some_and(|name| name.starts_with(IMPORT_STAGING_PREFIX));
+            if is_staging && path.is_dir() {
+                if let Err(e) = fs::remove_dir_all(&path) {
+                    error!("Failed to delete {:?}: {}", path, e);
+                }
+            }
+        }
+    }
+
+    /// `file_name`, or a numbered variant of it when a recording or entry
+    /// already uses it.
+    fn unused_file_name(&self, conn: &Connection, file_name: &str) -> Result<String> {
+        let path = Path::new(file_name);
+        let stem = path
+            .file_stem()
+            .and_then(|stem| stem.to_str())
+            .unwrap_or("handy");
+        let extension = path
+            .extension()
+            .and_then(|ext| ext.to_str())
+            .unwrap_or_default();
+
+        let mut candidate = file_name.to_string();
+        for n in 1.. {
+            let in_use: bool = conn.query_row(
+                "SELECT EXISTS (SELECT 1 FROM transcription_history WHERE file_name = ?1)",
+                params![candidate],
+                |row| row.get(0),
+            )?;
+            if !in_use && !self.recordings_dir.join(&candidate).exists() {
+                break;
+            }
+            candidate = format!("{stem}-imported-{n}.{extension}");
+        }
+        Ok(candidate)
+    }
+
+    fn merge_entry_with_conn(conn: &Connection, entry: &ArchivedEntry) -> Result<MergeOutcome> {
+        let existing = conn
+            .query_row(
+                "SELECT id, saved, title, note, post_processed_text FROM transcription_history
+                 WHERE timestamp = ?1 AND transcription_text = ?2
+                 ORDER BY id LIMIT 1",
+                params![entry.timestamp, entry.transcription_text],
+                |row| {
+                    Ok((
+                        row.get::<_, i64>(0)?,
+                        row.get::<_, bool>(1)?,
+                        row.get::<_, String>(2)?,
+                        row.get::<_, Option<String>>(3)?,
+                        row.get::<_, Option<String>>(4)?,
+                    ))
+                },
+            )
+            .optional()?;
+
+        let outcome = match existing {
+            Some((id, saved, title, note, post_processed_text)) => MergeOutcome::Duplicate {
+                id,
+                differs: saved != entry.saved
+                    || title != entry.title
+                    || note != entry.note
+                    || post_processed_text != entry.post_processed_text,
+            },
+            None => {
+                let metadata = &entry.metadata;
+                conn.execute(
+                    "INSERT INTO transcription_history (
+                        file_name, timestamp, saved, title, transcription_text,
+                        post_processed_text, post_process_prompt, recovered, model_id, engine,
+                        language, translate, audio_duration_ms, speech_duration_ms,
+                        inference_ms, paste_method, binding_id, original_text, note,
+                        audio_pruned
+                     ) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12, ?13, ?14,
+                        ?15, ?16, ?17, ?18, ?19, ?20)",
+                    params![
+                        entry.file_name,
+                        entry.timestamp,
+                        entry.saved,
+                        entry.title,
+                        entry.transcription_text,
+                        entry.post_processed_text,
+                        entry.post_process_prompt,
+                        entry.recovered,
+                        metadata.model_id,
+                        metadata.engine,
+                        metadata.language,
+                        metadata.translate,
+                        metadata.audio_duration_ms,
+                        metadata.speech_duration_ms,
+                        metadata.inference_ms,
+                        metadata.paste_method,
+                        metadata.binding_id,
+                        entry.original_text,
+                        entry.note,
+                        entry.audio_pruned
+                    ],
+                )?;
+                MergeOutcome::Inserted(conn.last_insert_rowid())
+            }
+        };
+
+        let id = match outcome {
+            MergeOutcome::Inserted(id) | MergeOutcome::Duplicate { id, .. } => id,
+        };
+        for tag in entry
+            .tags
+            .iter()
+            .map(|tag| tag.trim())
+            .filter(|tag| !tag.is_empty())
+        {
+            attach_tag(conn, id, tag)?;
+        }
+        for collection in entry
+            .collections
+            .iter()
+            .map(|name| name.trim())
+            .filter(|name| !name.is_empty())
+        {
+            attach_collection(conn, id, collection)?;
+        }
+
+        Ok(outcome)
+    }
+
+    /// Corrections made by hand so far, most frequent first.
+    pub fn learned_corrections(&self) -> Result<Vec<LearnedCorrection>> {
+        let conn = self.get_connection()?;
+        let mut stmt = conn.prepare(
+            "SELECT original, replacement, count FROM learned_corrections ORDER BY count DESC, original",
+        )?;
+        let rows = stmt.query_map([], |row| {
+            Ok(LearnedCorrection {
+                original: row.get("original")?,
+                replacement: row.get("replacement")?,
+                count: row.get("count")?,
+            })
+        })?;
+
+        let mut corrections = Vec::new();
+        for row in rows {
+            corrections.push(row?);
+        }
+        Ok(corrections)
+    }
+
+    /// Turns journals of recordings that never made it into history into
+    /// untranscribed entries marked as recovered, announcing them with
+    /// `history-updated`.
+    fn recover_journals(&self) -> Result<()> {
+        let dir = self.recordings_dir.with_file_name(JOURNAL_DIR);
+        if !dir.exists() {
+            return Ok(());
+        }
+
+        let mut recovered = Vec::new();
+        for entry in fs::read_dir(&dir)? {
+            let path = entry?.path();
+            if path.extension().and_then(|ext| ext.to_str()) != Some("pcm") {
+                continue;
+            }
+            match self.recover_journal(&path) {
+                Ok(Some(id)) => {
+                    info!("Recovered recording from {:?}", path);
+                    recovered.push(id);
+                }
+                Ok(None) => debug!("Discarding empty journal {:?}", path),
+                Err(e) => {
+                    error!("Failed to recover {:?}: {}", path, e);
+                    continue;
+                }
+            }
+            if let Err(e) = fs::remove_file(&path) {
+                error!("Failed to delete journal {:?}: {}", path, e);
+            }
+        }
+
+        if !recovered.is_empty() {
+            self.emit_history_updated(HistoryUpdate {
+                inserted: recovered,
+                ..Default::default()
+            });
+        }
+        Ok(())
+    }
+
+    /// Returns the new entry's id, or `None` when the journal held no audio.
+    fn recover_journal(&self, path: &Path) -> Result<Option<i64>> {
+        let samples = read_journal(path)?;
+        if samples.is_empty() {
+            return Ok(None);
+        }
+
+        let timestamp = journal_timestamp(path).unwrap_or_else(|| Utc::now().timestamp());
+        let format = crate::settings::get_recording_file_format(&self.app_handle);
+        let file_name = format!("handy-{}-recovered.{}", timestamp, format.extension());
+        self.write_recording(&self.recordings_dir.join(&file_name), &samples, format)?;
+
+        let conn = self.get_connection()?;
+        conn.execute(
+            "INSERT INTO transcription_history (file_name, timestamp, saved, title, transcription_text, recovered) VALUES (?1, ?2, ?3, ?4, ?5, ?6)",
+            params![
+                file_name,
+                timestamp,
+                false,
+                format!("{} (recovered)", self.format_timestamp_title(timestamp)),
+                "",
+                true
+            ],
+        )?;
+        Ok(Some(conn.last_insert_rowid()))
+    }
+
+    /// Stores the transcription of a recovered entry and clears its flag.
+    pub fn complete_recovered_entry(
+        &self,
+        id: i64,
+        transcription_text: String,
+        metadata: &TranscriptionMetadata,
+    ) -> Result<()> {
+        let conn = self.get_connection()?;
+        conn.execute(
+            "UPDATE transcription_history SET
+                transcription_text = ?1,
+                recovered = 0,
+                model_id = ?2,
+                engine = ?3,
+                language = ?4,
+                translate = ?5,
+                speech_duration_ms = ?6,
+                inference_ms = ?7
+             WHERE id = ?8",
+            params![
+                transcription_text,
+                metadata.model_id,
+                metadata.engine,
+                metadata.language,
+                metadata.translate,
+                metadata.speech_duration_ms,
+                metadata.inference_ms,
+                id
+            ],
+        )?;
+
+        self.emit_history_updated(HistoryUpdate {
+            updated: vec![id],
+            ..Default::default()
+        });
+
+        Ok(())
     }
 
     pub fn get_audio_file_path(&self, file_name: &str) -> PathBuf {
         self.recordings_dir.join(file_name)
     }
 
-    pub async fn get_entry_by_id(&self, id: i64) -> Result<Option<HistoryEntry>> {
+    /// A recording's file contents for playback, decrypted in memory when
+    /// the history is encrypted.
+    pub fn read_recording_file(&self, file_name: &str) -> Result<Vec<u8>> {
+        if !backup::is_plain_file_name(file_name) {
+            anyhow::bail!("Invalid recording name: {}", file_name);
+        }
+        self.vault
+            .unseal(fs::read(self.get_audio_file_path(file_name))?)
+    }
+
+    pub fn encryption_state(&self) -> EncryptionState {
+        self.vault.state()
+    }
+
+    fn emit_encryption_changed(&self) {
+        if let Err(e) = self
+            .app_handle
+            .emit("history-encryption-changed", self.vault.state())
+        {
+            error!("Failed to emit history-encryption-changed event: {}", e);
+        }
+    }
+
+    /// Encrypts the database and every recording with a key derived from
+    /// `passphrase`, optionally kept in the OS keyring.
+    pub fn enable_encryption(&self, passphrase: &str, remember_key: bool) -> Result<()> {
+        self.while_rewriting_recordings(|| {
+            self.vault.enable(passphrase, remember_key)?;
+            self.emit_encryption_changed();
+            self.encrypt_existing_data()
+        })
+    }
+
+    /// Decrypts everything and turns encryption off; needs the passphrase
+    /// even while unlocked.
+    pub fn disable_encryption(&self, passphrase: &str) -> Result<()> {
+        self.while_rewriting_recordings(|| {
+            self.vault.unlock(passphrase)?;
+            self.decrypt_existing_data()?;
+            self.vault.disable()?;
+            self.emit_encryption_changed();
+            Ok(())
+        })
+    }
+
+    pub fn unlock(&self, passphrase: &str) -> Result<()> {
+        let was_locked = self.vault.status() == EncryptionStatus::Locked;
+        self.vault.unlock(passphrase)?;
+        if was_locked {
+            self.open_history()?;
+            self.save_pending_dictations();
+        }
+        self.emit_encryption_changed();
+        Ok(())
+    }
+
+    pub fn lock(&self) -> Result<()> {
+        if self.vault.status() == EncryptionStatus::Disabled {
+            anyhow::bail!("History encryption is not enabled");
+        }
+        self.vault.lock();
+        self.emit_encryption_changed();
+        Ok(())
+    }
+
+    pub fn set_remember_key(&self, remember_key: bool) -> Result<()> {
+        self.vault.set_remember_key(remember_key)?;
+        self.emit_encryption_changed();
+        Ok(())
+    }
+
+    fn while_rewriting_recordings(&self, f: impl FnOnce() -> Result<()>) -> Result<()> {
+        if self.converting.swap(true, Ordering::SeqCst) {
+            anyhow::bail!("Recordings are already being converted or encrypted");
+        }
+        let result = f();
+        self.converting.store(false, Ordering::SeqCst);
+        result
+    }
+
+    /// Encrypts whatever is still plaintext. Safe to run again after an
+    /// interruption: the database is swapped in whole, and recordings are
+    /// encrypted one file at a time.
+    fn encrypt_existing_data(&self) -> Result<()> {
+        let key = self
+            .vault
+            .key()
+            .ok_or_else(|| anyhow::anyhow!("History is locked"))?;
+
+        if encryption::is_plaintext_database(&self.db_path)? {
+            self.replace_database(
+                || Ok(Connection::open(&self.db_path)?),
+                &key.sqlcipher_attach_key(),
+            )?;
+            info!("Encrypted the history database");
+        }
+
+        let encrypted = self.rewrite_recordings(true, |data| key.seal(&data))?;
+        if encrypted > 0 {
+            info!("Encrypted {} recordings", encrypted);
+        }
+        Ok(())
+    }
+
+    fn decrypt_existing_data(&self) -> Result<()> {
+        let key = self
+            .vault
+            .key()
+            .ok_or_else(|| anyhow::anyhow!("History is locked"))?;
+
+        if self.db_path.exists() && !encryption::is_plaintext_database(&self.db_path)? {
+            self.replace_database(|| self.open_connection(), "KEY ''")?;
+            info!("Decrypted the history database");
+        }
+
+        let decrypted = self.rewrite_recordings(false, |data| key.unseal(&data))?;
+        if decrypted > 0 {
+            info!("Decrypted {} recordings", decrypted);
+        }
+        Ok(())
+    }
+
+    /// Copies the database, opened by `open`, into a new file keyed by
+    /// `key_clause`, then swaps it in. Other connections are waited out and
+    /// held back meanwhile: no write may land in the old file after the copy,
+    /// and Windows can't replace a file that is open.
+    fn replace_database(
+        &self,
+        open: impl FnOnce() -> Result<Connection>,
+        key_clause: &str,
+    ) -> Result<()> {
+        self.db_gate.exclusive(|| {
+            let temp_path = self.db_path.with_extension("db.tmp");
+            if temp_path.exists() {
+                fs::remove_file(&temp_path)?;
+            }
+
+            let conn = open()?;
+            let version: i32 = conn.pragma_query_value(None, "user_version", |row| row.get(0))?;
+            let target = temp_path.to_string_lossy().replace('\'', "''");
+            conn.execute(
+                &format!("ATTACH DATABASE '{target}' AS export {key_clause}"),
+                [],
+            )?;
+            conn.query_row("SELECT sqlcipher_export('export')", [], |_| Ok(()))?;
+            conn.execute_batch(&format!("PRAGMA export.user_version = {version};"))?;
+            conn.execute("DETACH DATABASE export", [])?;
+            conn.close().map_err(|(_, e)| e)?;
+
+            fs::rename(&temp_path, &self.db_path)?;
+            Ok(())
+        })
+    }
+
+    /// Rewrites every recording that isn't yet in the wanted state, returning
+    /// how many were. Fails if any recording couldn't be rewritten, so
+    /// encryption isn't turned off with files still sealed.
+    fn rewrite_recordings(
+        &self,
+        sealed: bool,
+        rewrite: impl Fn(Vec<u8>) -> Result<Vec<u8>>,
+    ) -> Result<usize> {
+        let mut rewritten = 0;
+        let mut failed = 0;
+
+        for entry in fs::read_dir(&self.recordings_dir)? {
+            let path = entry?.path();
+            let is_temp_file = matches!(
+                path.extension().and_then(|ext| ext.to_str()),
+                Some("tmp" | "crypt")
+            );
+            if !path.is_file() || is_temp_file || encryption::is_sealed_file(&path)? == sealed {
+                continue;
+            }
+
+            let result = fs::read(&path)
+                .map_err(anyhow::Error::from)
+                .and_then(&rewrite)
+                .and_then(|data| {
+                    let mut temp_path = path.clone().into_os_string();
+                    temp_path.push(".crypt");
+                    fs::write(&temp_path, data)?;
+                    fs::rename(&temp_path, &path)?;
+                    Ok(())
+                });
+            match result {
+                Ok(()) => rewritten += 1,
+                Err(e) => {
+                    error!("Failed to rewrite recording {:?}: {}", path, e);
+                    failed += 1;
+                }
+            }
+        }
+
+        if failed > 0 {
+            anyhow::bail!("{} recordings could not be rewritten", failed);
+        }
+        Ok(rewritten)
+    }
+
+    pub fn is_converting(&self) -> bool {
+        self.converting.load(Ordering::SeqCst)
+    }
+
+    /// Re-encode existing recordings into `format`, updating their history
+    /// entries as it goes. Opus recordings are left alone, since re-encoding
+    /// lossy audio only loses more detail.
+    pub fn convert_recordings(
+        &self,
+        format: AudioFileFormat,
+    ) -> Result<RecordingConversionSummary> {
+        if self.converting.swap(true, Ordering::SeqCst) {
+            anyhow::bail!("A recording conversion is already running");
+        }
+
+        let mut converted = Vec::new();
+        let result = self.convert_recordings_inner(format, &mut converted);
+        self.converting.store(false, Ordering::SeqCst);
+
+        if !converted.is_empty() {
+            self.emit_history_updated(HistoryUpdate {
+                updated: converted,
+                ..Default::default()
+            });
+        }
+
+        result
+    }
+
+    fn convert_recordings_inner(
+        &self,
+        format: AudioFileFormat,
+        converted: &mut Vec<i64>,
+    ) -> Result<RecordingConversionSummary> {
         let conn = self.get_connection()?;
-        let mut stmt = conn.prepare(
-            "SELECT id, file_name, timestamp, saved, title, transcription_text
-             FROM transcription_history WHERE id = ?1",
+        let mut stmt = conn.prepare("SELECT id, file_name FROM transcription_history")?;
+        let rows = stmt.query_map([], |row| {
+            Ok((row.get::<_, i64>("id")?, row.get::<_, String>("file_name")?))
+        })?;
+
+        let mut entries: Vec<(i64, String)> = Vec::new();
+        for row in rows {
+            let (id, file_name) = row?;
+            if needs_conversion(&file_name, format) {
+                entries.push((id, file_name));
+            }
+        }
+
+        let total = entries.len();
+        let mut summary = RecordingConversionSummary::default();
+        info!("Converting {} recordings to {:?}", total, format);
+
+        for (processed, (id, file_name)) in entries.iter().enumerate() {
+            match self.convert_recording(&conn, *id, file_name, format) {
+                Ok(true) => {
+                    summary.converted += 1;
+                    converted.push(*id);
+                }
+                Ok(false) => summary.skipped += 1,
+                Err(e) => {
+                    error!("Failed to convert recording {}: {}", file_name, e);
+                    summary.failed += 1;
+                }
+            }
+
+            let progress = RecordingConversionProgress {
+                processed: processed + 1,
+                total,
+            };
+            if let Err(e) = self
+                .app_handle
+                .emit("recording-conversion-progress", progress)
+            {
+                error!("Failed to emit recording-conversion-progress event: {}", e);
+            }
+        }
+
+        Ok(summary)
+    }
+
+    /// Returns `false` when the recording was skipped.
+    fn convert_recording(
+        &self,
+        conn: &Connection,
+        id: i64,
+        file_name: &str,
+        format: AudioFileFormat,
+    ) -> Result<bool> {
+        let source_path = self.recordings_dir.join(file_name);
+        if !source_path.exists() {
+            return Ok(false);
+        }
+
+        let (samples, sample_rate) = self.read_recording(&source_path)?;
+        if sample_rate != constants::WHISPER_SAMPLE_RATE {
+            debug!("Skipping {} recorded at {} Hz", file_name, sample_rate);
+            return Ok(false);
+        }
+
+        let new_file_name = Path::new(file_name)
+            .with_extension(format.extension())
+            .to_string_lossy()
+            .into_owned();
+        let new_path = self.recordings_dir.join(&new_file_name);
+        self.write_recording(&new_path, &samples, format)?;
+
+        // The entry may have been deleted while we were encoding
+        let updated = conn.execute(
+            "UPDATE transcription_history SET file_name = ?1 WHERE id = ?2 AND file_name = ?3",
+            params![new_file_name, id, file_name],
         )?;
+        if updated == 0 {
+            let _ = fs::remove_file(&new_path);
+            return Ok(false);
+        }
 
-        let entry = stmt
-            .query_row([id], |row| {
-                Ok(HistoryEntry {
-                    id: row.get("id")?,
-                    file_name: row.get("file_name")?,
-                    timestamp: row.get("timestamp")?,
-                    saved: row.get("saved")?,
-                    title: row.get("title")?,
-                    transcription_text: row.get("transcription_text")?,
-                })
-            })
-            .optional()?;
+        if let Err(e) = fs::remove_file(&source_path) {
+            error!("Failed to delete converted recording {}: {}", file_name, e);
+        }
+        debug!("Converted {} to {}", file_name, new_file_name);
+        Ok(true)
+    }
+
+    pub async fn get_entry_by_id(&self, id: i64) -> Result<Option<HistoryEntry>> {
+        let conn = self.get_connection()?;
+        let mut stmt = conn.prepare(&format!(
+            "SELECT {ENTRY_COLUMNS} FROM transcription_history WHERE id = ?1"
+        ))?;
+
+        let entry = stmt.query_row([id], entry_from_row).optional()?;
 
         Ok(entry)
     }
@@ -470,10 +2479,10 @@ impl HistoryManager {
 
         debug!("Deleted history entry with id: {}", id);
 
-        // Emit history updated event
-        if let Err(e) = self.app_handle.emit("history-updated", ()) {
-            error!("Failed to emit history-updated event: {}", e);
-        }
+        self.emit_history_updated(HistoryUpdate {
+            deleted: vec![id],
+            ..Default::default()
+        });
 
         Ok(())
     }
@@ -489,27 +2498,364 @@ impl HistoryManager {
     }
 }
 
+fn entry_from_row(row: &rusqlite::Row) -> rusqlite::Result<HistoryEntry> {
+    Ok(HistoryEntry {
+        id: row.get("id")?,
+        file_name: row.get("file_name")?,
+        timestamp: row.get("timestamp")?,
+        saved: row.get("saved")?,
+        title: row.get("title")?,
+        transcription_text: row.get("transcription_text")?,
+        recovered: row.get("recovered")?,
+        metadata: TranscriptionMetadata {
+            model_id: row.get("model_id")?,
+            engine: row.get("engine")?,
+            language: row.get("language")?,
+            translate: row.get("translate")?,
+            audio_duration_ms: row.get("audio_duration_ms")?,
+            speech_duration_ms: row.get("speech_duration_ms")?,
+            inference_ms: row.get("inference_ms")?,
+            paste_method: row.get("paste_method")?,
+            binding_id: row.get("binding_id")?,
+        },
+        original_text: row.get("original_text")?,
+        note: row.get("note")?,
+        tags: parse_tag_ids(row.get("tag_ids")?),
+        audio_pruned: row.get("audio_pruned")?,
+    })
+}
+
+/// Tags an entry by name, creating the tag if needed. Returns the tag's id.
+fn attach_tag(conn: &Connection, entry_id: i64, name: &str) -> Result<i64> {
+    conn.execute(
+        "INSERT INTO tags (name) VALUES (?1) ON CONFLICT(name) DO NOTHING",
+        params![name],
+    )?;
+    let tag_id: i64 = conn.query_row(
+        "SELECT id FROM tags WHERE name = ?1",
+        params![name],
+        |row| row.get(0),
+    )?;
+    conn.execute(
+        "INSERT OR IGNORE INTO entry_tags (entry_id, tag_id) VALUES (?1, ?2)",
+        params![entry_id, tag_id],
+    )?;
+    Ok(tag_id)
+}
+
+/// Adds an entry to a collection by name, creating the collection if needed.
+fn attach_collection(conn: &Connection, entry_id: i64, name: &str) -> Result<()> {
+    conn.execute(
+        "INSERT INTO collections (name, created_at) VALUES (?1, ?2) ON CONFLICT(name) DO NOTHING",
+        params![name, Utc::now().timestamp()],
+    )?;
+    conn.execute(
+        "INSERT OR IGNORE INTO collection_entries (collection_id, entry_id)
+         SELECT id, ?2 FROM collections WHERE name = ?1",
+        params![name, entry_id],
+    )?;
+    Ok(())
+}
+
+/// What merging an archived entry into the history did.
+#[derive(Debug, PartialEq, Eq)]
+enum MergeOutcome {
+    Inserted(i64),
+    /// Already in history; `differs` when its details don't match.
+    Duplicate {
+        id: i64,
+        differs: bool,
+    },
+}
+
+fn tag_from_row(row: &rusqlite::Row) -> rusqlite::Result<HistoryTag> {
+    Ok(HistoryTag {
+        id: row.get(0)?,
+        name: row.get(1)?,
+        retention_days: row.get(2)?,
+        entry_count: row.get(3)?,
+    })
+}
+
+fn ensure_entry_exists(conn: &Connection, entry_id: i64) -> Result<()> {
+    conn.query_row(
+        "SELECT 1 FROM transcription_history WHERE id = ?1",
+        params![entry_id],
+        |_| Ok(()),
+    )
+    .optional()?
+    .ok_or_else(|| anyhow::anyhow!("History entry {} not found", entry_id))
+}
+
+/// Total size of the recordings in `dir`.
+fn recordings_size(dir: &Path) -> Result<u64> {
+    let mut size = 0;
+    for entry in fs::read_dir(dir)? {
+        let metadata = entry?.metadata()?;
+        if metadata.is_file() {
+            size += metadata.len();
+        }
+    }
+    Ok(size)
+}
+
+/// Splits names joined by `group_concat` with a unit separator.
+fn split_names(names: Option<String>) -> Vec<String> {
+    let mut names: Vec<String> = names
+        .iter()
+        .flat_map(|names| names.split('\u{1f}'))
+        .map(str::to_string)
+        .collect();
+    names.sort();
+    names
+}
+
+/// Reads the comma separated ids `group_concat` makes, in ascending order.
+fn parse_tag_ids(ids: Option<String>) -> Vec<i64> {
+    let mut ids: Vec<i64> = ids
+        .iter()
+        .flat_map(|ids| ids.split(','))
+        .filter_map(|id| id.parse().ok())
+        .collect();
+    ids.sort_unstable();
+    ids
+}
+
+/// Turns what the user typed into an FTS5 query that matches entries holding
+/// every word, the last one as a prefix so results update while typing. Each
+/// word is quoted so punctuation can't be read as query syntax.
+fn fts_query(input: &str) -> Option<String> {
+    let terms: Vec<String> = input
+        .split_whitespace()
+        .map(|word| format!("\"{}\"", word.replace('"', "\"\"")))
+        .collect();
+    if terms.is_empty() {
+        return None;
+    }
+    Some(format!("{}*", terms.join(" ")))
+}
+
+/// Splits a snippet produced with `char(2)`/`char(3)` around matches.
+fn parse_snippet(snippet: &str) -> Vec<SnippetPart> {
+    let mut parts = Vec::new();
+    let mut highlighted = false;
+    for (i, text) in snippet.split(['\u{2}', '\u{3}']).enumerate() {
+        // Markers alternate, starting with an opening one
+        if i > 0 {
+            highlighted = !highlighted;
+        }
+        if !text.is_empty() {
+            parts.push(SnippetPart {
+                text: text.to_string(),
+                highlighted,
+            });
+        }
+    }
+    parts
+}
+
+/// Directory next to `recordings` that holds crash-safe recording journals.
+const JOURNAL_DIR: &str = "journal";
+
+/// Prefix of the directories in `recordings` that imports stage into.
+const IMPORT_STAGING_PREFIX: &str = ".import-";
+
+/// How long swapping the database waits for open connections to close.
+const DATABASE_SWAP_TIMEOUT: std::time::Duration = std::time::Duration::from_secs(30);
+
+struct PendingDictation {
+    timestamp: i64,
+    audio_samples: Vec<f32>,
+    transcription_text: String,
+    metadata: TranscriptionMetadata,
+    append: bool,
+}
+
+#[derive(Default)]
+struct GateState {
+    open: usize,
+    exclusive: bool,
+}
+
+/// Counts open connections to the history database, so that it can be
+/// replaced with none open.
+#[derive(Default)]
+struct DatabaseGate {
+    state: Mutex<GateState>,
+    changed: Condvar,
+}
+
+impl DatabaseGate {
+    /// Waits out a swap in progress, then counts a connection as open until
+    /// the lease is dropped.
+    fn enter(self: &Arc<Self>) -> GateLease {
+        let state = self.state.lock().unwrap();
+        let mut state = self
+            .changed
+            .wait_while(state, |state| state.exclusive)
+            .unwrap();
+        state.open += 1;
+        GateLease(Arc::clone(self))
+    }
+
+    /// Runs `f` once every connection has closed, holding new ones back
+    /// until it returns. Gives up if connections stay open too long, which
+    /// includes one held by the caller.
+    fn exclusive<T>(&self, f: impl FnOnce() -> Result<T>) -> Result<T> {
+        let state = self.state.lock().unwrap();
+        let mut state = self
+            .changed
+            .wait_while(state, |state| state.exclusive)
+            .unwrap();
+        state.exclusive = true;
+        let (mut state, wait) = self
+            .changed
+            .wait_timeout_while(state, DATABASE_SWAP_TIMEOUT, |state| state.open > 0)
+            .unwrap();
+        if wait.timed_out() {
+            state.exclusive = false;
+            self.changed.notify_all();
+            anyhow::bail!("The history database is still in use");
+        }
+        drop(state);
+
+        let result = f();
+        self.state.lock().unwrap().exclusive = false;
+        self.changed.notify_all();
+        result
+    }
+}
+
+struct GateLease(Arc<DatabaseGate>);
+
+impl Drop for GateLease {
+    fn drop(&mut self) {
+        self.0.state.lock().unwrap().open -= 1;
+        self.0.changed.notify_all();
+    }
+}
+
+/// A database connection that keeps the database from being swapped while
+/// it's open.
+struct HistoryConnection {
+    // Declared first so the connection closes before the lease is released
+    conn: Connection,
+    _lease: GateLease,
+}
+
+impl std::ops::Deref for HistoryConnection {
+    type Target = Connection;
+
+    fn deref(&self) -> &Connection {
+        &self.conn
+    }
+}
+
+impl std::ops::DerefMut for HistoryConnection {
+    fn deref_mut(&mut self) -> &mut Connection {
+        &mut self.conn
+    }
+}
+
+/// Silence placed between a recording and audio appended to it.
+const APPEND_GAP: std::time::Duration = std::time::Duration::from_millis(500);
+
+fn append_recording(mut previous: Vec<f32>, addition: &[f32]) -> Vec<f32> {
+    if !previous.is_empty() {
+        let gap = (constants::WHISPER_SAMPLE_RATE as f64 * APPEND_GAP.as_secs_f64()) as usize;
+        previous.resize(previous.len() + gap, 0.0);
+    }
+    previous.extend_from_slice(addition);
+    previous
+}
+
+fn join_transcripts(previous: &str, addition: &str) -> String {
+    let previous = previous.trim_end();
+    let addition = addition.trim_start();
+    if previous.is_empty() {
+        addition.to_string()
+    } else if addition.is_empty() {
+        previous.to_string()
+    } else {
+        format!("{previous} {addition}")
+    }
+}
+
+pub fn journal_dir(app_handle: &AppHandle) -> Result<PathBuf> {
+    Ok(app_handle.path().app_data_dir()?.join(JOURNAL_DIR))
+}
+
+/// Deletes a journal once its recording is safely in history.
+pub fn discard_journal(path: Option<PathBuf>) {
+    if let Some(path) = path {
+        if let Err(e) = fs::remove_file(&path) {
+            if e.kind() != std::io::ErrorKind::NotFound {
+                error!("Failed to delete journal {:?}: {}", path, e);
+            }
+        }
+    }
+}
+
+/// Journals are named `journal-<unix millis>.pcm`.
+fn journal_timestamp(path: &Path) -> Option<i64> {
+    let millis: i64 = path
+        .file_stem()?
+        .to_str()?
+        .strip_prefix("journal-")?
+        .parse()
+        .ok()?;
+    Some(millis / 1000)
+}
+
+/// Whether a recording is lossless and not already stored as `format`.
+fn needs_conversion(file_name: &str, format: AudioFileFormat) -> bool {
+    let extension = Path::new(file_name)
+        .extension()
+        .and_then(|ext| ext.to_str())
+        .unwrap_or_default()
+        .to_ascii_lowercase();
+    matches!(extension.as_str(), "wav" | "flac") && extension != format.extension()
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
     use rusqlite::{params, Connection};
 
     fn setup_conn() -> Connection {
-        let conn = Connection::open_in_memory().expect("open in-memory db");
-        conn.execute_batch(
-            "CREATE TABLE transcription_history (
-                id INTEGER PRIMARY KEY AUTOINCREMENT,
-                file_name TEXT NOT NULL,
-                timestamp INTEGER NOT NULL,
-                saved BOOLEAN NOT NULL DEFAULT 0,
-                title TEXT NOT NULL,
-                transcription_text TEXT NOT NULL
-            );",
-        )
-        .expect("create transcription_history table");
+        let mut conn = Connection::open_in_memory().expect("open in-memory db");
+        Migrations::new(MIGRATIONS.to_vec())
+            .to_latest(&mut conn)
+            .expect("apply migrations");
         conn
     }
 
+    #[test]
+    fn database_swap_waits_for_open_connections() {
+        let gate = Arc::new(DatabaseGate::default());
+        let lease = gate.enter();
+        let closed = Arc::new(AtomicBool::new(false));
+
+        let closer = {
+            let closed = Arc::clone(&closed);
+            std::thread::spawn(move || {
+                std::thread::sleep(std::time::Duration::from_millis(50));
+                closed.store(true, Ordering::SeqCst);
+                drop(lease);
+            })
+        };
+        gate.exclusive(|| {
+            assert!(closed.load(Ordering::SeqCst));
+            Ok(())
+        })
+        .unwrap();
+        closer.join().unwrap();
+
+        // Connections open again once the swap is done
+        drop(gate.enter());
+        assert_eq!(gate.state.lock().unwrap().open, 0);
+    }
+
     fn insert_entry(conn: &Connection, timestamp: i64, text: &str) {
         conn.execute(
             "INSERT INTO transcription_history (file_name, timestamp, saved, title, transcription_text)
@@ -545,4 +2891,582 @@ mod tests {
         assert_eq!(entry.timestamp, 200);
         assert_eq!(entry.transcription_text, "second");
     }
+
+    fn page_query(limit: u32) -> HistoryPageQuery {
+        HistoryPageQuery {
+            before: None,
+            limit,
+            saved_only: false,
+            model_id: None,
+            language: None,
+            from: None,
+            to: None,
+            tag_id: None,
+            collection_id: None,
+        }
+    }
+
+    #[test]
+    fn history_pages_continue_from_the_cursor() {
+        let conn = setup_conn();
+        // Two entries share a timestamp, so the id breaks the tie
+        for timestamp in [100, 200, 200, 300, 400] {
+            insert_entry(&conn, timestamp, "text");
+        }
+
+        let mut query = page_query(2);
+        let mut pages = Vec::new();
+        loop {
+            let page = HistoryManager::get_history_page_with_conn(&conn, &query).unwrap();
+            pages.push(page.entries.iter().map(|e| e.id).collect::<Vec<_>>());
+            match page.next {
+                Some(cursor) => query.before = Some(cursor),
+                None => break,
+            }
+        }
+
+        assert_eq!(pages, vec![vec![5, 4], vec![3, 2], vec![1]]);
+    }
+
+    #[test]
+    fn history_pages_apply_filters() {
+        let conn = setup_conn();
+        for timestamp in [100, 200, 300, 400] {
+            insert_entry(&conn, timestamp, "text");
+        }
+        conn.execute_batch(
+            "UPDATE transcription_history SET model_id = 'small', language = 'en';
+             UPDATE transcription_history SET model_id = 'large', saved = 1 WHERE timestamp >= 300;
+             UPDATE transcription_history SET language = 'de' WHERE timestamp = 400;",
+        )
+        .unwrap();
+
+        let timestamps = |query: &HistoryPageQuery| -> Vec<i64> {
+            HistoryManager::get_history_page_with_conn(&conn, query)
+                .unwrap()
+                .entries
+                .iter()
+                .map(|e| e.timestamp)
+                .collect()
+        };
+
+        let mut query = page_query(10);
+        query.model_id = Some("large".to_string());
+        assert_eq!(timestamps(&query), vec![400, 300]);
+
+        query.language = Some("en".to_string());
+        assert_eq!(timestamps(&query), vec![300]);
+
+        let mut query = page_query(10);
+        query.saved_only = true;
+        query.to = Some(400);
+        assert_eq!(timestamps(&query), vec![300]);
+
+        let mut query = page_query(10);
+        query.from = Some(200);
+        query.to = Some(300);
+        assert_eq!(timestamps(&query), vec![200]);
+    }
+
+    fn search_query(query: &str) -> HistorySearchQuery {
+        HistorySearchQuery {
+            query: query.to_string(),
+            from: None,
+            to: None,
+            saved_only: false,
+            tag_id: None,
+            offset: 0,
+            limit: 50,
+        }
+    }
+
+    #[test]
+    fn search_follows_inserts_updates_and_deletes() {
+        let conn = setup_conn();
+        insert_entry(&conn, 100, "Send the invoice to accounting");
+        insert_entry(&conn, 200, "Lunch at noon");
+        insert_entry(&conn, 300, "The invoices are overdue");
+
+        let results = HistoryManager::search_with_conn(&conn, &search_query("invoic")).unwrap();
+        assert_eq!(results.total, 2);
+
+        conn.execute(
+            "DELETE FROM transcription_history WHERE timestamp = 100",
+            [],
+        )
+        .unwrap();
+        conn.execute(
+            "UPDATE transcription_history SET transcription_text = 'Pay the invoice' WHERE timestamp = 200",
+            [],
+        )
+        .unwrap();
+
+        let results = HistoryManager::search_with_conn(&conn, &search_query("invoice")).unwrap();
+        let mut timestamps: Vec<i64> = results.hits.iter().map(|hit| hit.entry.timestamp).collect();
+        timestamps.sort();
+        assert_eq!(timestamps, vec![200, 300]);
+        assert!(results.hits.iter().all(|hit| hit
+            .snippet
+            .iter()
+            .any(|part| part.highlighted && part.text.starts_with("invoice"))));
+    }
+
+    #[cfg(feature = "encryption")]
+    #[test]
+    fn search_ranks_matches_in_an_encrypted_database() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("history.db");
+        let vault = Vault::open(dir.path(), "com.pais.chatterfox.tests").unwrap();
+        let key = vault.enable("correct horse", false).unwrap();
+
+        let mut conn = Connection::open(&path).unwrap();
+        conn.execute_batch(&key.sqlcipher_pragma()).unwrap();
+        Migrations::new(MIGRATIONS.to_vec())
+            .to_latest(&mut conn)
+            .unwrap();
+        insert_entry(&conn, 100, "Send the invoice to accounting after lunch");
+        insert_entry(&conn, 200, "Invoice the invoice");
+
+        // FTS5 and bm25 come with the SQLCipher build too
+        let results = HistoryManager::search_with_conn(&conn, &search_query("invoice")).unwrap();
+        assert_eq!(results.total, 2);
+        assert_eq!(results.hits[0].entry.timestamp, 200);
+        drop(conn);
+        assert!(!encryption::is_plaintext_database(&path).unwrap());
+    }
+
+    #[test]
+    fn search_filters_and_pages() {
+        let conn = setup_conn();
+        for timestamp in [100, 200, 300, 400] {
+            insert_entry(&conn, timestamp, "weekly report");
+        }
+        conn.execute(
+            "UPDATE transcription_history SET saved = 1 WHERE timestamp IN (200, 400)",
+            [],
+        )
+        .unwrap();
+
+        let mut query = search_query("report");
+        query.from = Some(200);
+        query.to = Some(400);
+        let results = HistoryManager::search_with_conn(&conn, &query).unwrap();
+        assert_eq!(results.total, 2);
+
+        query.saved_only = true;
+        let results = HistoryManager::search_with_conn(&conn, &query).unwrap();
+        assert_eq!(results.total, 1);
+        assert_eq!(results.hits[0].entry.timestamp, 200);
+
+        let mut query = search_query("report");
+        query.offset = 3;
+        query.limit = 2;
+        let results = HistoryManager::search_with_conn(&conn, &query).unwrap();
+        assert_eq!(results.total, 4);
+        assert_eq!(results.hits.len(), 1);
+    }
+
+    #[test]
+    fn search_input_is_quoted() {
+        assert_eq!(fts_query("  "), None);
+        assert_eq!(
+            fts_query("say \"hi\" OR"),
+            Some("\"say\" \"\"\"hi\"\"\" \"OR\"*".to_string())
+        );
+        assert_eq!(
+            parse_snippet("a \u{2}b\u{3} c"),
+            vec![
+                SnippetPart {
+                    text: "a ".to_string(),
+                    highlighted: false
+                },
+                SnippetPart {
+                    text: "b".to_string(),
+                    highlighted: true
+                },
+                SnippetPart {
+                    text: " c".to_string(),
+                    highlighted: false
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn entries_carry_their_metadata() {
+        let conn = setup_conn();
+        insert_entry(&conn, 100, "old");
+        insert_entry(&conn, 200, "new");
+        conn.execute(
+            "UPDATE transcription_history SET model_id = 'parakeet-tdt', engine = 'parakeet',
+                 translate = 0, audio_duration_ms = 4200, speech_duration_ms = 3100,
+                 binding_id = 'transcribe'
+             WHERE timestamp = 200",
+            [],
+        )
+        .unwrap();
+
+        let entry = HistoryManager::get_latest_entry_with_conn(&conn)
+            .unwrap()
+            .unwrap();
+        assert_eq!(entry.metadata.engine.as_deref(), Some("parakeet"));
+        assert_eq!(entry.metadata.translate, Some(false));
+        assert_eq!(entry.metadata.audio_duration_ms, Some(4200));
+        assert_eq!(entry.metadata.speech_duration_ms, Some(3100));
+        assert_eq!(entry.metadata.inference_ms, None);
+        assert_eq!(entry.metadata.binding_id.as_deref(), Some("transcribe"));
+
+        // Older entries simply have none
+        let mut query = page_query(1);
+        query.before = Some(HistoryCursor {
+            timestamp: entry.timestamp,
+            id: entry.id,
+        });
+        let page = HistoryManager::get_history_page_with_conn(&conn, &query).unwrap();
+        assert!(page.entries[0].metadata.model_id.is_none());
+    }
+
+    fn correction_count(conn: &Connection, original: &str) -> Option<u32> {
+        conn.query_row(
+            "SELECT count FROM learned_corrections WHERE original = ?1",
+            params![original],
+            |row| row.get(0),
+        )
+        .optional()
+        .unwrap()
+    }
+
+    #[test]
+    fn edits_keep_the_original_and_count_corrections_once_per_entry() {
+        let mut conn = setup_conn();
+        insert_entry(&conn, 100, "deploy with cube control");
+        insert_entry(&conn, 200, "cube control get pods");
+
+        let learned =
+            HistoryManager::update_entry_text_with_conn(&mut conn, 1, "deploy with kubectl")
+                .unwrap();
+        assert_eq!(
+            learned,
+            vec![LearnedCorrection {
+                original: "cube control".to_string(),
+                replacement: "kubectl".to_string(),
+                count: 1,
+            }]
+        );
+
+        // Editing the same entry again doesn't count the correction twice
+        HistoryManager::update_entry_text_with_conn(&mut conn, 1, "Deploy with kubectl.").unwrap();
+        HistoryManager::update_entry_text_with_conn(&mut conn, 2, "kubectl get pods").unwrap();
+        assert_eq!(correction_count(&conn, "cube control"), Some(2));
+
+        let entry = HistoryManager::get_latest_entry_with_conn(&conn)
+            .unwrap()
+            .unwrap();
+        assert_eq!(entry.transcription_text, "kubectl get pods");
+        assert_eq!(
+            entry.original_text.as_deref(),
+            Some("cube control get pods")
+        );
+
+        // Reverting restores the model output and takes the correction back
+        HistoryManager::update_entry_text_with_conn(&mut conn, 2, "cube control get pods").unwrap();
+        let entry = HistoryManager::get_latest_entry_with_conn(&conn)
+            .unwrap()
+            .unwrap();
+        assert_eq!(entry.original_text, None);
+        assert_eq!(correction_count(&conn, "cube control"), Some(1));
+    }
+
+    #[test]
+    fn appending_extends_the_model_transcript_and_timings() {
+        let mut conn = setup_conn();
+        insert_entry(&conn, 100, "cube control get pods");
+        HistoryManager::update_entry_text_with_conn(&mut conn, 1, "kubectl get pods").unwrap();
+        let entry = HistoryManager::get_latest_entry_with_conn(&conn)
+            .unwrap()
+            .unwrap();
+
+        // Like entries saved before timings were recorded, it has none yet
+        let metadata = TranscriptionMetadata {
+            audio_duration_ms: Some(1500),
+            speech_duration_ms: Some(1200),
+            inference_ms: Some(300),
+            ..Default::default()
+        };
+        HistoryManager::append_to_entry_with_conn(
+            &conn,
+            &entry,
+            "handy-100.wav",
+            "in staging",
+            &metadata,
+        )
+        .unwrap();
+
+        let entry = HistoryManager::get_latest_entry_with_conn(&conn)
+            .unwrap()
+            .unwrap();
+        assert_eq!(entry.transcription_text, "kubectl get pods in staging");
+        assert_eq!(
+            entry.original_text.as_deref(),
+            Some("cube control get pods in staging")
+        );
+        assert_eq!(entry.metadata.audio_duration_ms, Some(1500));
+        assert_eq!(entry.metadata.speech_duration_ms, Some(1200));
+        assert_eq!(entry.metadata.inference_ms, Some(300));
+    }
+
+    #[test]
+    fn appended_audio_follows_a_gap() {
+        let combined = append_recording(vec![0.5; 10], &[0.25; 5]);
+        assert_eq!(combined.len(), 10 + 8000 + 5);
+        assert!(combined[10..8010].iter().all(|&s| s == 0.0));
+        assert_eq!(&combined[8010..], &[0.25; 5]);
+
+        // Nothing to separate from when the original audio is gone
+        assert_eq!(append_recording(Vec::new(), &[0.25; 5]), vec![0.25; 5]);
+    }
+
+    #[test]
+    fn tags_are_shared_by_name_and_filter_history() {
+        let conn = setup_conn();
+        for timestamp in [100, 200, 300] {
+            insert_entry(&conn, timestamp, "text");
+        }
+
+        let work = HistoryManager::tag_entry_with_conn(&conn, 1, "Work").unwrap();
+        let again = HistoryManager::tag_entry_with_conn(&conn, 3, " work ").unwrap();
+        let ideas = HistoryManager::tag_entry_with_conn(&conn, 3, "ideas").unwrap();
+        assert_eq!(again.id, work.id);
+        assert_eq!(again.entry_count, 2);
+        assert!(HistoryManager::tag_entry_with_conn(&conn, 9, "work").is_err());
+        assert!(HistoryManager::tag_entry_with_conn(&conn, 1, "  ").is_err());
+
+        let tags = HistoryManager::get_tags_with_conn(&conn).unwrap();
+        let tags: Vec<_> = tags
+            .iter()
+            .map(|t| (t.name.as_str(), t.entry_count))
+            .collect();
+        assert_eq!(tags, vec![("ideas", 1), ("Work", 2)]);
+
+        let mut query = page_query(10);
+        query.tag_id = Some(work.id);
+        let page = HistoryManager::get_history_page_with_conn(&conn, &query).unwrap();
+        let ids: Vec<_> = page.entries.iter().map(|e| e.id).collect();
+        assert_eq!(ids, vec![3, 1]);
+        assert_eq!(page.entries[0].tags, vec![work.id, ideas.id]);
+
+        let mut search = search_query("text");
+        search.tag_id = Some(ideas.id);
+        let results = HistoryManager::search_with_conn(&conn, &search).unwrap();
+        assert_eq!(results.total, 1);
+        assert_eq!(results.hits[0].entry.tags, vec![work.id, ideas.id]);
+    }
+
+    #[test]
+    fn tagged_entries_follow_their_tag_retention() {
+        let conn = setup_conn();
+        let day = 86400;
+        let now = 100 * day;
+        for age in [10, 10, 10, 40] {
+            insert_entry(&conn, now - age * day, "text");
+        }
+        let month = HistoryManager::tag_entry_with_conn(&conn, 1, "month").unwrap();
+        let keep = HistoryManager::tag_entry_with_conn(&conn, 2, "keep").unwrap();
+        let week = HistoryManager::tag_entry_with_conn(&conn, 3, "week").unwrap();
+        HistoryManager::tag_entry_with_conn(&conn, 4, "month").unwrap();
+        conn.execute_batch(&format!(
+            "UPDATE tags SET retention_days = 30 WHERE id = {};
+             UPDATE tags SET retention_days = 7 WHERE id = {};",
+            month.id, week.id
+        ))
+        .unwrap();
+
+        // Entry 3 also keeps a tag without a rule, so it stays like a saved one
+        HistoryManager::tag_entry_with_conn(&conn, 3, "keep").unwrap();
+        let expired = HistoryManager::expired_tagged_entries_with_conn(&conn, now).unwrap();
+        assert_eq!(expired, vec![(4, format!("handy-{}.wav", now - 40 * day))]);
+
+        conn.execute(
+            "DELETE FROM entry_tags WHERE entry_id = 3 AND tag_id = ?1",
+            params![keep.id],
+        )
+        .unwrap();
+        let expired = HistoryManager::expired_tagged_entries_with_conn(&conn, now).unwrap();
+        let ids: Vec<_> = expired.iter().map(|(id, _)| *id).collect();
+        assert_eq!(ids, vec![3, 4]);
+    }
+
+    #[test]
+    fn audio_pruning_skips_protected_and_pruned_entries() {
+        let conn = setup_conn();
+        for timestamp in [100, 200, 300, 400, 500, 600] {
+            insert_entry(&conn, timestamp, "text");
+        }
+        conn.execute_batch(
+            "UPDATE transcription_history SET saved = 1 WHERE id = 2;
+             UPDATE transcription_history SET recovered = 1 WHERE id = 4;
+             UPDATE transcription_history SET audio_pruned = 1 WHERE id = 5;",
+        )
+        .unwrap();
+        HistoryManager::tag_entry_with_conn(&conn, 3, "keep").unwrap();
+
+        let ids = |before| -> Vec<i64> {
+            HistoryManager::prunable_audio_with_conn(&conn, before)
+                .unwrap()
+                .into_iter()
+                .map(|(id, _)| id)
+                .collect()
+        };
+        // Oldest first, so a quota evicts them in order
+        assert_eq!(ids(None), vec![1, 6]);
+        assert_eq!(ids(Some(600)), vec![1]);
+
+        let entry = HistoryManager::get_history_page_with_conn(&conn, &page_query(10))
+            .unwrap()
+            .entries
+            .into_iter()
+            .find(|entry| entry.id == 5)
+            .unwrap();
+        assert!(entry.audio_pruned);
+    }
+
+    #[test]
+    fn archived_entries_merge_without_duplicates() {
+        let source = setup_conn();
+        insert_entry(&source, 100, "first");
+        insert_entry(&source, 200, "second");
+        HistoryManager::tag_entry_with_conn(&source, 1, "work").unwrap();
+        HistoryManager::tag_entry_with_conn(&source, 1, "ideas").unwrap();
+        attach_collection(&source, 2, "drafts").unwrap();
+        source
+            .execute(
+                "UPDATE transcription_history SET note = 'call back' WHERE id = 2",
+                [],
+            )
+            .unwrap();
+
+        let archived = HistoryManager::archived_entries_with_conn(&source).unwrap();
+        assert_eq!(archived[0].tags, vec!["ideas", "work"]);
+        assert_eq!(archived[1].collections, vec!["drafts"]);
+        assert_eq!(archived[1].note.as_deref(), Some("call back"));
+
+        // The target already has the first entry, untagged, and another one
+        let target = setup_conn();
+        insert_entry(&target, 100, "first");
+        insert_entry(&target, 150, "own entry");
+
+        let outcomes: Vec<_> = archived
+            .iter()
+            .map(|entry| HistoryManager::merge_entry_with_conn(&target, entry).unwrap())
+            .collect();
+        assert_eq!(
+            outcomes,
+            vec![
+                MergeOutcome::Duplicate {
+                    id: 1,
+                    differs: false
+                },
+                MergeOutcome::Inserted(3)
+            ]
+        );
+
+        let merged = HistoryManager::archived_entries_with_conn(&target).unwrap();
+        let merged: Vec<_> = merged
+            .iter()
+            .map(|e| {
+                (
+                    e.timestamp,
+                    e.tags.len(),
+                    e.collections.len(),
+                    e.note.is_some(),
+                )
+            })
+            .collect();
+        assert_eq!(
+            merged,
+            vec![(100, 2, 0, false), (150, 0, 0, false), (200, 0, 1, true)]
+        );
+
+        // Importing the same archive again changes nothing
+        for entry in &archived {
+            let outcome = HistoryManager::merge_entry_with_conn(&target, entry).unwrap();
+            assert!(matches!(
+                outcome,
+                MergeOutcome::Duplicate { differs: false, .. }
+            ));
+        }
+        let count: i64 = target
+            .query_row("SELECT COUNT(*) FROM transcription_history", [], |row| {
+                row.get(0)
+            })
+            .unwrap();
+        assert_eq!(count, 3);
+    }
+
+    #[test]
+    fn usage_rows_count_pasted_text_and_selected_languages() {
+        let conn = setup_conn();
+        insert_entry(&conn, 100, "raw transcript");
+        insert_entry(&conn, 200, "second");
+        insert_entry(&conn, 300, "");
+        conn.execute_batch(
+            "UPDATE transcription_history SET post_processed_text = 'cleaned up text',
+                 language = 'fr' WHERE id = 1;
+             UPDATE transcription_history SET language = 'de' WHERE id = 2;
+             UPDATE transcription_history SET recovered = 1 WHERE id = 3;",
+        )
+        .unwrap();
+
+        let query = UsageStatsQuery::default();
+        let rows = HistoryManager::usage_rows_with_conn(&conn, &query).unwrap();
+        let rows: Vec<_> = rows
+            .iter()
+            .map(|r| (r.text.as_str(), r.language.as_deref()))
+            .collect();
+        assert_eq!(
+            rows,
+            vec![("cleaned up text", Some("fr")), ("second", Some("de"))]
+        );
+
+        let query = UsageStatsQuery {
+            from: Some(150),
+            ..Default::default()
+        };
+        let rows = HistoryManager::usage_rows_with_conn(&conn, &query).unwrap();
+        assert_eq!(rows.len(), 1);
+    }
+
+    #[test]
+    fn transcripts_are_joined_with_a_single_space() {
+        assert_eq!(
+            join_transcripts("Hello there. ", " Bye."),
+            "Hello there. Bye."
+        );
+        assert_eq!(join_transcripts("", "Bye."), "Bye.");
+        assert_eq!(join_transcripts("Hello.", ""), "Hello.");
+    }
+
+    #[test]
+    fn journal_timestamp_comes_from_the_file_name() {
+        assert_eq!(
+            journal_timestamp(Path::new("/data/journal/journal-1700000000123.pcm")),
+            Some(1_700_000_000)
+        );
+        assert_eq!(
+            journal_timestamp(Path::new("/data/journal/other.pcm")),
+            None
+        );
+    }
+
+    #[test]
+    fn only_lossless_recordings_in_another_format_are_converted() {
+        assert!(needs_conversion("handy-1.wav", AudioFileFormat::Flac));
+        assert!(needs_conversion("handy-1.WAV", AudioFileFormat::Flac));
+        assert!(needs_conversion(
+            "handy-1.flac",
+            AudioFileFormat::Opus { bitrate: 24000 }
+        ));
+        assert!(!needs_conversion("handy-1.flac", AudioFileFormat::Flac));
+        assert!(!needs_conversion("handy-1.opus", AudioFileFormat::Wav));
+    }
 }
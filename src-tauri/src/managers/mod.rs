@@ -1,4 +1,7 @@
 pub mod audio;
+pub mod backup;
 pub mod history;
 pub mod model;
+pub mod retranscription;
+pub mod stats;
 pub mod transcription;
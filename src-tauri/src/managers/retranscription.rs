@@ -0,0 +1,209 @@
+use crate::audio_toolkit::constants;
+use crate::managers::history::HistoryManager;
+use crate::managers::transcription::{DetachedEngine, TranscriptionManager};
+use anyhow::Result;
+use log::{debug, error, info};
+use serde::Serialize;
+use specta::Type;
+use std::collections::VecDeque;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+use tauri::{AppHandle, Emitter};
+
+/// A history entry to run through a model again.
+#[derive(Clone, Debug)]
+pub struct RetranscriptionJob {
+    pub entry_id: i64,
+    pub model_id: String,
+    pub language: String,
+}
+
+#[derive(Clone, Debug, Serialize, Type)]
+pub struct RetranscriptionProgress {
+    pub entry_id: i64,
+    pub processed: usize,
+    pub total: usize,
+    /// Why this entry failed, if it did.
+    pub error: Option<String>,
+}
+
+#[derive(Clone, Debug, Default, Serialize, Type)]
+pub struct RetranscriptionSummary {
+    pub completed: usize,
+    pub failed: usize,
+    /// Jobs dropped from the queue by a cancellation.
+    pub cancelled: usize,
+}
+
+#[derive(Default)]
+struct QueueState {
+    jobs: VecDeque<RetranscriptionJob>,
+    processed: usize,
+    total: usize,
+    summary: RetranscriptionSummary,
+    /// Whether a worker thread is draining the queue.
+    running: bool,
+}
+
+/// Re-transcribes history recordings in the background, one at a time,
+/// storing the results as alternative transcripts. Jobs queued while a batch
+/// runs are added to it.
+///
+/// A batch loads its models into an engine of its own, so dictation keeps
+/// its model while the batch runs, at the cost of holding both in memory.
+pub struct RetranscriptionManager {
+    app_handle: AppHandle,
+    history_manager: Arc<HistoryManager>,
+    transcription_manager: Arc<TranscriptionManager>,
+    state: Mutex<QueueState>,
+    cancel: AtomicBool,
+}
+
+impl RetranscriptionManager {
+    pub fn new(
+        app_handle: &AppHandle,
+        history_manager: Arc<HistoryManager>,
+        transcription_manager: Arc<TranscriptionManager>,
+    ) -> Self {
+        Self {
+            app_handle: app_handle.clone(),
+            history_manager,
+            transcription_manager,
+            state: Mutex::new(QueueState::default()),
+            cancel: AtomicBool::new(false),
+        }
+    }
+
+    pub fn enqueue(self: &Arc<Self>, jobs: Vec<RetranscriptionJob>) {
+        if jobs.is_empty() {
+            return;
+        }
+
+        let mut state = self.state.lock().unwrap();
+        state.total += jobs.len();
+        state.jobs.extend(jobs);
+        if state.running {
+            return;
+        }
+        state.running = true;
+        drop(state);
+
+        self.cancel.store(false, Ordering::SeqCst);
+        let manager = Arc::clone(self);
+        std::thread::spawn(move || manager.run());
+    }
+
+    /// Drops every queued job; the one being transcribed still finishes.
+    pub fn cancel(&self) {
+        self.cancel.store(true, Ordering::SeqCst);
+    }
+
+    fn run(&self) {
+        // Dropped with the batch, freeing the model
+        let mut engine: Option<DetachedEngine> = None;
+
+        loop {
+            let Some(job) = self.next_job() else {
+                // The queue is only closed under the lock, so no job queued
+                // after `next_job` is lost
+                let mut state = self.state.lock().unwrap();
+                if !state.jobs.is_empty() {
+                    continue;
+                }
+                let summary = std::mem::take(&mut state.summary);
+                *state = QueueState::default();
+                drop(state);
+
+                info!(
+                    "Re-transcription finished: {} completed, {} failed, {} cancelled",
+                    summary.completed, summary.failed, summary.cancelled
+                );
+                if let Err(e) = self.app_handle.emit("retranscription-finished", summary) {
+                    error!("Failed to emit retranscription-finished event: {}", e);
+                }
+                return;
+            };
+
+            let result = self.retranscribe(&job, &mut engine);
+
+            let progress = {
+                let mut state = self.state.lock().unwrap();
+                state.processed += 1;
+                match &result {
+                    Ok(()) => state.summary.completed += 1,
+                    Err(_) => state.summary.failed += 1,
+                }
+                RetranscriptionProgress {
+                    entry_id: job.entry_id,
+                    processed: state.processed,
+                    total: state.total,
+                    error: result.err().map(|e| {
+                        error!("Failed to re-transcribe entry {}: {}", job.entry_id, e);
+                        e.to_string()
+                    }),
+                }
+            };
+            if let Err(e) = self.app_handle.emit("retranscription-progress", progress) {
+                error!("Failed to emit retranscription-progress event: {}", e);
+            }
+        }
+    }
+
+    fn next_job(&self) -> Option<RetranscriptionJob> {
+        let mut state = self.state.lock().unwrap();
+        if self.cancel.load(Ordering::SeqCst) {
+            state.summary.cancelled += state.jobs.len();
+            state.jobs.clear();
+        }
+        state.jobs.pop_front()
+    }
+
+    fn retranscribe(
+        &self,
+        job: &RetranscriptionJob,
+        engine: &mut Option<DetachedEngine>,
+    ) -> Result<()> {
+        let entry =
+            tauri::async_runtime::block_on(self.history_manager.get_entry_by_id(job.entry_id))?
+                .ok_or_else(|| anyhow::anyhow!("History entry {} not found", job.entry_id))?;
+
+        if entry.audio_pruned {
+            anyhow::bail!(
+                "The recording of entry {} was deleted by retention",
+                job.entry_id
+            );
+        }
+        let path = self.history_manager.get_audio_file_path(&entry.file_name);
+        if !path.exists() {
+            anyhow::bail!("The recording of entry {} is gone", job.entry_id);
+        }
+        let (samples, sample_rate) = self.history_manager.read_recording(&path)?;
+        if sample_rate != constants::WHISPER_SAMPLE_RATE {
+            anyhow::bail!("{} was recorded at {} Hz", entry.file_name, sample_rate);
+        }
+
+        let engine = match engine.take() {
+            Some(loaded) if loaded.is_ready_for(&job.model_id) => engine.insert(loaded),
+            stale => {
+                // Free the previous model before loading the next one
+                drop(stale);
+                engine.insert(self.transcription_manager.load_detached(&job.model_id)?)
+            }
+        };
+
+        let output =
+            self.transcription_manager
+                .transcribe_detached(engine, samples, &job.language)?;
+        self.history_manager.add_alternative_transcript(
+            job.entry_id,
+            &job.model_id,
+            &job.language,
+            &output,
+        )?;
+        debug!(
+            "Re-transcribed entry {} with {} ({})",
+            job.entry_id, job.model_id, job.language
+        );
+        Ok(())
+    }
+}
@@ -0,0 +1,248 @@
+use chrono::{DateTime, Datelike, Local, NaiveDate};
+use serde::{Deserialize, Serialize};
+use specta::Type;
+use std::collections::{BTreeMap, HashMap};
+
+/// Typing speed assumed when estimating the time dictation saved.
+pub const DEFAULT_TYPING_WPM: u32 = 40;
+
+/// A date range of history to summarize. Dates are unix timestamps in
+/// seconds, `from` inclusive and `to` exclusive.
+#[derive(Clone, Debug, Default, Deserialize, Type)]
+pub struct UsageStatsQuery {
+    #[serde(default)]
+    pub from: Option<i64>,
+    #[serde(default)]
+    pub to: Option<i64>,
+    /// Typing speed to compare against, [`DEFAULT_TYPING_WPM`] when unset.
+    #[serde(default)]
+    pub typing_wpm: Option<u32>,
+}
+
+/// Words dictated in a local calendar day or week. Weeks start on Monday.
+#[derive(Clone, Debug, PartialEq, Serialize, Type)]
+pub struct UsagePeriod {
+    /// First day of the period, `YYYY-MM-DD`.
+    pub start: String,
+    pub entries: u32,
+    pub words: u64,
+}
+
+/// How much a model or language was used, most used first.
+#[derive(Clone, Debug, PartialEq, Serialize, Type)]
+pub struct UsageCount {
+    pub name: String,
+    pub entries: u32,
+    pub words: u64,
+}
+
+#[derive(Clone, Debug, Default, Serialize, Type)]
+pub struct UsageStats {
+    pub entries: u32,
+    pub words: u64,
+    /// Days and weeks with any dictation, oldest first.
+    pub daily: Vec<UsagePeriod>,
+    pub weekly: Vec<UsagePeriod>,
+    /// Recorded audio, excluding paused time.
+    pub audio_minutes: f64,
+    /// Words per minute of speech, over the entries whose duration is known.
+    pub words_per_minute: Option<f64>,
+    /// Minutes typing the same words would have taken, less the time spent
+    /// dictating them.
+    pub typing_minutes_saved: f64,
+    pub models: Vec<UsageCount>,
+    pub languages: Vec<UsageCount>,
+}
+
+/// What the statistics need from a history entry.
+#[derive(Clone, Debug, Default)]
+pub struct UsageRow {
+    pub timestamp: i64,
+    /// The text that was pasted: post-processed when it was, the transcript
+    /// otherwise.
+    pub text: String,
+    pub model_id: Option<String>,
+    pub language: Option<String>,
+    pub audio_duration_ms: Option<u64>,
+    pub speech_duration_ms: Option<u64>,
+}
+
+pub fn aggregate(rows: &[UsageRow], typing_wpm: u32) -> UsageStats {
+    let mut stats = UsageStats::default();
+    let mut daily: BTreeMap<NaiveDate, UsagePeriod> = BTreeMap::new();
+    let mut weekly: BTreeMap<NaiveDate, UsagePeriod> = BTreeMap::new();
+    let mut models: HashMap<&str, UsageCount> = HashMap::new();
+    let mut languages: HashMap<&str, UsageCount> = HashMap::new();
+    let mut audio_ms = 0u64;
+    let mut timed_words = 0u64;
+    let mut speech_ms = 0u64;
+
+    for row in rows {
+        let words = row.text.split_whitespace().count() as u64;
+        stats.entries += 1;
+        stats.words += words;
+
+        if let Some(day) = local_date(row.timestamp) {
+            let week = day - chrono::Duration::days(day.weekday().num_days_from_monday() as i64);
+            add_period(&mut daily, day, words);
+            add_period(&mut weekly, week, words);
+        }
+
+        if let Some(ms) = row.audio_duration_ms {
+            audio_ms += ms;
+        }
+        // Speech excludes the silence voice activity detection dropped;
+        // entries recorded before that was measured fall back to the audio
+        if let Some(ms) = row.speech_duration_ms.or(row.audio_duration_ms) {
+            if ms > 0 {
+                speech_ms += ms;
+                timed_words += words;
+            }
+        }
+
+        if let Some(model) = row.model_id.as_deref() {
+            add_count(&mut models, model, words);
+        }
+        if let Some(language) = row.language.as_deref().filter(|l| *l != "auto") {
+            add_count(&mut languages, language, words);
+        }
+    }
+
+    stats.daily = daily.into_values().collect();
+    stats.weekly = weekly.into_values().collect();
+    stats.audio_minutes = audio_ms as f64 / 60_000.0;
+    if speech_ms > 0 {
+        stats.words_per_minute = Some(timed_words as f64 / (speech_ms as f64 / 60_000.0));
+    }
+    let typing_minutes = stats.words as f64 / typing_wpm.max(1) as f64;
+    stats.typing_minutes_saved = (typing_minutes - stats.audio_minutes).max(0.0);
+    stats.models = ranked(models);
+    stats.languages = ranked(languages);
+    stats
+}
+
+fn local_date(timestamp: i64) -> Option<NaiveDate> {
+    DateTime::from_timestamp(timestamp, 0).map(|utc| utc.with_timezone(&Local).date_naive())
+}
+
+fn add_period(periods: &mut BTreeMap<NaiveDate, UsagePeriod>, start: NaiveDate, words: u64) {
+    let period = periods.entry(start).or_insert_with(|| UsagePeriod {
+        start: start.format("%Y-%m-%d").to_string(),
+        entries: 0,
+        words: 0,
+    });
+    period.entries += 1;
+    period.words += words;
+}
+
+fn add_count<'a>(counts: &mut HashMap<&'a str, UsageCount>, name: &'a str, words: u64) {
+    let count = counts.entry(name).or_insert_with(|| UsageCount {
+        name: name.to_string(),
+        entries: 0,
+        words: 0,
+    });
+    count.entries += 1;
+    count.words += words;
+}
+
+fn ranked(counts: HashMap<&str, UsageCount>) -> Vec<UsageCount> {
+    let mut counts: Vec<UsageCount> = counts.into_values().collect();
+    counts.sort_by(|a, b| {
+        b.entries
+            .cmp(&a.entries)
+            .then(b.words.cmp(&a.words))
+            .then_with(|| a.name.cmp(&b.name))
+    });
+    counts
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    fn row(date: (i32, u32, u32), text: &str, model: &str, language: &str) -> UsageRow {
+        let timestamp = Local
+            .with_ymd_and_hms(date.0, date.1, date.2, 12, 0, 0)
+            .unwrap()
+            .timestamp();
+        UsageRow {
+            timestamp,
+            text: text.to_string(),
+            model_id: Some(model.to_string()),
+            language: Some(language.to_string()),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn groups_words_by_local_day_and_week() {
+        // 2026-03-01 is a Sunday, so it belongs to the week before the others
+        let rows = vec![
+            row((2026, 3, 1), "one two", "small", "en"),
+            row((2026, 3, 2), "three four five", "small", "en"),
+            row((2026, 3, 2), "six", "small", "en"),
+            row((2026, 3, 4), "seven", "small", "en"),
+        ];
+
+        let stats = aggregate(&rows, DEFAULT_TYPING_WPM);
+
+        assert_eq!(stats.entries, 4);
+        assert_eq!(stats.words, 7);
+        let daily: Vec<_> = stats
+            .daily
+            .iter()
+            .map(|p| (p.start.as_str(), p.words))
+            .collect();
+        assert_eq!(
+            daily,
+            vec![("2026-03-01", 2), ("2026-03-02", 4), ("2026-03-04", 1)]
+        );
+        let weekly: Vec<_> = stats
+            .weekly
+            .iter()
+            .map(|p| (p.start.as_str(), p.entries, p.words))
+            .collect();
+        assert_eq!(weekly, vec![("2026-02-23", 1, 2), ("2026-03-02", 3, 5)]);
+    }
+
+    #[test]
+    fn speaking_rate_uses_only_timed_entries() {
+        let mut timed = row((2026, 3, 2), &"word ".repeat(150), "small", "en");
+        timed.audio_duration_ms = Some(90_000);
+        timed.speech_duration_ms = Some(60_000);
+        let untimed = row((2026, 3, 2), &"word ".repeat(50), "small", "en");
+
+        let stats = aggregate(&[timed, untimed], 40);
+
+        assert_eq!(stats.words_per_minute, Some(150.0));
+        assert_eq!(stats.audio_minutes, 1.5);
+        // 200 words take 5 minutes to type; 1.5 were spent dictating
+        assert_eq!(stats.typing_minutes_saved, 3.5);
+    }
+
+    #[test]
+    fn ranks_models_and_languages_by_use() {
+        let rows = vec![
+            row((2026, 3, 2), "a", "small", "auto"),
+            row((2026, 3, 2), "a b", "turbo", "de"),
+            row((2026, 3, 2), "a b c", "turbo", "en"),
+        ];
+
+        let stats = aggregate(&rows, DEFAULT_TYPING_WPM);
+
+        let models: Vec<_> = stats.models.iter().map(|c| c.name.as_str()).collect();
+        assert_eq!(models, vec!["turbo", "small"]);
+        // Detection-only entries don't count towards a language
+        let languages: Vec<_> = stats.languages.iter().map(|c| c.name.as_str()).collect();
+        assert_eq!(languages, vec!["en", "de"]);
+    }
+
+    #[test]
+    fn empty_history_has_no_speaking_rate() {
+        let stats = aggregate(&[], DEFAULT_TYPING_WPM);
+        assert_eq!(stats.entries, 0);
+        assert_eq!(stats.words_per_minute, None);
+        assert_eq!(stats.typing_minutes_saved, 0.0);
+    }
+}
@@ -1,10 +1,11 @@
-use crate::audio_toolkit::{apply_custom_words, filter_transcription_output};
+use crate::audio_toolkit::{apply_custom_words, apply_replacements, filter_transcription_output};
 use crate::managers::model::{EngineType, ModelManager};
-use crate::settings::{get_settings, ModelUnloadTimeout};
+use crate::settings::{get_settings, AppSettings, ModelUnloadTimeout};
 use anyhow::Result;
 use log::{debug, error, info, warn};
 use serde::Serialize;
 use std::panic::{catch_unwind, AssertUnwindSafe};
+use std::path::Path;
 use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
 use std::sync::{Arc, Condvar, Mutex, MutexGuard};
 use std::thread;
@@ -44,6 +45,44 @@ enum LoadedEngine {
     SenseVoice(SenseVoiceEngine),
 }
 
+impl LoadedEngine {
+    fn name(&self) -> &'static str {
+        match self {
+            LoadedEngine::Whisper(_) => "whisper",
+            LoadedEngine::Parakeet(_) => "parakeet",
+            LoadedEngine::Moonshine(_) => "moonshine",
+            LoadedEngine::MoonshineStreaming(_) => "moonshine_streaming",
+            LoadedEngine::SenseVoice(_) => "sense_voice",
+        }
+    }
+}
+
+/// A transcript along with what produced it.
+#[derive(Clone, Debug, Default)]
+pub struct TranscriptionOutput {
+    pub text: String,
+    pub model_id: Option<String>,
+    /// Unset when there was no audio to run the engine on.
+    pub engine: Option<&'static str>,
+    /// Time spent in the engine itself.
+    pub inference: Duration,
+}
+
+/// A model loaded apart from the one used for dictation, so background work
+/// such as re-transcription never swaps the model out from under a recording.
+pub struct DetachedEngine {
+    model_id: String,
+    /// Empty once the engine has panicked.
+    engine: Option<LoadedEngine>,
+}
+
+impl DetachedEngine {
+    /// Whether this engine can transcribe with `model_id`.
+    pub fn is_ready_for(&self, model_id: &str) -> bool {
+        self.model_id == model_id && self.engine.is_some()
+    }
+}
+
 #[derive(Clone)]
 pub struct TranscriptionManager {
     engine: Arc<Mutex<Option<LoadedEngine>>>,
@@ -242,109 +281,19 @@ impl TranscriptionManager {
 
         let model_path = self.model_manager.get_model_path(model_id)?;
 
-        // Create appropriate engine based on model type
-        let loaded_engine = match model_info.engine_type {
-            EngineType::Whisper => {
-                let mut engine = WhisperEngine::new();
-                engine.load_model(&model_path).map_err(|e| {
-                    let error_msg = format!("Failed to load whisper model {}: {}", model_id, e);
-                    let _ = self.app_handle.emit(
-                        "model-state-changed",
-                        ModelStateEvent {
-                            event_type: "loading_failed".to_string(),
-                            model_id: Some(model_id.to_string()),
-                            model_name: Some(model_info.name.clone()),
-                            error: Some(error_msg.clone()),
-                        },
-                    );
-                    anyhow::anyhow!(error_msg)
-                })?;
-                LoadedEngine::Whisper(engine)
-            }
-            EngineType::Parakeet => {
-                let mut engine = ParakeetEngine::new();
-                engine
-                    .load_model_with_params(&model_path, ParakeetModelParams::int8())
-                    .map_err(|e| {
-                        let error_msg =
-                            format!("Failed to load parakeet model {}: {}", model_id, e);
-                        let _ = self.app_handle.emit(
-                            "model-state-changed",
-                            ModelStateEvent {
-                                event_type: "loading_failed".to_string(),
-                                model_id: Some(model_id.to_string()),
-                                model_name: Some(model_info.name.clone()),
-                                error: Some(error_msg.clone()),
-                            },
-                        );
-                        anyhow::anyhow!(error_msg)
-                    })?;
-                LoadedEngine::Parakeet(engine)
-            }
-            EngineType::Moonshine => {
-                let mut engine = MoonshineEngine::new();
-                engine
-                    .load_model_with_params(
-                        &model_path,
-                        MoonshineModelParams::variant(ModelVariant::Base),
-                    )
-                    .map_err(|e| {
-                        let error_msg =
-                            format!("Failed to load moonshine model {}: {}", model_id, e);
-                        let _ = self.app_handle.emit(
-                            "model-state-changed",
-                            ModelStateEvent {
-                                event_type: "loading_failed".to_string(),
-                                model_id: Some(model_id.to_string()),
-                                model_name: Some(model_info.name.clone()),
-                                error: Some(error_msg.clone()),
-                            },
-                        );
-                        anyhow::anyhow!(error_msg)
-                    })?;
-                LoadedEngine::Moonshine(engine)
-            }
-            EngineType::MoonshineStreaming => {
-                let mut engine = MoonshineStreamingEngine::new();
-                engine
-                    .load_model_with_params(&model_path, StreamingModelParams::default())
-                    .map_err(|e| {
-                        let error_msg = format!(
-                            "Failed to load moonshine streaming model {}: {}",
-                            model_id, e
-                        );
-                        let _ = self.app_handle.emit(
-                            "model-state-changed",
-                            ModelStateEvent {
-                                event_type: "loading_failed".to_string(),
-                                model_id: Some(model_id.to_string()),
-                                model_name: Some(model_info.name.clone()),
-                                error: Some(error_msg.clone()),
-                            },
-                        );
-                        anyhow::anyhow!(error_msg)
-                    })?;
-                LoadedEngine::MoonshineStreaming(engine)
-            }
-            EngineType::SenseVoice => {
-                let mut engine = SenseVoiceEngine::new();
-                engine
-                    .load_model_with_params(&model_path, SenseVoiceModelParams::int8())
-                    .map_err(|e| {
-                        let error_msg =
-                            format!("Failed to load SenseVoice model {}: {}", model_id, e);
-                        let _ = self.app_handle.emit(
-                            "model-state-changed",
-                            ModelStateEvent {
-                                event_type: "loading_failed".to_string(),
-                                model_id: Some(model_id.to_string()),
-                                model_name: Some(model_info.name.clone()),
-                                error: Some(error_msg.clone()),
-                            },
-                        );
-                        anyhow::anyhow!(error_msg)
-                    })?;
-                LoadedEngine::SenseVoice(engine)
+        let loaded_engine = match create_engine(model_id, &model_info.engine_type, &model_path) {
+            Ok(engine) => engine,
+            Err(error_msg) => {
+                let _ = self.app_handle.emit(
+                    "model-state-changed",
+                    ModelStateEvent {
+                        event_type: "loading_failed".to_string(),
+                        model_id: Some(model_id.to_string()),
+                        model_name: Some(model_info.name.clone()),
+                        error: Some(error_msg.clone()),
+                    },
+                );
+                return Err(anyhow::anyhow!(error_msg));
             }
         };
 
@@ -403,7 +352,81 @@ impl TranscriptionManager {
         current_model.clone()
     }
 
+    /// Loads `model_id` into an engine of its own, leaving the engine used for
+    /// dictation, its idle timer and the announced model untouched. The two
+    /// engines take memory side by side while both are loaded.
+    pub fn load_detached(&self, model_id: &str) -> Result<DetachedEngine> {
+        let model_info = self
+            .model_manager
+            .get_model_info(model_id)
+            .ok_or_else(|| anyhow::anyhow!("Model not found: {}", model_id))?;
+        if !model_info.is_downloaded {
+            return Err(anyhow::anyhow!("Model not downloaded"));
+        }
+        let model_path = self.model_manager.get_model_path(model_id)?;
+        let engine = create_engine(model_id, &model_info.engine_type, &model_path)
+            .map_err(|e| anyhow::anyhow!(e))?;
+        debug!("Loaded detached engine for {}", model_id);
+
+        Ok(DetachedEngine {
+            model_id: model_id.to_string(),
+            engine: Some(engine),
+        })
+    }
+
+    /// Transcribes in `language` with an engine from
+    /// [`load_detached`](Self::load_detached). An engine that panics is
+    /// dropped, so the caller loads a new one.
+    pub fn transcribe_detached(
+        &self,
+        detached: &mut DetachedEngine,
+        audio: Vec<f32>,
+        language: &str,
+    ) -> Result<TranscriptionOutput> {
+        if audio.is_empty() {
+            return Ok(TranscriptionOutput::default());
+        }
+        let mut engine = detached
+            .engine
+            .take()
+            .ok_or_else(|| anyhow::anyhow!("{} is not loaded", detached.model_id))?;
+
+        let settings = get_settings(&self.app_handle);
+        let engine_name = engine.name();
+        let inference_start = std::time::Instant::now();
+        let transcribe_result = catch_unwind(AssertUnwindSafe(|| {
+            run_engine(&mut engine, audio, language, settings.translate_to_english)
+        }));
+        let inference = inference_start.elapsed();
+
+        let result = match transcribe_result {
+            Ok(inner_result) => {
+                detached.engine = Some(engine);
+                inner_result?
+            }
+            Err(panic_payload) => {
+                let panic_msg = panic_message(panic_payload.as_ref());
+                error!("Detached transcription engine panicked: {}", panic_msg);
+                return Err(anyhow::anyhow!(
+                    "Transcription engine panicked: {}",
+                    panic_msg
+                ));
+            }
+        };
+
+        Ok(TranscriptionOutput {
+            text: clean_up_transcript(result.text, &settings),
+            model_id: Some(detached.model_id.clone()),
+            engine: Some(engine_name),
+            inference,
+        })
+    }
+
     pub fn transcribe(&self, audio: Vec<f32>) -> Result<String> {
+        self.transcribe_detailed(audio).map(|output| output.text)
+    }
+
+    pub fn transcribe_detailed(&self, audio: Vec<f32>) -> Result<TranscriptionOutput> {
         // Update last activity timestamp
         self.last_activity.store(
             SystemTime::now()
@@ -420,7 +443,7 @@ impl TranscriptionManager {
         if audio.is_empty() {
             debug!("Empty audio vector");
             self.maybe_unload_immediately("empty audio");
-            return Ok(String::new());
+            return Ok(TranscriptionOutput::default());
         }
 
         // Check if model is loaded, if not try to load it
@@ -440,6 +463,9 @@ impl TranscriptionManager {
         // Get current settings for configuration
         let settings = get_settings(&self.app_handle);
 
+        let engine_name;
+        let inference;
+
         // Perform transcription with the appropriate engine.
         // We use catch_unwind to prevent engine panics from poisoning the mutex,
         // which would make the app hang indefinitely on subsequent operations.
@@ -461,77 +487,21 @@ impl TranscriptionManager {
             // Release the lock before transcribing — no mutex held during the engine call
             drop(engine_guard);
 
-            let transcribe_result = catch_unwind(AssertUnwindSafe(
-                || -> Result<transcribe_rs::TranscriptionResult> {
-                    match &mut engine {
-                        LoadedEngine::Whisper(whisper_engine) => {
-                            let whisper_language = if settings.selected_language == "auto" {
-                                None
-                            } else {
-                                let normalized = if settings.selected_language == "zh-Hans"
-                                    || settings.selected_language == "zh-Hant"
-                                {
-                                    "zh".to_string()
-                                } else {
-                                    settings.selected_language.clone()
-                                };
-                                Some(normalized)
-                            };
-
-                            let params = WhisperInferenceParams {
-                                language: whisper_language,
-                                translate: settings.translate_to_english,
-                                ..Default::default()
-                            };
-
-                            whisper_engine
-                                .transcribe_samples(audio, Some(params))
-                                .map_err(|e| anyhow::anyhow!("Whisper transcription failed: {}", e))
-                        }
-                        LoadedEngine::Parakeet(parakeet_engine) => {
-                            let params = ParakeetInferenceParams {
-                                timestamp_granularity: TimestampGranularity::Segment,
-                                ..Default::default()
-                            };
-                            parakeet_engine
-                                .transcribe_samples(audio, Some(params))
-                                .map_err(|e| {
-                                    anyhow::anyhow!("Parakeet transcription failed: {}", e)
-                                })
-                        }
-                        LoadedEngine::Moonshine(moonshine_engine) => moonshine_engine
-                            .transcribe_samples(audio, None)
-                            .map_err(|e| anyhow::anyhow!("Moonshine transcription failed: {}", e)),
-                        LoadedEngine::MoonshineStreaming(streaming_engine) => streaming_engine
-                            .transcribe_samples(audio, None)
-                            .map_err(|e| {
-                                anyhow::anyhow!("Moonshine streaming transcription failed: {}", e)
-                            }),
-                        LoadedEngine::SenseVoice(sense_voice_engine) => {
-                            let language = match settings.selected_language.as_str() {
-                                "zh" | "zh-Hans" | "zh-Hant" => SenseVoiceLanguage::Chinese,
-                                "en" => SenseVoiceLanguage::English,
-                                "ja" => SenseVoiceLanguage::Japanese,
-                                "ko" => SenseVoiceLanguage::Korean,
-                                "yue" => SenseVoiceLanguage::Cantonese,
-                                _ => SenseVoiceLanguage::Auto,
-                            };
-                            let params = SenseVoiceInferenceParams {
-                                language,
-                                use_itn: true,
-                            };
-                            sense_voice_engine
-                                .transcribe_samples(audio, Some(params))
-                                .map_err(|e| {
-                                    anyhow::anyhow!("SenseVoice transcription failed: {}", e)
-                                })
-                        }
-                    }
-                },
-            ));
+            engine_name = engine.name();
+            let inference_start = std::time::Instant::now();
+
+            let transcribe_result = catch_unwind(AssertUnwindSafe(|| {
+                run_engine(
+                    &mut engine,
+                    audio,
+                    &settings.selected_language,
+                    settings.translate_to_english,
+                )
+            }));
 
             match transcribe_result {
                 Ok(inner_result) => {
+                    inference = inference_start.elapsed();
                     // Success or normal error — put the engine back
                     let mut engine_guard = self.lock_engine();
                     *engine_guard = Some(engine);
@@ -540,13 +510,7 @@ impl TranscriptionManager {
                 Err(panic_payload) => {
                     // Engine panicked — do NOT put it back (it's in an unknown state).
                     // The engine is dropped here, effectively unloading it.
-                    let panic_msg = if let Some(s) = panic_payload.downcast_ref::<&str>() {
-                        s.to_string()
-                    } else if let Some(s) = panic_payload.downcast_ref::<String>() {
-                        s.clone()
-                    } else {
-                        "unknown panic".to_string()
-                    };
+                    let panic_msg = panic_message(panic_payload.as_ref());
                     error!(
                         "Transcription engine panicked: {}. Model has been unloaded.",
                         panic_msg
@@ -579,23 +543,7 @@ impl TranscriptionManager {
             }
         };
 
-        // Apply word correction if custom words are configured
-        let corrected_result = if !settings.custom_words.is_empty() {
-            apply_custom_words(
-                &result.text,
-                &settings.custom_words,
-                settings.word_correction_threshold,
-            )
-        } else {
-            result.text
-        };
-
-        // Filter out filler words and hallucinations
-        let filtered_result = filter_transcription_output(
-            &corrected_result,
-            &settings.app_language,
-            &settings.custom_filler_words,
-        );
+        let filtered_result = clean_up_transcript(result.text, &settings);
 
         let et = std::time::Instant::now();
         let translation_note = if settings.translate_to_english {
@@ -617,9 +565,171 @@ impl TranscriptionManager {
             info!("Transcription result: {}", final_result);
         }
 
+        let model_id = self.get_current_model();
         self.maybe_unload_immediately("transcription");
 
-        Ok(final_result)
+        Ok(TranscriptionOutput {
+            text: final_result,
+            model_id,
+            engine: Some(engine_name),
+            inference,
+        })
+    }
+}
+
+fn create_engine(
+    model_id: &str,
+    engine_type: &EngineType,
+    model_path: &Path,
+) -> std::result::Result<LoadedEngine, String> {
+    match engine_type {
+        EngineType::Whisper => {
+            let mut engine = WhisperEngine::new();
+            engine
+                .load_model(model_path)
+                .map_err(|e| format!("Failed to load whisper model {}: {}", model_id, e))?;
+            Ok(LoadedEngine::Whisper(engine))
+        }
+        EngineType::Parakeet => {
+            let mut engine = ParakeetEngine::new();
+            engine
+                .load_model_with_params(model_path, ParakeetModelParams::int8())
+                .map_err(|e| format!("Failed to load parakeet model {}: {}", model_id, e))?;
+            Ok(LoadedEngine::Parakeet(engine))
+        }
+        EngineType::Moonshine => {
+            let mut engine = MoonshineEngine::new();
+            engine
+                .load_model_with_params(
+                    model_path,
+                    MoonshineModelParams::variant(ModelVariant::Base),
+                )
+                .map_err(|e| format!("Failed to load moonshine model {}: {}", model_id, e))?;
+            Ok(LoadedEngine::Moonshine(engine))
+        }
+        EngineType::MoonshineStreaming => {
+            let mut engine = MoonshineStreamingEngine::new();
+            engine
+                .load_model_with_params(model_path, StreamingModelParams::default())
+                .map_err(|e| {
+                    format!(
+                        "Failed to load moonshine streaming model {}: {}",
+                        model_id, e
+                    )
+                })?;
+            Ok(LoadedEngine::MoonshineStreaming(engine))
+        }
+        EngineType::SenseVoice => {
+            let mut engine = SenseVoiceEngine::new();
+            engine
+                .load_model_with_params(model_path, SenseVoiceModelParams::int8())
+                .map_err(|e| format!("Failed to load SenseVoice model {}: {}", model_id, e))?;
+            Ok(LoadedEngine::SenseVoice(engine))
+        }
+    }
+}
+
+fn run_engine(
+    engine: &mut LoadedEngine,
+    audio: Vec<f32>,
+    language: &str,
+    translate: bool,
+) -> Result<transcribe_rs::TranscriptionResult> {
+    match engine {
+        LoadedEngine::Whisper(whisper_engine) => {
+            let whisper_language = if language == "auto" {
+                None
+            } else {
+                let normalized = if language == "zh-Hans" || language == "zh-Hant" {
+                    "zh".to_string()
+                } else {
+                    language.to_string()
+                };
+                Some(normalized)
+            };
+
+            let params = WhisperInferenceParams {
+                language: whisper_language,
+                translate,
+                ..Default::default()
+            };
+
+            whisper_engine
+                .transcribe_samples(audio, Some(params))
+                .map_err(|e| anyhow::anyhow!("Whisper transcription failed: {}", e))
+        }
+        LoadedEngine::Parakeet(parakeet_engine) => {
+            let params = ParakeetInferenceParams {
+                timestamp_granularity: TimestampGranularity::Segment,
+                ..Default::default()
+            };
+            parakeet_engine
+                .transcribe_samples(audio, Some(params))
+                .map_err(|e| anyhow::anyhow!("Parakeet transcription failed: {}", e))
+        }
+        LoadedEngine::Moonshine(moonshine_engine) => moonshine_engine
+            .transcribe_samples(audio, None)
+            .map_err(|e| anyhow::anyhow!("Moonshine transcription failed: {}", e)),
+        LoadedEngine::MoonshineStreaming(streaming_engine) => streaming_engine
+            .transcribe_samples(audio, None)
+            .map_err(|e| anyhow::anyhow!("Moonshine streaming transcription failed: {}", e)),
+        LoadedEngine::SenseVoice(sense_voice_engine) => {
+            let language = match language {
+                "zh" | "zh-Hans" | "zh-Hant" => SenseVoiceLanguage::Chinese,
+                "en" => SenseVoiceLanguage::English,
+                "ja" => SenseVoiceLanguage::Japanese,
+                "ko" => SenseVoiceLanguage::Korean,
+                "yue" => SenseVoiceLanguage::Cantonese,
+                _ => SenseVoiceLanguage::Auto,
+            };
+            let params = SenseVoiceInferenceParams {
+                language,
+                use_itn: true,
+            };
+            sense_voice_engine
+                .transcribe_samples(audio, Some(params))
+                .map_err(|e| anyhow::anyhow!("SenseVoice transcription failed: {}", e))
+        }
+    }
+}
+
+/// Applies custom words, replacements and the filler word filter.
+fn clean_up_transcript(text: String, settings: &AppSettings) -> String {
+    // Apply word correction if custom words are configured
+    let corrected_result = if !settings.custom_words.is_empty() {
+        apply_custom_words(
+            &text,
+            &settings.custom_words,
+            settings.word_correction_threshold,
+        )
+    } else {
+        text
+    };
+
+    // Then whole-phrase replacements, including ones learned from edits
+    let corrected_result = apply_replacements(
+        &corrected_result,
+        settings
+            .word_replacements
+            .iter()
+            .map(|r| (r.from.as_str(), r.to.as_str())),
+    );
+
+    // Filter out filler words and hallucinations
+    filter_transcription_output(
+        &corrected_result,
+        &settings.app_language,
+        &settings.custom_filler_words,
+    )
+}
+
+fn panic_message(payload: &(dyn std::any::Any + Send)) -> String {
+    if let Some(s) = payload.downcast_ref::<&str>() {
+        s.to_string()
+    } else if let Some(s) = payload.downcast_ref::<String>() {
+        s.clone()
+    } else {
+        "unknown panic".to_string()
     }
 }
 
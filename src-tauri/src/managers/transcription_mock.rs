@@ -6,6 +6,7 @@ use crate::managers::model::ModelManager;
 use anyhow::Result;
 use serde::Serialize;
 use std::sync::Arc;
+use std::time::Duration;
 use tauri::AppHandle;
 
 #[derive(Clone, Debug, Serialize)]
@@ -16,6 +17,24 @@ pub struct ModelStateEvent {
     pub error: Option<String>,
 }
 
+#[derive(Clone, Debug, Default)]
+pub struct TranscriptionOutput {
+    pub text: String,
+    pub model_id: Option<String>,
+    pub engine: Option<&'static str>,
+    pub inference: Duration,
+}
+
+pub struct DetachedEngine {
+    model_id: String,
+}
+
+impl DetachedEngine {
+    pub fn is_ready_for(&self, model_id: &str) -> bool {
+        self.model_id == model_id
+    }
+}
+
 #[derive(Clone)]
 pub struct TranscriptionManager {
     #[allow(dead_code)]
@@ -49,7 +68,26 @@ impl TranscriptionManager {
         None
     }
 
+    pub fn load_detached(&self, model_id: &str) -> Result<DetachedEngine> {
+        Ok(DetachedEngine {
+            model_id: model_id.to_string(),
+        })
+    }
+
+    pub fn transcribe_detached(
+        &self,
+        _detached: &mut DetachedEngine,
+        _audio: Vec<f32>,
+        _language: &str,
+    ) -> Result<TranscriptionOutput> {
+        Ok(TranscriptionOutput::default())
+    }
+
     pub fn transcribe(&self, _audio: Vec<f32>) -> Result<String> {
         Ok(String::new())
     }
+
+    pub fn transcribe_detailed(&self, _audio: Vec<f32>) -> Result<TranscriptionOutput> {
+        Ok(TranscriptionOutput::default())
+    }
 }
@@ -0,0 +1,424 @@
+//! Quiets other audio while recording, by muting or ducking the default
+//! output or by pausing media players.
+//!
+//! Whatever is changed is written to disk before it is changed, so if the app
+//! dies mid-recording the next launch can still put things back.
+
+use crate::settings::RecordingOutputAction;
+use log::{debug, error, warn};
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::{Path, PathBuf};
+use tauri::{AppHandle, Manager};
+
+const STATE_FILE: &str = "output_state.json";
+
+/// What was done to the output, and what it takes to undo it.
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct OutputState {
+    #[serde(default)]
+    muted: bool,
+    /// Output volume before ducking, from 0.0 to 1.0.
+    #[serde(default)]
+    volume: Option<f32>,
+    /// D-Bus names of the media players that were paused.
+    #[serde(default)]
+    paused_players: Vec<String>,
+}
+
+fn state_path(app: &AppHandle) -> Option<PathBuf> {
+    match app.path().app_data_dir() {
+        Ok(dir) => Some(dir.join(STATE_FILE)),
+        Err(e) => {
+            error!("Failed to resolve app data dir: {}", e);
+            None
+        }
+    }
+}
+
+fn save_state(path: &Path, state: &OutputState) -> bool {
+    let written = serde_json::to_vec(state)
+        .map_err(|e| e.to_string())
+        .and_then(|json| {
+            if let Some(dir) = path.parent() {
+                fs::create_dir_all(dir).map_err(|e| e.to_string())?;
+            }
+            fs::write(path, json).map_err(|e| e.to_string())
+        });
+    if let Err(e) = &written {
+        error!("Failed to save output state: {}", e);
+    }
+    written.is_ok()
+}
+
+/// Quiets the output for a recording. Does nothing if an earlier change has
+/// not been restored yet, so the original volume is never overwritten.
+pub fn quiet_output(app: &AppHandle, action: RecordingOutputAction) {
+    let Some(path) = state_path(app) else {
+        return;
+    };
+    if path.exists() {
+        debug!("Output already quieted");
+        return;
+    }
+
+    match effective_action(action) {
+        RecordingOutputAction::Mute => {
+            let state = OutputState {
+                muted: true,
+                ..Default::default()
+            };
+            if save_state(&path, &state) {
+                set_mute(true);
+            }
+        }
+        RecordingOutputAction::Duck => duck_output(&path, app),
+        RecordingOutputAction::PauseMedia => pause_media(&path),
+    }
+}
+
+/// Undoes whatever [`quiet_output`] did, including in an earlier run of the
+/// app that never got to restore it.
+pub fn restore_output(app: &AppHandle) {
+    let Some(path) = state_path(app) else {
+        return;
+    };
+    let state: OutputState = match fs::read(&path) {
+        Ok(bytes) => serde_json::from_slice(&bytes).unwrap_or_else(|e| {
+            warn!("Unreadable output state, unmuting: {}", e);
+            OutputState {
+                muted: true,
+                ..Default::default()
+            }
+        }),
+        Err(_) => return,
+    };
+
+    if state.muted {
+        set_mute(false);
+    }
+    if let Some(volume) = state.volume {
+        restore_volume(volume);
+    }
+    if !state.paused_players.is_empty() {
+        resume_media(&state.paused_players);
+    }
+
+    if let Err(e) = fs::remove_file(&path) {
+        error!("Failed to remove output state: {}", e);
+    }
+    debug!("Output restored");
+}
+
+/// Ducking and media control are only available on Linux; elsewhere the
+/// output is muted instead.
+fn effective_action(action: RecordingOutputAction) -> RecordingOutputAction {
+    if cfg!(target_os = "linux") {
+        action
+    } else {
+        RecordingOutputAction::Mute
+    }
+}
+
+#[cfg(target_os = "linux")]
+fn duck_output(path: &Path, app: &AppHandle) {
+    let Some(volume) = linux::get_volume() else {
+        warn!("Could not read the output volume, not ducking");
+        return;
+    };
+    let percent = crate::settings::get_settings(app)
+        .duck_volume_percent
+        .min(100);
+    let state = OutputState {
+        volume: Some(volume),
+        ..Default::default()
+    };
+    if save_state(path, &state) {
+        linux::set_volume(volume * percent as f32 / 100.0);
+    }
+}
+
+#[cfg(not(target_os = "linux"))]
+fn duck_output(_path: &Path, _app: &AppHandle) {}
+
+#[cfg(target_os = "linux")]
+fn restore_volume(volume: f32) {
+    linux::set_volume(volume);
+}
+
+#[cfg(not(target_os = "linux"))]
+fn restore_volume(_volume: f32) {}
+
+#[cfg(target_os = "linux")]
+fn pause_media(path: &Path) {
+    let conn = match linux::session() {
+        Ok(conn) => conn,
+        Err(e) => {
+            warn!("Failed to connect to the session bus: {}", e);
+            return;
+        }
+    };
+    let players = match linux::playing_players(&conn) {
+        Ok(players) => players,
+        Err(e) => {
+            warn!("Failed to list media players: {}", e);
+            return;
+        }
+    };
+    if players.is_empty() {
+        return;
+    }
+
+    let state = OutputState {
+        paused_players: players,
+        ..Default::default()
+    };
+    if save_state(path, &state) {
+        for player in &state.paused_players {
+            if let Err(e) = linux::call_player(&conn, player, "Pause") {
+                warn!("Failed to pause {}: {}", player, e);
+            }
+        }
+    }
+}
+
+#[cfg(not(target_os = "linux"))]
+fn pause_media(_path: &Path) {}
+
+#[cfg(target_os = "linux")]
+fn resume_media(players: &[String]) {
+    let conn = match linux::session() {
+        Ok(conn) => conn,
+        Err(e) => {
+            warn!("Failed to connect to the session bus: {}", e);
+            return;
+        }
+    };
+    for player in players {
+        // Players that quit in the meantime are simply gone
+        if let Err(e) = linux::call_player(&conn, player, "Play") {
+            debug!("Failed to resume {}: {}", player, e);
+        }
+    }
+}
+
+#[cfg(not(target_os = "linux"))]
+fn resume_media(_players: &[String]) {}
+
+fn set_mute(mute: bool) {
+    // Expected behavior:
+    // - Windows: works on most systems using standard audio drivers.
+    // - Linux: works on many systems (PipeWire, PulseAudio, ALSA),
+    //   but some distros may lack the tools used.
+    // - macOS: works on most standard setups via AppleScript.
+    // If unsupported, fails silently.
+
+    #[cfg(target_os = "windows")]
+    {
+        unsafe {
+            use windows::Win32::{
+                Media::Audio::{
+                    eMultimedia, eRender, Endpoints::IAudioEndpointVolume, IMMDeviceEnumerator,
+                    MMDeviceEnumerator,
+                },
+                System::Com::{CoCreateInstance, CoInitializeEx, CLSCTX_ALL, COINIT_MULTITHREADED},
+            };
+
+            macro_rules! unwrap_or_return {
+                ($expr:expr) => {
+                    match $expr {
+                        Ok(val) => val,
+                        Err(_) => return,
+                    }
+                };
+            }
+
+            // Initialize the COM library for this thread.
+            // If already initialized (e.g., by another library like Tauri), this does nothing.
+            let _ = CoInitializeEx(None, COINIT_MULTITHREADED);
+
+            let all_devices: IMMDeviceEnumerator =
+                unwrap_or_return!(CoCreateInstance(&MMDeviceEnumerator, None, CLSCTX_ALL));
+            let default_device =
+                unwrap_or_return!(all_devices.GetDefaultAudioEndpoint(eRender, eMultimedia));
+            let volume_interface = unwrap_or_return!(
+                default_device.Activate::<IAudioEndpointVolume>(CLSCTX_ALL, None)
+            );
+
+            let _ = volume_interface.SetMute(mute, std::ptr::null());
+        }
+    }
+
+    #[cfg(target_os = "linux")]
+    {
+        use std::process::Command;
+
+        let mute_val = if mute { "1" } else { "0" };
+        let amixer_state = if mute { "mute" } else { "unmute" };
+
+        // Try multiple backends to increase compatibility
+        // 1. PipeWire (wpctl)
+        if Command::new("wpctl")
+            .args(["set-mute", "@DEFAULT_AUDIO_SINK@", mute_val])
+            .output()
+            .map(|o| o.status.success())
+            .unwrap_or(false)
+        {
+            return;
+        }
+
+        // 2. PulseAudio (pactl)
+        if Command::new("pactl")
+            .args(["set-sink-mute", "@DEFAULT_SINK@", mute_val])
+            .output()
+            .map(|o| o.status.success())
+            .unwrap_or(false)
+        {
+            return;
+        }
+
+        // 3. ALSA (amixer)
+        let _ = Command::new("amixer")
+            .args(["set", "Master", amixer_state])
+            .output();
+    }
+
+    #[cfg(target_os = "macos")]
+    {
+        use std::process::Command;
+        let script = format!(
+            "set volume output muted {}",
+            if mute { "true" } else { "false" }
+        );
+        let _ = Command::new("osascript").args(["-e", &script]).output();
+    }
+}
+
+#[cfg(target_os = "linux")]
+mod linux {
+    use log::debug;
+    use std::process::Command;
+    use zbus::blocking::{fdo::DBusProxy, Connection, Proxy};
+
+    const MPRIS_PREFIX: &str = "org.mpris.MediaPlayer2.";
+    const MPRIS_PATH: &str = "/org/mpris/MediaPlayer2";
+    const MPRIS_PLAYER: &str = "org.mpris.MediaPlayer2.Player";
+
+    fn command_output(program: &str, args: &[&str]) -> Option<String> {
+        let output = Command::new(program).args(args).output().ok()?;
+        output
+            .status
+            .success()
+            .then(|| String::from_utf8_lossy(&output.stdout).into_owned())
+    }
+
+    /// Volume of the default sink, from 0.0 to 1.0, using the same backends
+    /// as muting.
+    pub fn get_volume() -> Option<f32> {
+        command_output("wpctl", &["get-volume", "@DEFAULT_AUDIO_SINK@"])
+            .and_then(|out| parse_wpctl_volume(&out))
+            .or_else(|| {
+                command_output("pactl", &["get-sink-volume", "@DEFAULT_SINK@"])
+                    .and_then(|out| parse_percent(&out))
+            })
+            .or_else(|| {
+                command_output("amixer", &["get", "Master"]).and_then(|out| parse_percent(&out))
+            })
+    }
+
+    pub fn set_volume(volume: f32) {
+        let volume = volume.clamp(0.0, 1.0);
+        let percent = format!("{}%", (volume * 100.0).round() as u32);
+
+        if command_output(
+            "wpctl",
+            &[
+                "set-volume",
+                "@DEFAULT_AUDIO_SINK@",
+                &format!("{volume:.2}"),
+            ],
+        )
+        .is_some()
+        {
+            return;
+        }
+        if command_output("pactl", &["set-sink-volume", "@DEFAULT_SINK@", &percent]).is_some() {
+            return;
+        }
+        let _ = command_output("amixer", &["set", "Master", &percent]);
+    }
+
+    /// Parses `wpctl get-volume` output such as `Volume: 0.40 [MUTED]`.
+    fn parse_wpctl_volume(output: &str) -> Option<f32> {
+        output
+            .trim()
+            .strip_prefix("Volume:")?
+            .split_whitespace()
+            .next()?
+            .parse()
+            .ok()
+    }
+
+    /// Finds the first `NN%` in `pactl` or `amixer` output.
+    fn parse_percent(output: &str) -> Option<f32> {
+        output
+            .split(|c: char| c.is_whitespace() || c == '[' || c == ']' || c == '/')
+            .find_map(|token| token.strip_suffix('%')?.parse::<f32>().ok())
+            .map(|percent| percent / 100.0)
+    }
+
+    fn player<'a>(conn: &Connection, name: &'a str) -> zbus::Result<Proxy<'a>> {
+        Proxy::new(conn, name, MPRIS_PATH, MPRIS_PLAYER)
+    }
+
+    /// Connection to the session bus, shared by the player calls that follow.
+    pub fn session() -> zbus::Result<Connection> {
+        Connection::session()
+    }
+
+    /// D-Bus names of the MPRIS players that are currently playing. Players
+    /// that can't be queried are left out rather than failing the scan.
+    pub fn playing_players(conn: &Connection) -> zbus::Result<Vec<String>> {
+        let mut playing = Vec::new();
+        for name in DBusProxy::new(conn)?.list_names()? {
+            if !name.starts_with(MPRIS_PREFIX) {
+                continue;
+            }
+            let status = player(conn, name.as_str())
+                .and_then(|proxy| proxy.get_property::<String>("PlaybackStatus"));
+            match status.as_deref() {
+                Ok("Playing") => playing.push(name.to_string()),
+                Ok(_) => {}
+                Err(e) => debug!("Skipping media player {}: {}", name, e),
+            }
+        }
+        Ok(playing)
+    }
+
+    pub fn call_player(conn: &Connection, name: &str, method: &str) -> zbus::Result<()> {
+        player(conn, name)?.call_method(method, &())?;
+        Ok(())
+    }
+
+    #[cfg(test)]
+    mod tests {
+        use super::*;
+
+        #[test]
+        fn parses_wpctl_volume() {
+            assert_eq!(parse_wpctl_volume("Volume: 0.40\n"), Some(0.40));
+            assert_eq!(parse_wpctl_volume("Volume: 1.00 [MUTED]\n"), Some(1.0));
+            assert_eq!(parse_wpctl_volume("error"), None);
+        }
+
+        #[test]
+        fn parses_pactl_and_amixer_percentages() {
+            let pactl = "Volume: front-left: 26214 /  40% / -23.88 dB,   front-right: 26214 /  40% / -23.88 dB\n";
+            assert_eq!(parse_percent(pactl), Some(0.40));
+
+            let amixer =
+                "Simple mixer control 'Master',0\n  Mono: Playback 55 [86%] [-7.50dB] [on]\n";
+            assert_eq!(parse_percent(amixer), Some(0.86));
+        }
+    }
+}
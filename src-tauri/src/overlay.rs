@@ -313,6 +313,11 @@ pub fn show_recording_overlay(app_handle: &AppHandle) {
     show_overlay_state(app_handle, "recording");
 }
 
+/// Shows the overlay in its paused state
+pub fn show_paused_overlay(app_handle: &AppHandle) {
+    show_overlay_state(app_handle, "paused");
+}
+
 /// Shows the transcribing overlay window
 pub fn show_transcribing_overlay(app_handle: &AppHandle) {
     show_overlay_state(app_handle, "transcribing");
@@ -354,6 +359,16 @@ pub fn hide_recording_overlay(app_handle: &AppHandle) {
     }
 }
 
+/// Tells the app and the overlay how many seconds remain before the current
+/// recording is stopped at its maximum duration.
+pub fn emit_recording_limit_approaching(app_handle: &AppHandle, remaining_secs: u64) {
+    let _ = app_handle.emit("recording-limit-approaching", remaining_secs);
+
+    if let Some(overlay_window) = app_handle.get_webview_window("recording_overlay") {
+        let _ = overlay_window.emit("recording-limit-approaching", remaining_secs);
+    }
+}
+
 pub fn emit_levels(app_handle: &AppHandle, levels: &Vec<f32>) {
     // emit levels to main app
     let _ = app_handle.emit("mic-level", levels);
@@ -0,0 +1,121 @@
+//! The `recording` URI scheme, which serves history recordings to the
+//! webview. Encrypted recordings are decrypted in memory, so playing one
+//! never leaves a plaintext copy on disk.
+
+use crate::managers::history::HistoryManager;
+use log::debug;
+use percent_encoding::percent_decode_str;
+use std::ops::RangeInclusive;
+use std::path::Path;
+use std::sync::Arc;
+use tauri::http::{header, Request, Response, StatusCode};
+use tauri::{AppHandle, Manager, UriSchemeContext, UriSchemeResponder};
+
+pub const RECORDING_SCHEME: &str = "recording";
+
+pub fn handle_request(
+    ctx: UriSchemeContext<'_, tauri::Wry>,
+    request: Request<Vec<u8>>,
+    responder: UriSchemeResponder,
+) {
+    let app = ctx.app_handle().clone();
+    // Decrypting a long recording takes a moment, so keep it off the webview
+    tauri::async_runtime::spawn_blocking(move || responder.respond(respond(&app, &request)));
+}
+
+fn respond(app: &AppHandle, request: &Request<Vec<u8>>) -> Response<Vec<u8>> {
+    let file_name = percent_decode_str(request.uri().path().trim_start_matches('/'))
+        .decode_utf8_lossy()
+        .into_owned();
+    let history_manager = app.state::<Arc<HistoryManager>>();
+    let data = match history_manager.read_recording_file(&file_name) {
+        Ok(data) => data,
+        Err(e) => {
+            debug!("Can't serve recording {}: {}", file_name, e);
+            return empty_response(StatusCode::NOT_FOUND);
+        }
+    };
+
+    let total = data.len();
+    let builder = Response::builder()
+        .header(header::CONTENT_TYPE, content_type(&file_name))
+        .header(header::ACCEPT_RANGES, "bytes");
+    // Media elements seek with range requests, and WebKit insists on them
+    let range = request
+        .headers()
+        .get(header::RANGE)
+        .and_then(|value| value.to_str().ok())
+        .and_then(|value| parse_range(value, total));
+    let response = match range {
+        Some(range) => builder
+            .status(StatusCode::PARTIAL_CONTENT)
+            .header(
+                header::CONTENT_RANGE,
+                format!("bytes {}-{}/{}", range.start(), range.end(), total),
+            )
+            .body(data[range].to_vec()),
+        None => builder.body(data),
+    };
+    response.unwrap_or_else(|_| empty_response(StatusCode::INTERNAL_SERVER_ERROR))
+}
+
+fn empty_response(status: StatusCode) -> Response<Vec<u8>> {
+    let mut response = Response::new(Vec::new());
+    *response.status_mut() = status;
+    response
+}
+
+fn content_type(file_name: &str) -> &'static str {
+    let extension = Path::new(file_name)
+        .extension()
+        .and_then(|ext| ext.to_str())
+        .unwrap_or_default()
+        .to_ascii_lowercase();
+    match extension.as_str() {
+        "wav" => "audio/wav",
+        "flac" => "audio/flac",
+        "opus" | "ogg" => "audio/ogg",
+        _ => "application/octet-stream",
+    }
+}
+
+/// The bytes asked for by a single-range `Range` header, or `None` when the
+/// header is malformed or can't be satisfied, in which case the whole file is
+/// served.
+fn parse_range(value: &str, total: usize) -> Option<RangeInclusive<usize>> {
+    let (start, end) = value.strip_prefix("bytes=")?.trim().split_once('-')?;
+    let last = total.checked_sub(1)?;
+    let (start, end) = match (start.trim(), end.trim()) {
+        // The last `suffix` bytes
+        ("", suffix) => {
+            let suffix: usize = suffix.parse().ok()?;
+            (total.checked_sub(suffix.min(total))?, last)
+        }
+        (start, "") => (start.parse().ok()?, last),
+        (start, end) => (start.parse().ok()?, end.parse::<usize>().ok()?.min(last)),
+    };
+    (start <= end).then_some(start..=end)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_single_byte_ranges() {
+        assert_eq!(parse_range("bytes=0-", 100), Some(0..=99));
+        assert_eq!(parse_range("bytes=10-19", 100), Some(10..=19));
+        assert_eq!(parse_range("bytes=90-200", 100), Some(90..=99));
+        assert_eq!(parse_range("bytes=-10", 100), Some(90..=99));
+        assert_eq!(parse_range("bytes=-500", 100), Some(0..=99));
+    }
+
+    #[test]
+    fn ignores_ranges_it_cannot_satisfy() {
+        assert_eq!(parse_range("bytes=100-", 100), None);
+        assert_eq!(parse_range("bytes=20-10", 100), None);
+        assert_eq!(parse_range("bytes=0-", 0), None);
+        assert_eq!(parse_range("items=0-10", 100), None);
+        assert_eq!(parse_range("bytes=0-10,20-30", 100), None);
+    }
+}
@@ -113,6 +113,19 @@ pub enum PasteMethod {
     CtrlShiftV,
 }
 
+impl PasteMethod {
+    /// The serialized name, as stored in history.
+    pub fn as_str(self) -> &'static str {
+        match self {
+            PasteMethod::CtrlV => "ctrl_v",
+            PasteMethod::Direct => "direct",
+            PasteMethod::None => "none",
+            PasteMethod::ShiftInsert => "shift_insert",
+            PasteMethod::CtrlShiftV => "ctrl_shift_v",
+        }
+    }
+}
+
 #[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, Type)]
 #[serde(rename_all = "snake_case")]
 pub enum ClipboardHandling {
@@ -128,6 +141,106 @@ pub enum RecordingRetentionPeriod {
     Days3,
     Weeks2,
     Months3,
+    /// Keep every transcript, but delete audio older than
+    /// `audio_retention_days`.
+    TextOnly,
+    /// Keep every transcript, deleting the oldest audio once recordings take
+    /// more than `recordings_quota_mb`.
+    DiskQuota,
+}
+
+/// How recordings kept in history are stored on disk.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, Type)]
+#[serde(rename_all = "snake_case")]
+pub enum RecordingFormat {
+    Wav,
+    Flac,
+    Opus,
+}
+
+impl Default for RecordingFormat {
+    fn default() -> Self {
+        RecordingFormat::Wav
+    }
+}
+
+/// How other audio is kept out of the way while recording.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, Type)]
+#[serde(rename_all = "snake_case")]
+pub enum RecordingOutputAction {
+    /// Mute the default output device.
+    Mute,
+    /// Lower the default output to `duck_volume_percent` of its volume.
+    Duck,
+    /// Pause media players that are playing, and resume them afterwards.
+    PauseMedia,
+}
+
+impl Default for RecordingOutputAction {
+    fn default() -> Self {
+        RecordingOutputAction::Mute
+    }
+}
+
+/// Device selection remembered for an audio host other than the active one.
+#[derive(Serialize, Deserialize, Debug, Clone, Default, Type)]
+pub struct HostDeviceSettings {
+    #[serde(default)]
+    pub microphone_priority: Vec<String>,
+    #[serde(default)]
+    pub selected_output_device: Option<String>,
+}
+
+/// Text substituted for a phrase in every transcription, matched as whole
+/// words regardless of case.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq, Type)]
+pub struct WordReplacement {
+    pub from: String,
+    pub to: String,
+}
+
+/// What the recorder captures.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, Type)]
+#[serde(rename_all = "snake_case")]
+pub enum CaptureSource {
+    Microphone,
+    /// A monitor source, i.e. what the computer is playing.
+    SystemAudio,
+    /// The microphone with a monitor source mixed in.
+    MicrophoneAndSystemAudio,
+}
+
+impl Default for CaptureSource {
+    fn default() -> Self {
+        CaptureSource::Microphone
+    }
+}
+
+/// How a multi-channel microphone is reduced to mono.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, Type)]
+#[serde(rename_all = "snake_case")]
+pub enum ChannelSelection {
+    Average,
+    Loudest,
+    Channel(u16),
+}
+
+impl Default for ChannelSelection {
+    fn default() -> Self {
+        ChannelSelection::Average
+    }
+}
+
+impl From<ChannelSelection> for crate::audio_toolkit::ChannelMix {
+    fn from(selection: ChannelSelection) -> Self {
+        match selection {
+            ChannelSelection::Average => crate::audio_toolkit::ChannelMix::Average,
+            ChannelSelection::Loudest => crate::audio_toolkit::ChannelMix::Loudest,
+            ChannelSelection::Channel(index) => {
+                crate::audio_toolkit::ChannelMix::Channel(index as usize)
+            }
+        }
+    }
 }
 
 #[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, Type)]
@@ -230,6 +343,10 @@ pub struct AppSettings {
     pub audio_feedback_volume: f32,
     #[serde(default = "default_sound_theme")]
     pub sound_theme: SoundTheme,
+    /// Folder in the sound themes directory whose sounds replace the built-in
+    /// theme's, event by event.
+    #[serde(default)]
+    pub sound_theme_folder: Option<String>,
     #[serde(default = "default_start_hidden")]
     pub start_hidden: bool,
     #[serde(default = "default_autostart_enabled")]
@@ -240,12 +357,36 @@ pub struct AppSettings {
     pub selected_model: String,
     #[serde(default = "default_always_on_microphone")]
     pub always_on_microphone: bool,
+    /// Legacy single-device setting, folded into `microphone_priority` on load.
     #[serde(default)]
     pub selected_microphone: Option<String>,
+    /// Legacy clamshell-mode device, folded into `microphone_priority` on load.
     #[serde(default)]
     pub clamshell_microphone: Option<String>,
+    /// Preferred input devices, most preferred first. Entries may contain
+    /// `*` and `?` wildcards. An empty list means the system default.
+    #[serde(default)]
+    pub microphone_priority: Vec<String>,
+    /// Channel selection per input device name.
+    #[serde(default)]
+    pub microphone_channels: HashMap<String, ChannelSelection>,
+    /// Software gain multiplier per input device name.
+    #[serde(default)]
+    pub microphone_gains: HashMap<String, f32>,
+    #[serde(default)]
+    pub audio_source: CaptureSource,
+    /// Monitor source to capture; `None` follows the default output device.
+    #[serde(default)]
+    pub monitor_source: Option<String>,
     #[serde(default)]
     pub selected_output_device: Option<String>,
+    /// cpal host to use; `None` picks the platform default.
+    #[serde(default)]
+    pub audio_host: Option<String>,
+    /// Device selections of inactive hosts, keyed by host name. Device names
+    /// differ between hosts, so switching swaps these in and out.
+    #[serde(default)]
+    pub host_devices: HashMap<String, HostDeviceSettings>,
     #[serde(default = "default_translate_to_english")]
     pub translate_to_english: bool,
     #[serde(default = "default_selected_language")]
@@ -259,6 +400,8 @@ pub struct AppSettings {
     #[serde(default)]
     pub custom_words: Vec<String>,
     #[serde(default)]
+    pub word_replacements: Vec<WordReplacement>,
+    #[serde(default)]
     pub model_unload_timeout: ModelUnloadTimeout,
     #[serde(default = "default_word_correction_threshold")]
     pub word_correction_threshold: f64,
@@ -266,6 +409,16 @@ pub struct AppSettings {
     pub history_limit: usize,
     #[serde(default = "default_recording_retention_period")]
     pub recording_retention_period: RecordingRetentionPeriod,
+    #[serde(default = "default_audio_retention_days")]
+    pub audio_retention_days: u32,
+    #[serde(default = "default_recordings_quota_mb")]
+    pub recordings_quota_mb: u64,
+    #[serde(default)]
+    pub crash_safe_recording: bool,
+    #[serde(default)]
+    pub recording_format: RecordingFormat,
+    #[serde(default = "default_opus_bitrate_kbps")]
+    pub opus_bitrate_kbps: u32,
     #[serde(default)]
     pub paste_method: PasteMethod,
     #[serde(default)]
@@ -273,6 +426,22 @@ pub struct AppSettings {
     #[serde(default)]
     pub mute_while_recording: bool,
     #[serde(default)]
+    pub recording_output_action: RecordingOutputAction,
+    #[serde(default = "default_duck_volume_percent")]
+    pub duck_volume_percent: u8,
+    #[serde(default)]
+    pub high_pass_filter: bool,
+    #[serde(default)]
+    pub noise_reduction: bool,
+    #[serde(default)]
+    pub auto_gain: bool,
+    #[serde(default)]
+    pub max_recording_duration_secs: Option<u32>,
+    /// Audio kept in memory while recording before the rest is written to
+    /// disk; `None` keeps the whole recording in memory.
+    #[serde(default = "default_spill_after_secs")]
+    pub spill_after_secs: Option<u32>,
+    #[serde(default)]
     pub append_trailing_space: bool,
     #[serde(default = "default_app_language")]
     pub app_language: String,
@@ -343,6 +512,26 @@ fn default_recording_retention_period() -> RecordingRetentionPeriod {
     RecordingRetentionPeriod::PreserveLimit
 }
 
+fn default_audio_retention_days() -> u32 {
+    30
+}
+
+fn default_recordings_quota_mb() -> u64 {
+    1024
+}
+
+fn default_opus_bitrate_kbps() -> u32 {
+    24
+}
+
+fn default_duck_volume_percent() -> u8 {
+    20
+}
+
+fn default_spill_after_secs() -> Option<u32> {
+    Some(5 * 60)
+}
+
 fn default_audio_feedback_volume() -> f32 {
     1.0
 }
@@ -369,6 +558,12 @@ pub fn get_default_settings() -> AppSettings {
     #[cfg(not(any(target_os = "windows", target_os = "macos", target_os = "linux")))]
     let default_shortcut = "alt+space";
 
+    #[cfg(target_os = "macos")]
+    let (default_pause_shortcut, default_append_shortcut) =
+        ("option+shift+space", "control+option+space");
+    #[cfg(not(target_os = "macos"))]
+    let (default_pause_shortcut, default_append_shortcut) = ("ctrl+shift+space", "ctrl+alt+space");
+
     let mut bindings = HashMap::new();
     bindings.insert(
         "transcribe".to_string(),
@@ -380,6 +575,16 @@ pub fn get_default_settings() -> AppSettings {
             current_binding: default_shortcut.to_string(),
         },
     );
+    bindings.insert(
+        "append".to_string(),
+        ShortcutBinding {
+            id: "append".to_string(),
+            name: "Append".to_string(),
+            description: "Adds your speech to the previous transcription.".to_string(),
+            default_binding: default_append_shortcut.to_string(),
+            current_binding: default_append_shortcut.to_string(),
+        },
+    );
     bindings.insert(
         "cancel".to_string(),
         ShortcutBinding {
@@ -390,6 +595,16 @@ pub fn get_default_settings() -> AppSettings {
             current_binding: "escape".to_string(),
         },
     );
+    bindings.insert(
+        "pause".to_string(),
+        ShortcutBinding {
+            id: "pause".to_string(),
+            name: "Pause".to_string(),
+            description: "Pauses or resumes the current recording.".to_string(),
+            default_binding: default_pause_shortcut.to_string(),
+            current_binding: default_pause_shortcut.to_string(),
+        },
+    );
 
     AppSettings {
         bindings,
@@ -397,6 +612,7 @@ pub fn get_default_settings() -> AppSettings {
         audio_feedback: false,
         audio_feedback_volume: default_audio_feedback_volume(),
         sound_theme: default_sound_theme(),
+        sound_theme_folder: None,
         start_hidden: default_start_hidden(),
         autostart_enabled: default_autostart_enabled(),
         update_checks_enabled: default_update_checks_enabled(),
@@ -404,20 +620,40 @@ pub fn get_default_settings() -> AppSettings {
         always_on_microphone: false,
         selected_microphone: None,
         clamshell_microphone: None,
+        microphone_priority: Vec::new(),
+        microphone_channels: HashMap::new(),
+        microphone_gains: HashMap::new(),
+        audio_source: CaptureSource::default(),
+        monitor_source: None,
         selected_output_device: None,
+        audio_host: None,
+        host_devices: HashMap::new(),
         translate_to_english: false,
         selected_language: "auto".to_string(),
         overlay_position: default_overlay_position(),
         debug_mode: false,
         log_level: default_log_level(),
         custom_words: Vec::new(),
+        word_replacements: Vec::new(),
         model_unload_timeout: ModelUnloadTimeout::Never,
         word_correction_threshold: default_word_correction_threshold(),
         history_limit: default_history_limit(),
         recording_retention_period: default_recording_retention_period(),
+        audio_retention_days: default_audio_retention_days(),
+        recordings_quota_mb: default_recordings_quota_mb(),
+        crash_safe_recording: false,
+        recording_format: RecordingFormat::default(),
+        opus_bitrate_kbps: default_opus_bitrate_kbps(),
         paste_method: PasteMethod::default(),
         clipboard_handling: ClipboardHandling::default(),
         mute_while_recording: false,
+        recording_output_action: RecordingOutputAction::default(),
+        duck_volume_percent: default_duck_volume_percent(),
+        high_pass_filter: false,
+        noise_reduction: false,
+        auto_gain: false,
+        max_recording_duration_secs: None,
+        spill_after_secs: default_spill_after_secs(),
         append_trailing_space: false,
         app_language: default_app_language(),
         keyboard_implementation: KeyboardImplementation::default(),
@@ -426,6 +662,43 @@ pub fn get_default_settings() -> AppSettings {
     }
 }
 
+/// Moves the legacy `clamshell_microphone` and `selected_microphone` values into
+/// `microphone_priority`. The clamshell device goes first since it is normally
+/// only present while docked. Returns true if the settings were changed.
+fn migrate_microphone_priority(settings: &mut AppSettings) -> bool {
+    let legacy = [
+        settings.clamshell_microphone.take(),
+        settings.selected_microphone.take(),
+    ];
+
+    let mut updated = false;
+    for name in legacy.into_iter().flatten() {
+        updated = true;
+        if !settings.microphone_priority.contains(&name) {
+            settings.microphone_priority.push(name);
+        }
+    }
+    updated
+}
+
+/// Stores the device selection of `from_host` and restores the one last used
+/// with `to_host`.
+pub fn switch_audio_host(settings: &mut AppSettings, from_host: &str, to_host: &str) {
+    if from_host == to_host {
+        return;
+    }
+
+    let current = HostDeviceSettings {
+        microphone_priority: std::mem::take(&mut settings.microphone_priority),
+        selected_output_device: settings.selected_output_device.take(),
+    };
+    settings.host_devices.insert(from_host.to_string(), current);
+
+    let restored = settings.host_devices.remove(to_host).unwrap_or_default();
+    settings.microphone_priority = restored.microphone_priority;
+    settings.selected_output_device = restored.selected_output_device;
+}
+
 pub fn load_or_create_app_settings(app: &AppHandle) -> AppSettings {
     // Initialize store
     let store = app
@@ -449,6 +722,11 @@ pub fn load_or_create_app_settings(app: &AppHandle) -> AppSettings {
                     }
                 }
 
+                if migrate_microphone_priority(&mut settings) {
+                    debug!("Migrated microphone selection into priority list");
+                    updated = true;
+                }
+
                 if updated {
                     debug!("Settings updated with new bindings");
                     store.set("settings", serde_json::to_value(&settings).unwrap());
@@ -479,11 +757,19 @@ pub fn get_settings(app: &AppHandle) -> AppSettings {
         .expect("Failed to initialize store");
 
     let settings = if let Some(settings_value) = store.get("settings") {
-        serde_json::from_value::<AppSettings>(settings_value).unwrap_or_else(|_| {
-            let default_settings = get_default_settings();
-            store.set("settings", serde_json::to_value(&default_settings).unwrap());
-            default_settings
-        })
+        match serde_json::from_value::<AppSettings>(settings_value) {
+            Ok(mut settings) => {
+                if migrate_microphone_priority(&mut settings) {
+                    store.set("settings", serde_json::to_value(&settings).unwrap());
+                }
+                settings
+            }
+            Err(_) => {
+                let default_settings = get_default_settings();
+                store.set("settings", serde_json::to_value(&default_settings).unwrap());
+                default_settings
+            }
+        }
     } else {
         let default_settings = get_default_settings();
         store.set("settings", serde_json::to_value(&default_settings).unwrap());
@@ -524,3 +810,16 @@ pub fn get_recording_retention_period(app: &AppHandle) -> RecordingRetentionPeri
     let settings = get_settings(app);
     settings.recording_retention_period
 }
+
+pub fn get_recording_file_format(app: &AppHandle) -> crate::audio_toolkit::AudioFileFormat {
+    use crate::audio_toolkit::AudioFileFormat;
+
+    let settings = get_settings(app);
+    match settings.recording_format {
+        RecordingFormat::Wav => AudioFileFormat::Wav,
+        RecordingFormat::Flac => AudioFileFormat::Flac,
+        RecordingFormat::Opus => AudioFileFormat::Opus {
+            bitrate: settings.opus_bitrate_kbps * 1000,
+        },
+    }
+}
@@ -16,7 +16,7 @@ use crate::ManagedToggleState;
 ///
 /// This function contains the shared logic for:
 /// - Looking up the action in ACTION_MAP
-/// - Handling the cancel binding (only fires when recording)
+/// - Handling the cancel and pause bindings (only fire when recording)
 /// - Handling push-to-talk mode (start on press, stop on release)
 /// - Handling toggle mode (toggle state on press only)
 ///
@@ -41,8 +41,9 @@ pub fn handle_shortcut_event(
         return;
     };
 
-    // Cancel binding: only fires when recording and key is pressed
-    if binding_id == "cancel" {
+    // Cancel and pause bindings: only fire when recording and key is pressed.
+    // Pause stays registered all the time, so it is ignored between recordings
+    if binding_id == "cancel" || binding_id == "pause" {
         let audio_manager = app.state::<Arc<AudioRecordingManager>>();
         if audio_manager.is_recording() && is_pressed {
             action.start(app, binding_id, hotkey_string);
@@ -21,7 +21,7 @@ use tauri_plugin_autostart::ManagerExt;
 
 use crate::settings::{
     self, get_settings, ClipboardHandling, KeyboardImplementation, OverlayPosition, PasteMethod,
-    ShortcutBinding, SoundTheme,
+    RecordingOutputAction, ShortcutBinding, SoundTheme, WordReplacement,
 };
 use crate::tray;
 
@@ -428,6 +428,18 @@ pub fn update_custom_words(app: AppHandle, words: Vec<String>) -> Result<(), Str
     Ok(())
 }
 
+#[tauri::command]
+#[specta::specta]
+pub fn update_word_replacements(
+    app: AppHandle,
+    replacements: Vec<WordReplacement>,
+) -> Result<(), String> {
+    let mut settings = settings::get_settings(&app);
+    settings.word_replacements = replacements;
+    settings::write_settings(&app, settings);
+    Ok(())
+}
+
 #[tauri::command]
 #[specta::specta]
 pub fn change_word_correction_threshold_setting(
@@ -489,6 +501,30 @@ pub fn change_mute_while_recording_setting(app: AppHandle, enabled: bool) -> Res
     Ok(())
 }
 
+#[tauri::command]
+#[specta::specta]
+pub fn change_recording_output_action_setting(
+    app: AppHandle,
+    action: RecordingOutputAction,
+) -> Result<(), String> {
+    let mut settings = settings::get_settings(&app);
+    settings.recording_output_action = action;
+    settings::write_settings(&app, settings);
+    Ok(())
+}
+
+#[tauri::command]
+#[specta::specta]
+pub fn change_duck_volume_setting(app: AppHandle, percent: u8) -> Result<(), String> {
+    if percent > 100 {
+        return Err("Duck volume must be between 0 and 100 percent".to_string());
+    }
+    let mut settings = settings::get_settings(&app);
+    settings.duck_volume_percent = percent;
+    settings::write_settings(&app, settings);
+    Ok(())
+}
+
 #[tauri::command]
 #[specta::specta]
 pub fn change_append_trailing_space_setting(app: AppHandle, enabled: bool) -> Result<(), String> {
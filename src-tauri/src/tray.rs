@@ -13,6 +13,8 @@ use tauri_plugin_clipboard_manager::ClipboardExt;
 pub enum TrayIconState {
     Idle,
     Recording,
+    /// Recording, but not capturing until resumed.
+    Paused,
     Transcribing,
 }
 
@@ -46,15 +48,18 @@ pub fn get_current_theme(app: &AppHandle) -> AppTheme {
 pub fn get_icon_path(theme: AppTheme, state: TrayIconState) -> &'static str {
     match (theme, state) {
         // Dark theme uses light icons
-        (AppTheme::Dark, TrayIconState::Idle) => "resources/tray_idle.png",
+        // Nothing is captured while paused, so paused shows the idle icon
+        (AppTheme::Dark, TrayIconState::Idle | TrayIconState::Paused) => "resources/tray_idle.png",
         (AppTheme::Dark, TrayIconState::Recording) => "resources/tray_recording.png",
         (AppTheme::Dark, TrayIconState::Transcribing) => "resources/tray_transcribing.png",
         // Light theme uses dark icons
-        (AppTheme::Light, TrayIconState::Idle) => "resources/tray_idle_dark.png",
+        (AppTheme::Light, TrayIconState::Idle | TrayIconState::Paused) => {
+            "resources/tray_idle_dark.png"
+        }
         (AppTheme::Light, TrayIconState::Recording) => "resources/tray_recording_dark.png",
         (AppTheme::Light, TrayIconState::Transcribing) => "resources/tray_transcribing_dark.png",
         // Colored theme uses pink icons (for Linux)
-        (AppTheme::Colored, TrayIconState::Idle) => "resources/handy.png",
+        (AppTheme::Colored, TrayIconState::Idle | TrayIconState::Paused) => "resources/handy.png",
         (AppTheme::Colored, TrayIconState::Recording) => "resources/recording.png",
         (AppTheme::Colored, TrayIconState::Transcribing) => "resources/transcribing.png",
     }
@@ -75,10 +80,22 @@ pub fn change_tray_icon(app: &AppHandle, icon: TrayIconState) {
         .expect("failed to set icon"),
     ));
 
+    // A new state clears any recording limit warning
+    let _ = tray.set_tooltip(None::<&str>);
+
     // Update menu based on state
     update_tray_menu(app, &icon, None);
 }
 
+/// Warns in the tray tooltip that the current recording is about to reach
+/// its maximum duration. The warning is cleared by the next icon change.
+pub fn show_recording_limit_warning(app: &AppHandle) {
+    let settings = settings::get_settings(app);
+    let strings = get_tray_translations(Some(settings.app_language));
+    let tray = app.state::<TrayIcon>();
+    let _ = tray.set_tooltip(Some(&strings.recording_limit_near));
+}
+
 pub fn update_tray_menu(app: &AppHandle, state: &TrayIconState, locale: Option<&str>) {
     let settings = settings::get_settings(app);
 
@@ -128,10 +145,10 @@ pub fn update_tray_menu(app: &AppHandle, state: &TrayIconState, locale: Option<&
     let separator = || PredefinedMenuItem::separator(app).expect("failed to create separator");
 
     let menu = match state {
-        TrayIconState::Recording | TrayIconState::Transcribing => {
+        TrayIconState::Recording | TrayIconState::Paused | TrayIconState::Transcribing => {
             let cancel_i = MenuItem::with_id(app, "cancel", &strings.cancel, true, None::<&str>)
                 .expect("failed to create cancel item");
-            Menu::with_items(
+            let menu = Menu::with_items(
                 app,
                 &[
                     &version_i,
@@ -146,7 +163,24 @@ pub fn update_tray_menu(app: &AppHandle, state: &TrayIconState, locale: Option<&
                     &quit_i,
                 ],
             )
-            .expect("failed to create menu")
+            .expect("failed to create menu");
+
+            // Recordings can be paused and resumed until they are transcribed
+            let pause_i = match state {
+                TrayIconState::Recording => Some(
+                    MenuItem::with_id(app, "pause", &strings.pause, true, None::<&str>)
+                        .expect("failed to create pause item"),
+                ),
+                TrayIconState::Paused => Some(
+                    MenuItem::with_id(app, "resume", &strings.resume, true, None::<&str>)
+                        .expect("failed to create resume item"),
+                ),
+                _ => None,
+            };
+            if let Some(pause_i) = pause_i {
+                let _ = menu.insert(&pause_i, 2);
+            }
+            menu
         }
         TrayIconState::Idle => Menu::with_items(
             app,
@@ -217,6 +251,12 @@ mod tests {
             saved: false,
             title: "Recording".to_string(),
             transcription_text: transcription.to_string(),
+            recovered: false,
+            metadata: Default::default(),
+            original_text: None,
+            note: None,
+            tags: Vec::new(),
+            audio_pruned: false,
         }
     }
 
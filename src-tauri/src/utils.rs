@@ -1,10 +1,12 @@
+use crate::actions::ACTION_MAP;
+use crate::audio_feedback::{play_feedback_sound, SoundType};
 use crate::managers::audio::AudioRecordingManager;
 use crate::managers::transcription::TranscriptionManager;
 use crate::shortcut;
 use crate::ManagedToggleState;
 use log::{info, warn};
 use std::sync::Arc;
-use tauri::{AppHandle, Manager};
+use tauri::{AppHandle, Emitter, Manager};
 
 // Re-export all utility modules for easy access
 // pub use crate::audio_feedback::*;
@@ -15,6 +17,17 @@ pub use crate::tray::*;
 /// Centralized cancellation function that can be called from anywhere in the app.
 /// Handles cancelling both recording and transcription operations and updates UI state.
 pub fn cancel_current_operation(app: &AppHandle) {
+    end_current_operation(app, SoundType::Cancel);
+}
+
+/// Reports a `recording-error` and abandons the current operation like
+/// [`cancel_current_operation`], playing the error sound instead.
+pub fn fail_current_operation(app: &AppHandle, message: String) {
+    let _ = app.emit("recording-error", message);
+    end_current_operation(app, SoundType::Error);
+}
+
+fn end_current_operation(app: &AppHandle, sound: SoundType) {
     info!("Initiating operation cancellation...");
 
     // Unregister the cancel shortcut asynchronously
@@ -31,7 +44,11 @@ pub fn cancel_current_operation(app: &AppHandle) {
 
     // Cancel any ongoing recording
     let audio_manager = app.state::<Arc<AudioRecordingManager>>();
+    let was_recording = audio_manager.is_recording();
     audio_manager.cancel_recording();
+    if was_recording {
+        play_feedback_sound(app, sound);
+    }
 
     // Update tray icon and hide overlay
     change_tray_icon(app, crate::tray::TrayIconState::Idle);
@@ -44,6 +61,59 @@ pub fn cancel_current_operation(app: &AppHandle) {
     info!("Operation cancellation completed - returned to idle state");
 }
 
+/// Stops the current recording as if its shortcut had been released, so the
+/// audio is transcribed and pasted as usual.
+pub fn stop_current_recording(app: &AppHandle) {
+    let audio_manager = app.state::<Arc<AudioRecordingManager>>();
+    let Some(binding_id) = audio_manager.active_binding() else {
+        return;
+    };
+
+    // In toggle mode the next press should start a new recording
+    let toggle_state_manager = app.state::<ManagedToggleState>();
+    if let Ok(mut states) = toggle_state_manager.lock() {
+        states.active_toggles.insert(binding_id.clone(), false);
+    } else {
+        warn!("Failed to lock toggle state manager while stopping recording");
+    }
+
+    match ACTION_MAP.get(&binding_id) {
+        Some(action) => action.stop(app, &binding_id, ""),
+        None => warn!("No action to stop recording for binding '{binding_id}'"),
+    }
+}
+
+/// Pauses or resumes the current recording and updates the tray and overlay
+/// to match. Does nothing when not recording.
+pub fn set_recording_paused(app: &AppHandle, paused: bool) {
+    let audio_manager = app.state::<Arc<AudioRecordingManager>>();
+    let result = if paused {
+        audio_manager.pause_recording()
+    } else {
+        audio_manager.resume_recording()
+    };
+    if let Err(e) = result {
+        warn!("Failed to change pause state: {e}");
+        return;
+    }
+
+    if paused {
+        change_tray_icon(app, crate::tray::TrayIconState::Paused);
+        show_paused_overlay(app);
+    } else {
+        change_tray_icon(app, crate::tray::TrayIconState::Recording);
+        show_recording_overlay(app);
+    }
+    let _ = app.emit("recording-paused", paused);
+}
+
+/// Pauses a running recording, or resumes a paused one.
+pub fn toggle_recording_pause(app: &AppHandle) {
+    let audio_manager = app.state::<Arc<AudioRecordingManager>>();
+    let paused = audio_manager.is_paused();
+    set_recording_paused(app, !paused);
+}
+
 /// Check if using the Wayland display server protocol
 #[cfg(target_os = "linux")]
 pub fn is_wayland() -> bool {
@@ -133,6 +133,14 @@ async updateCustomWords(words: string[]) : Promise<Result<null, string>> {
     else return { status: "error", error: e  as any };
 }
 },
+async updateWordReplacements(replacements: WordReplacement[]) : Promise<Result<null, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("update_word_replacements", { replacements }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
 /**
  * Temporarily unregister a binding while the user is editing it in the UI.
  * This avoids firing the action while keys are being recorded.
@@ -164,6 +172,145 @@ async changeMuteWhileRecordingSetting(enabled: boolean) : Promise<Result<null, s
     else return { status: "error", error: e  as any };
 }
 },
+async changeRecordingOutputActionSetting(action: RecordingOutputAction) : Promise<Result<null, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("change_recording_output_action_setting", { action }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+async changeDuckVolumeSetting(percent: number) : Promise<Result<null, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("change_duck_volume_setting", { percent }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+async changeHighPassFilterSetting(enabled: boolean) : Promise<Result<null, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("change_high_pass_filter_setting", { enabled }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+async changeNoiseReductionSetting(enabled: boolean) : Promise<Result<null, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("change_noise_reduction_setting", { enabled }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+async changeAutoGainSetting(enabled: boolean) : Promise<Result<null, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("change_auto_gain_setting", { enabled }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+async getAvailableSoundThemes() : Promise<SoundThemeInfo[]> {
+    return await TAURI_INVOKE("get_available_sound_themes");
+},
+/**
+ * Selects a theme folder to override the built-in sounds, or `None` to use
+ * the built-in theme alone.
+ */
+async setSoundThemeFolder(name: string | null) : Promise<Result<null, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("set_sound_theme_folder", { name }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Caps recording length; `None` records until stopped.
+ */
+async changeMaxRecordingDurationSetting(seconds: number | null) : Promise<Result<null, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("change_max_recording_duration_setting", { seconds }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Sets how much of a recording is kept in memory before the rest is written
+ * to disk; `None` keeps all of it in memory.
+ */
+async changeSpillAfterSetting(seconds: number | null) : Promise<Result<null, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("change_spill_after_setting", { seconds }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+async setMicrophoneGain(deviceName: string, gain: number) : Promise<Result<null, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("set_microphone_gain", { deviceName, gain }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+async getMicrophoneGain(deviceName: string) : Promise<number> {
+    return await TAURI_INVOKE("get_microphone_gain", { deviceName });
+},
+async getInputLevel() : Promise<InputLevelEvent | null> {
+    return await TAURI_INVOKE("get_input_level");
+},
+async startInputLevelMonitor() : Promise<Result<null, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("start_input_level_monitor") };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+async stopInputLevelMonitor() : Promise<void> {
+    await TAURI_INVOKE("stop_input_level_monitor");
+},
+async getAvailableMonitorSources() : Promise<MonitorSourceInfo[]> {
+    return await TAURI_INVOKE("get_available_monitor_sources");
+},
+/**
+ * Chooses between microphone, system audio, or both. `monitor_source` of
+ * `None` captures the default output device's monitor.
+ */
+async setAudioSource(source: CaptureSource, monitorSource: string | null) : Promise<Result<null, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("set_audio_source", { source, monitorSource }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+async getAudioSource() : Promise<CaptureSource> {
+    return await TAURI_INVOKE("get_audio_source");
+},
+async getAvailableAudioHosts() : Promise<string[]> {
+    return await TAURI_INVOKE("get_available_audio_hosts");
+},
+async getAudioHost() : Promise<string> {
+    return await TAURI_INVOKE("get_audio_host");
+},
+/**
+ * Switches the cpal host used for listing and opening devices. Device
+ * selections are remembered per host.
+ */
+async setAudioHost(host: string) : Promise<Result<null, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("set_audio_host", { host }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
 async changeAppendTrailingSpaceSetting(enabled: boolean) : Promise<Result<null, string>> {
     try {
     return { status: "ok", data: await TAURI_INVOKE("change_append_trailing_space_setting", { enabled }) };
@@ -221,6 +368,12 @@ async triggerUpdateCheck() : Promise<Result<null, string>> {
 async cancelOperation() : Promise<void> {
     await TAURI_INVOKE("cancel_operation");
 },
+/**
+ * Resumes a paused recording. Does nothing when not recording.
+ */
+async resumeRecording() : Promise<void> {
+    await TAURI_INVOKE("resume_recording");
+},
 async getAppDirPath() : Promise<Result<string, string>> {
     try {
     return { status: "ok", data: await TAURI_INVOKE("get_app_dir_path") };
@@ -429,6 +582,10 @@ async getAvailableMicrophones() : Promise<Result<AudioDevice[], string>> {
     else return { status: "error", error: e  as any };
 }
 },
+/**
+ * Makes `device_name` the most preferred microphone. Selecting "default"
+ * clears the priority list so the system default is used.
+ */
 async setSelectedMicrophone(deviceName: string) : Promise<Result<null, string>> {
     try {
     return { status: "ok", data: await TAURI_INVOKE("set_selected_microphone", { deviceName }) };
@@ -475,22 +632,44 @@ async playTestSound(soundType: string) : Promise<void> {
 async checkCustomSounds() : Promise<CustomSounds> {
     return await TAURI_INVOKE("check_custom_sounds");
 },
-async setClamshellMicrophone(deviceName: string) : Promise<Result<null, string>> {
+async setMicrophonePriority(deviceNames: string[]) : Promise<Result<null, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("set_microphone_priority", { deviceNames }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+async getMicrophonePriority() : Promise<Result<string[], string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("get_microphone_priority") };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+async getActiveMicrophone() : Promise<string | null> {
+    return await TAURI_INVOKE("get_active_microphone");
+},
+async getMicrophoneChannelCount(deviceName: string) : Promise<Result<number, string>> {
     try {
-    return { status: "ok", data: await TAURI_INVOKE("set_clamshell_microphone", { deviceName }) };
+    return { status: "ok", data: await TAURI_INVOKE("get_microphone_channel_count", { deviceName }) };
 } catch (e) {
     if(e instanceof Error) throw e;
     else return { status: "error", error: e  as any };
 }
 },
-async getClamshellMicrophone() : Promise<Result<string, string>> {
+async setMicrophoneChannel(deviceName: string, selection: ChannelSelection) : Promise<Result<null, string>> {
     try {
-    return { status: "ok", data: await TAURI_INVOKE("get_clamshell_microphone") };
+    return { status: "ok", data: await TAURI_INVOKE("set_microphone_channel", { deviceName, selection }) };
 } catch (e) {
     if(e instanceof Error) throw e;
     else return { status: "error", error: e  as any };
 }
 },
+async getMicrophoneChannel(deviceName: string) : Promise<ChannelSelection> {
+    return await TAURI_INVOKE("get_microphone_channel", { deviceName });
+},
 async isRecording() : Promise<boolean> {
     return await TAURI_INVOKE("is_recording");
 },
@@ -521,6 +700,33 @@ async getHistoryEntries() : Promise<Result<HistoryEntry[], string>> {
     else return { status: "error", error: e  as any };
 }
 },
+async getHistoryPage(query: HistoryPageQuery) : Promise<Result<HistoryPage, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("get_history_page", { query }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Fetches the entries named in a `history-updated` event.
+ */
+async getHistoryEntriesByIds(ids: number[]) : Promise<Result<HistoryEntry[], string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("get_history_entries_by_ids", { ids }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+async searchHistory(query: HistorySearchQuery) : Promise<Result<HistorySearchResults, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("search_history", { query }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
 async toggleHistoryEntrySaved(id: number) : Promise<Result<null, string>> {
     try {
     return { status: "ok", data: await TAURI_INVOKE("toggle_history_entry_saved", { id }) };
@@ -529,6 +735,41 @@ async toggleHistoryEntrySaved(id: number) : Promise<Result<null, string>> {
     else return { status: "error", error: e  as any };
 }
 },
+/**
+ * Replaces an entry's transcript with a hand edit. Corrections made often
+ * enough are added to the word replacements; the corrections this edit made
+ * are returned as suggestions.
+ */
+async updateHistoryEntryText(id: number, text: string) : Promise<Result<CorrectionSuggestion[], string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("update_history_entry_text", { id, text }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Restores the model's transcript of an edited entry.
+ */
+async revertHistoryEntryText(id: number) : Promise<Result<null, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("revert_history_entry_text", { id }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Every correction made by hand in history, most frequent first.
+ */
+async getCorrectionSuggestions() : Promise<Result<CorrectionSuggestion[], string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("get_correction_suggestions") };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
 async getAudioFilePath(fileName: string) : Promise<Result<string, string>> {
     try {
     return { status: "ok", data: await TAURI_INVOKE("get_audio_file_path", { fileName }) };
@@ -561,6 +802,291 @@ async updateRecordingRetentionPeriod(period: string) : Promise<Result<null, stri
     else return { status: "error", error: e  as any };
 }
 },
+/**
+ * Sets how old audio may get under the text-only retention period.
+ */
+async updateAudioRetentionDays(days: number) : Promise<Result<null, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("update_audio_retention_days", { days }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Sets the disk space recordings may take under the disk quota retention
+ * period.
+ */
+async updateRecordingsQuota(quotaMb: number) : Promise<Result<null, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("update_recordings_quota", { quotaMb }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+async updateRecordingFormat(format: RecordingFormat, opusBitrateKbps: number | null) : Promise<Result<null, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("update_recording_format", { format, opusBitrateKbps }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Converts existing recordings to the current recording format in the
+ * background, emitting `recording-conversion-progress` along the way and
+ * `recording-conversion-finished` with a summary at the end.
+ */
+async convertRecordings() : Promise<Result<null, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("convert_recordings") };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+async updateCrashSafeRecording(enabled: boolean) : Promise<Result<null, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("update_crash_safe_recording", { enabled }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Transcribes an entry recovered from a crash-safe journal and stores the
+ * result in history.
+ */
+async transcribeRecoveredEntry(id: number) : Promise<Result<string, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("transcribe_recovered_entry", { id }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Queues entries to be transcribed again with another model or language.
+ * Either name the entries in `ids` or pass a `query` to take every entry it
+ * matches. Results are stored as alternative transcripts and progress is
+ * reported through `retranscription-progress` events. Returns how many
+ * entries were queued.
+ */
+async retranscribeHistoryEntries(ids: number[] | null, query: HistoryPageQuery | null, modelId: string, language: string | null) : Promise<Result<number, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("retranscribe_history_entries", { ids, query, modelId, language }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+async cancelRetranscription() : Promise<Result<null, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("cancel_retranscription") };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+async getAlternativeTranscripts(entryId: number) : Promise<Result<AlternativeTranscript[], string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("get_alternative_transcripts", { entryId }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Words dictated, speaking rate, audio time, most used models and languages,
+ * and an estimate of the typing time saved over a date range.
+ */
+async getUsageStats(query: UsageStatsQuery) : Promise<Result<UsageStats, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("get_usage_stats", { query }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+async getHistoryTags() : Promise<Result<HistoryTag[], string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("get_history_tags") };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Tags an entry by name, creating the tag if it is new.
+ */
+async addHistoryEntryTag(id: number, tag: string) : Promise<Result<HistoryTag, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("add_history_entry_tag", { id, tag }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+async removeHistoryEntryTag(id: number, tagId: number) : Promise<Result<null, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("remove_history_entry_tag", { id, tagId }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Keeps entries with this tag for `retention_days`, or like saved entries
+ * when `None`.
+ */
+async updateHistoryTagRetention(tagId: number, retentionDays: number | null) : Promise<Result<null, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("update_history_tag_retention", { tagId, retentionDays }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+async deleteHistoryTag(tagId: number) : Promise<Result<null, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("delete_history_tag", { tagId }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+async updateHistoryEntryNote(id: number, note: string | null) : Promise<Result<null, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("update_history_entry_note", { id, note }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+async getHistoryCollections() : Promise<Result<HistoryCollection[], string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("get_history_collections") };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+async createHistoryCollection(name: string) : Promise<Result<HistoryCollection, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("create_history_collection", { name }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Deletes a collection; its entries stay in history.
+ */
+async deleteHistoryCollection(collectionId: number) : Promise<Result<null, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("delete_history_collection", { collectionId }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+async addToHistoryCollection(collectionId: number, ids: number[]) : Promise<Result<null, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("add_to_history_collection", { collectionId, ids }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+async removeFromHistoryCollection(collectionId: number, ids: number[]) : Promise<Result<null, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("remove_from_history_collection", { collectionId, ids }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+async getHistoryEncryption() : Promise<Result<EncryptionState, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("get_history_encryption") };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Encrypts the history database and recordings with a key derived from
+ * `passphrase`. With `remember_key` the key is kept in the OS keyring so the
+ * history unlocks at startup.
+ */
+async enableHistoryEncryption(passphrase: string, rememberKey: boolean) : Promise<Result<null, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("enable_history_encryption", { passphrase, rememberKey }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Decrypts the history and turns encryption off.
+ */
+async disableHistoryEncryption(passphrase: string) : Promise<Result<null, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("disable_history_encryption", { passphrase }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+async unlockHistory(passphrase: string) : Promise<Result<null, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("unlock_history", { passphrase }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+async lockHistory() : Promise<Result<null, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("lock_history") };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+async updateHistoryKeyRemembered(rememberKey: boolean) : Promise<Result<null, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("update_history_key_remembered", { rememberKey }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Exports the whole history, with its recordings, to a tar.gz archive at
+ * `path`.
+ */
+async exportHistory(path: string) : Promise<Result<ExportSummary, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("export_history", { path }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Merges an exported archive into the history, skipping entries that are
+ * already in it.
+ */
+async importHistory(path: string) : Promise<Result<ImportReport, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("import_history", { path }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
 /**
  * Checks if the Mac is a laptop by detecting battery presence
  * 
@@ -587,23 +1113,314 @@ async isLaptop() : Promise<Result<boolean, string>> {
 
 /** user-defined types **/
 
-export type AppSettings = { bindings: Partial<{ [key in string]: ShortcutBinding }>; push_to_talk: boolean; audio_feedback: boolean; audio_feedback_volume?: number; sound_theme?: SoundTheme; start_hidden?: boolean; autostart_enabled?: boolean; update_checks_enabled?: boolean; selected_model?: string; always_on_microphone?: boolean; selected_microphone?: string | null; clamshell_microphone?: string | null; selected_output_device?: string | null; translate_to_english?: boolean; selected_language?: string; overlay_position?: OverlayPosition; debug_mode?: boolean; log_level?: LogLevel; custom_words?: string[]; model_unload_timeout?: ModelUnloadTimeout; word_correction_threshold?: number; history_limit?: number; recording_retention_period?: RecordingRetentionPeriod; paste_method?: PasteMethod; clipboard_handling?: ClipboardHandling; mute_while_recording?: boolean; append_trailing_space?: boolean; app_language?: string; keyboard_implementation?: KeyboardImplementation; paste_delay_ms?: number; custom_filler_words?: string[] | null }
+/**
+ * Another model's or language's transcript of an entry's recording.
+ */
+export type AlternativeTranscript = { id: number; entry_id: number; timestamp: number; model_id: string; engine: string | null; language: string; transcription_text: string; inference_ms: number | null }
+export type AppSettings = { bindings: Partial<{ [key in string]: ShortcutBinding }>; push_to_talk: boolean; audio_feedback: boolean; audio_feedback_volume?: number; sound_theme?: SoundTheme; 
+/**
+ * Folder in the sound themes directory whose sounds replace the built-in
+ * theme's, event by event.
+ */
+sound_theme_folder?: string | null; start_hidden?: boolean; autostart_enabled?: boolean; update_checks_enabled?: boolean; selected_model?: string; always_on_microphone?: boolean; 
+/**
+ * Legacy single-device setting, folded into `microphone_priority` on load.
+ */
+selected_microphone?: string | null; 
+/**
+ * Legacy clamshell-mode device, folded into `microphone_priority` on load.
+ */
+clamshell_microphone?: string | null; 
+/**
+ * Preferred input devices, most preferred first. Entries may contain
+ * `*` and `?` wildcards. An empty list means the system default.
+ */
+microphone_priority?: string[]; 
+/**
+ * Channel selection per input device name.
+ */
+microphone_channels?: Partial<{ [key in string]: ChannelSelection }>; 
+/**
+ * Software gain multiplier per input device name.
+ */
+microphone_gains?: Partial<{ [key in string]: number }>; audio_source?: CaptureSource; 
+/**
+ * Monitor source to capture; `None` follows the default output device.
+ */
+monitor_source?: string | null; selected_output_device?: string | null; 
+/**
+ * cpal host to use; `None` picks the platform default.
+ */
+audio_host?: string | null; 
+/**
+ * Device selections of inactive hosts, keyed by host name. Device names
+ * differ between hosts, so switching swaps these in and out.
+ */
+host_devices?: Partial<{ [key in string]: HostDeviceSettings }>; translate_to_english?: boolean; selected_language?: string; overlay_position?: OverlayPosition; debug_mode?: boolean; log_level?: LogLevel; custom_words?: string[]; word_replacements?: WordReplacement[]; model_unload_timeout?: ModelUnloadTimeout; word_correction_threshold?: number; history_limit?: number; recording_retention_period?: RecordingRetentionPeriod; audio_retention_days?: number; recordings_quota_mb?: number; crash_safe_recording?: boolean; recording_format?: RecordingFormat; opus_bitrate_kbps?: number; paste_method?: PasteMethod; clipboard_handling?: ClipboardHandling; mute_while_recording?: boolean; recording_output_action?: RecordingOutputAction; duck_volume_percent?: number; high_pass_filter?: boolean; noise_reduction?: boolean; auto_gain?: boolean; max_recording_duration_secs?: number | null; 
+/**
+ * Audio kept in memory while recording before the rest is written to
+ * disk; `None` keeps the whole recording in memory.
+ */
+spill_after_secs?: number | null; append_trailing_space?: boolean; app_language?: string; keyboard_implementation?: KeyboardImplementation; paste_delay_ms?: number; custom_filler_words?: string[] | null }
 export type AudioDevice = { index: string; name: string; is_default: boolean }
 export type BindingResponse = { success: boolean; binding: ShortcutBinding | null; error: string | null }
+/**
+ * What the recorder captures.
+ */
+export type CaptureSource = "microphone" | 
+/**
+ * A monitor source, i.e. what the computer is playing.
+ */
+"system_audio" | 
+/**
+ * The microphone with a monitor source mixed in.
+ */
+"microphone_and_system_audio"
+/**
+ * How a multi-channel microphone is reduced to mono.
+ */
+export type ChannelSelection = "average" | "loudest" | { channel: number }
 export type ClipboardHandling = "dont_modify" | "copy_to_clipboard"
+export type CorrectionKind = 
+/**
+ * A single word, best added to the custom words so near misses are
+ * fixed too.
+ */
+"custom_word" | 
+/**
+ * A phrase replaced as a whole.
+ */
+"replacement"
+export type CorrectionSuggestion = { original: string; replacement: string; count: number; kind: CorrectionKind; 
+/**
+ * Already applied to every transcription as a word replacement.
+ */
+applied: boolean }
 export type CustomSounds = { start: boolean; stop: boolean }
+export type EncryptionState = { status: EncryptionStatus; 
+/**
+ * The key is kept in the OS keyring.
+ */
+remember_key: boolean }
+export type EncryptionStatus = 
+/**
+ * History is stored in plaintext.
+ */
+"disabled" | 
+/**
+ * History is encrypted and the passphrase hasn't been entered.
+ */
+"locked" | "unlocked"
 export type EngineType = "Whisper" | "Parakeet" | "Moonshine" | "MoonshineStreaming" | "SenseVoice"
-export type HistoryEntry = { id: number; file_name: string; timestamp: number; saved: boolean; title: string; transcription_text: string }
+export type ExportSummary = { entries: number; recordings: number }
+/**
+ * A named group of history entries.
+ */
+export type HistoryCollection = { id: number; name: string; created_at: number; entry_count: number }
+/**
+ * Position in the history, ordered newest first.
+ */
+export type HistoryCursor = { timestamp: number; id: number }
+export type HistoryEntry = { id: number; file_name: string; timestamp: number; saved: boolean; title: string; transcription_text: string; 
+/**
+ * Audio recovered from a crash-safe journal that hasn't been transcribed.
+ */
+recovered: boolean; metadata: TranscriptionMetadata; 
+/**
+ * The model's transcript, when it has been edited by hand.
+ */
+original_text: string | null; note: string | null; 
+/**
+ * Ids of the entry's tags.
+ */
+tags: number[]; 
+/**
+ * The recording was deleted by retention; only the text is kept.
+ */
+audio_pruned: boolean }
+export type HistoryPage = { entries: HistoryEntry[]; 
+/**
+ * Cursor for the next page, or `None` on the last one.
+ */
+next: HistoryCursor | null }
+/**
+ * A page of history, optionally filtered. Dates are unix timestamps in
+ * seconds, `from` inclusive and `to` exclusive.
+ */
+export type HistoryPageQuery = { 
+/**
+ * Only entries older than this; the first page when unset.
+ */
+before?: HistoryCursor | null; limit?: number; saved_only?: boolean; model_id?: string | null; language?: string | null; from?: number | null; to?: number | null; tag_id?: number | null; collection_id?: number | null }
+export type HistorySearchHit = { entry: HistoryEntry; snippet: SnippetPart[] }
+/**
+ * A full-text search over history. Dates are unix timestamps in seconds,
+ * `from` inclusive and `to` exclusive.
+ */
+export type HistorySearchQuery = { query: string; from?: number | null; to?: number | null; saved_only?: boolean; tag_id?: number | null; offset?: number; limit?: number }
+export type HistorySearchResults = { 
+/**
+ * Best matches first.
+ */
+hits: HistorySearchHit[]; 
+/**
+ * Number of matches across all pages.
+ */
+total: number }
+/**
+ * A user tag. The retention cleanup keeps tagged entries like saved ones,
+ * or for `retention_days` when every tag of the entry sets it.
+ */
+export type HistoryTag = { id: number; name: string; retention_days: number | null; entry_count: number }
+/**
+ * Device selection remembered for an audio host other than the active one.
+ */
+export type HostDeviceSettings = { microphone_priority?: string[]; selected_output_device?: string | null }
+export type ImportConflict = { timestamp: number; title: string; kind: ImportConflictKind }
+export type ImportConflictKind = 
+/**
+ * The entry was already in history with different details; the existing
+ * entry was kept and given the archive's tags and collections.
+ */
+"existing_differs" | 
+/**
+ * Another recording already had the entry's file name, so it was
+ * imported under a new one.
+ */
+"recording_renamed" | 
+/**
+ * The archive had no recording for the entry, so only its text was
+ * imported.
+ */
+"recording_missing"
+export type ImportReport = { imported: number; 
+/**
+ * Entries already in history, matched by timestamp and text.
+ */
+duplicates: number; conflicts: ImportConflict[] }
+/**
+ * Payload of the `input-level` event and `get_input_level` command.
+ */
+export type InputLevelEvent = { rms_dbfs: number; peak_dbfs: number; clipped_samples: number }
 export type KeyboardImplementation = "tauri" | "handy_keys"
 export type LogLevel = "trace" | "debug" | "info" | "warn" | "error"
 export type ModelInfo = { id: string; name: string; description: string; filename: string; url: string | null; size_mb: number; is_downloaded: boolean; is_downloading: boolean; partial_size: number; is_directory: boolean; engine_type: EngineType; accuracy_score: number; speed_score: number; supports_translation: boolean; is_recommended: boolean; supported_languages: string[]; is_custom: boolean }
 export type ModelLoadStatus = { is_loaded: boolean; current_model: string | null }
 export type ModelUnloadTimeout = "never" | "immediately" | "min_2" | "min_5" | "min_10" | "min_15" | "hour_1" | "sec_5"
+export type MonitorSourceInfo = { name: string; description: string }
 export type OverlayPosition = "none" | "top" | "bottom"
 export type PasteMethod = "ctrl_v" | "direct" | "none" | "shift_insert" | "ctrl_shift_v"
-export type RecordingRetentionPeriod = "never" | "preserve_limit" | "days_3" | "weeks_2" | "months_3"
+/**
+ * How recordings kept in history are stored on disk.
+ */
+export type RecordingFormat = "wav" | "flac" | "opus"
+/**
+ * How other audio is kept out of the way while recording.
+ */
+export type RecordingOutputAction = 
+/**
+ * Mute the default output device.
+ */
+"mute" | 
+/**
+ * Lower the default output to `duck_volume_percent` of its volume.
+ */
+"duck" | 
+/**
+ * Pause media players that are playing, and resume them afterwards.
+ */
+"pause_media"
+export type RecordingRetentionPeriod = "never" | "preserve_limit" | "days_3" | "weeks_2" | "months_3" | 
+/**
+ * Keep every transcript, but delete audio older than
+ * `audio_retention_days`.
+ */
+"text_only" | 
+/**
+ * Keep every transcript, deleting the oldest audio once recordings take
+ * more than `recordings_quota_mb`.
+ */
+"disk_quota"
 export type ShortcutBinding = { id: string; name: string; description: string; default_binding: string; current_binding: string }
+/**
+ * A piece of a search snippet; highlighted parts matched the query.
+ */
+export type SnippetPart = { text: string; highlighted: boolean }
 export type SoundTheme = "marimba" | "pop" | "custom"
+/**
+ * A sound theme folder found in the themes directory.
+ */
+export type SoundThemeInfo = { name: string; 
+/**
+ * Events the theme has its own sound for; the rest use the built-in theme.
+ */
+events: string[] }
+/**
+ * How an entry was transcribed. Entries from before this was recorded, and
+ * recovered ones that haven't been transcribed, leave it unset.
+ */
+export type TranscriptionMetadata = { model_id: string | null; engine: string | null; 
+/**
+ * Language selected in settings, `auto` for detection. The engines don't
+ * report which language they detected, so that isn't kept.
+ */
+language: string | null; translate: boolean | null; 
+/**
+ * Recorded audio, excluding paused time.
+ */
+audio_duration_ms: number | null; 
+/**
+ * Audio left after voice activity detection.
+ */
+speech_duration_ms: number | null; inference_ms: number | null; paste_method: string | null; 
+/**
+ * Shortcut binding that started the recording.
+ */
+binding_id: string | null }
+/**
+ * How much a model or language was used, most used first.
+ */
+export type UsageCount = { name: string; entries: number; words: number }
+/**
+ * Words dictated in a local calendar day or week. Weeks start on Monday.
+ */
+export type UsagePeriod = { 
+/**
+ * First day of the period, `YYYY-MM-DD`.
+ */
+start: string; entries: number; words: number }
+export type UsageStats = { entries: number; words: number; 
+/**
+ * Days and weeks with any dictation, oldest first.
+ */
+daily: UsagePeriod[]; weekly: UsagePeriod[]; 
+/**
+ * Recorded audio, excluding paused time.
+ */
+audio_minutes: number; 
+/**
+ * Words per minute of speech, over the entries whose duration is known.
+ */
+words_per_minute: number | null; 
+/**
+ * Minutes typing the same words would have taken, less the time spent
+ * dictating them.
+ */
+typing_minutes_saved: number; models: UsageCount[]; languages: UsageCount[] }
+/**
+ * A date range of history to summarize. Dates are unix timestamps in
+ * seconds, `from` inclusive and `to` exclusive.
+ */
+export type UsageStatsQuery = { from?: number | null; to?: number | null; 
+/**
+ * Typing speed to compare against, [`DEFAULT_TYPING_WPM`] when unset.
+ */
+typing_wpm?: number | null }
+/**
+ * Text substituted for a phrase in every transcription, matched as whole
+ * words regardless of case.
+ */
+export type WordReplacement = { from: string; to: string }
 
 /** tauri-specta globals **/
 
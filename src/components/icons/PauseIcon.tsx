@@ -0,0 +1,37 @@
+import React from "react";
+
+interface PauseIconProps {
+  width?: number;
+  height?: number;
+  color?: string;
+  className?: string;
+}
+
+const PauseIcon: React.FC<PauseIconProps> = ({
+  width = 24,
+  height = 24,
+  color = "#FAA2CA",
+  className = "",
+}) => {
+  return (
+    <svg
+      width={width}
+      height={height}
+      viewBox="0 0 24 24"
+      fill="none"
+      xmlns="http://www.w3.org/2000/svg"
+      className={className}
+    >
+      <g fill={color}>
+        <path d="m9 8c.55228 0 1 .44772 1 1v6c0 .5523-.44772 1-1 1s-1-.4477-1-1v-6c0-.55228.44772-1 1-1z" />
+        <path d="m15 8c.5523 0 1 .44772 1 1v6c0 .5523-.4477 1-1 1s-1-.4477-1-1v-6c0-.55228.4477-1 1-1z" />
+        <path
+          d="m20 12c0-4.41828-3.5817-8-8-8-4.41828 0-8 3.58172-8 8 0 4.4183 3.58172 8 8 8 4.4183 0 8-3.5817 8-8zm2 0c0 5.5228-4.4772 10-10 10-5.52285 0-10-4.4772-10-10 0-5.52285 4.47715-10 10-10 5.5228 0 10 4.47715 10 10z"
+          opacity=".4"
+        />
+      </g>
+    </svg>
+  );
+};
+
+export default PauseIcon;
@@ -0,0 +1,36 @@
+import React from "react";
+
+interface ResumeIconProps {
+  width?: number;
+  height?: number;
+  color?: string;
+  className?: string;
+}
+
+const ResumeIcon: React.FC<ResumeIconProps> = ({
+  width = 24,
+  height = 24,
+  color = "#FAA2CA",
+  className = "",
+}) => {
+  return (
+    <svg
+      width={width}
+      height={height}
+      viewBox="0 0 24 24"
+      fill="none"
+      xmlns="http://www.w3.org/2000/svg"
+      className={className}
+    >
+      <g fill={color}>
+        <path d="m10.0547 8.16797c-.3068-.20456-.7014-.22364-1.02656-.04963-.32514.17401-.52814.51282-.52814.88166v6c0 .3688.203.7076.52814.8817.32516.174.71976.1549 1.02656-.0497l4.5-3c.2782-.1854.4453-.4976.4453-.832s-.1671-.6466-.4453-.832z" />
+        <path
+          d="m20 12c0-4.41828-3.5817-8-8-8-4.41828 0-8 3.58172-8 8 0 4.4183 3.58172 8 8 8 4.4183 0 8-3.5817 8-8zm2 0c0 5.5228-4.4772 10-10 10-5.52285 0-10-4.4772-10-10 0-5.52285 4.47715-10 10-10 5.5228 0 10 4.47715 10 10z"
+          opacity=".4"
+        />
+      </g>
+    </svg>
+  );
+};
+
+export default ResumeIcon;
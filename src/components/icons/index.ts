@@ -1,3 +1,5 @@
 export { default as MicrophoneIcon } from "./MicrophoneIcon";
 export { default as TranscriptionIcon } from "./TranscriptionIcon";
 export { default as CancelIcon } from "./CancelIcon";
+export { default as PauseIcon } from "./PauseIcon";
+export { default as ResumeIcon } from "./ResumeIcon";
@@ -0,0 +1,168 @@
+import React, { useEffect, useState } from "react";
+import { useTranslation } from "react-i18next";
+import { commands } from "@/bindings";
+import { useSettings } from "../../hooks/useSettings";
+import { Input } from "../ui/Input";
+import { Button } from "../ui/Button";
+import { SettingContainer } from "../ui/SettingContainer";
+
+interface MicrophonePriorityProps {
+  descriptionMode?: "inline" | "tooltip";
+  grouped?: boolean;
+}
+
+export const MicrophonePriority: React.FC<MicrophonePriorityProps> =
+  React.memo(({ descriptionMode = "tooltip", grouped = false }) => {
+    const { t } = useTranslation();
+    const { getSetting, updateSetting, isUpdating, audioDevices } =
+      useSettings();
+    const [newDevice, setNewDevice] = useState("");
+    const [activeMicrophone, setActiveMicrophone] = useState<string | null>(
+      null,
+    );
+    const priority = getSetting("microphone_priority") || [];
+    const updating = isUpdating("microphone_priority");
+
+    useEffect(() => {
+      commands.getActiveMicrophone().then(setActiveMicrophone);
+    }, [priority.join("\n")]);
+
+    const handleAddDevice = () => {
+      const trimmedDevice = newDevice.trim();
+      if (trimmedDevice && !priority.includes(trimmedDevice)) {
+        updateSetting("microphone_priority", [...priority, trimmedDevice]);
+        setNewDevice("");
+      }
+    };
+
+    const handleRemoveDevice = (deviceToRemove: string) => {
+      updateSetting(
+        "microphone_priority",
+        priority.filter((device) => device !== deviceToRemove),
+      );
+    };
+
+    const handleMoveDevice = (index: number, offset: number) => {
+      const reordered = [...priority];
+      const [device] = reordered.splice(index, 1);
+      reordered.splice(index + offset, 0, device);
+      updateSetting("microphone_priority", reordered);
+    };
+
+    const handleKeyPress = (e: React.KeyboardEvent) => {
+      if (e.key === "Enter") {
+        e.preventDefault();
+        handleAddDevice();
+      }
+    };
+
+    return (
+      <>
+        <SettingContainer
+          title={t("settings.sound.microphonePriority.title")}
+          description={t("settings.sound.microphonePriority.description")}
+          descriptionMode={descriptionMode}
+          grouped={grouped}
+        >
+          <div className="flex items-center gap-2">
+            <Input
+              type="text"
+              className="max-w-48"
+              value={newDevice}
+              onChange={(e) => setNewDevice(e.target.value)}
+              onKeyDown={handleKeyPress}
+              placeholder={t("settings.sound.microphonePriority.placeholder")}
+              list="microphone-priority-devices"
+              variant="compact"
+              disabled={updating}
+            />
+            <datalist id="microphone-priority-devices">
+              {audioDevices
+                .filter((device) => !device.is_default)
+                .map((device) => (
+                  <option key={device.index} value={device.name} />
+                ))}
+            </datalist>
+            <Button
+              onClick={handleAddDevice}
+              disabled={
+                !newDevice.trim() ||
+                priority.includes(newDevice.trim()) ||
+                updating
+              }
+              variant="primary"
+              size="md"
+            >
+              {t("settings.sound.microphonePriority.add")}
+            </Button>
+          </div>
+        </SettingContainer>
+        {priority.length > 0 && (
+          <div
+            className={`px-4 p-2 ${grouped ? "" : "rounded-cf-sm shadow-cf-card"} space-y-1`}
+          >
+            {priority.map((device, index) => (
+              <div key={device} className="flex items-center gap-2 text-sm">
+                <span className="w-5 text-end text-cf-text-secondary">
+                  {index + 1}.
+                </span>
+                <span className="flex-1 truncate font-semibold">{device}</span>
+                <Button
+                  onClick={() => handleMoveDevice(index, -1)}
+                  disabled={index === 0 || updating}
+                  variant="ghost"
+                  size="sm"
+                  aria-label={t("settings.sound.microphonePriority.moveUp", {
+                    device,
+                  })}
+                >
+                  ↑
+                </Button>
+                <Button
+                  onClick={() => handleMoveDevice(index, 1)}
+                  disabled={index === priority.length - 1 || updating}
+                  variant="ghost"
+                  size="sm"
+                  aria-label={t("settings.sound.microphonePriority.moveDown", {
+                    device,
+                  })}
+                >
+                  ↓
+                </Button>
+                <Button
+                  onClick={() => handleRemoveDevice(device)}
+                  disabled={updating}
+                  variant="danger-ghost"
+                  size="sm"
+                  aria-label={t("settings.sound.microphonePriority.remove", {
+                    device,
+                  })}
+                >
+                  <svg
+                    className="w-3 h-3"
+                    fill="none"
+                    stroke="currentColor"
+                    viewBox="0 0 24 24"
+                  >
+                    <path
+                      strokeLinecap="round"
+                      strokeLinejoin="round"
+                      strokeWidth={2}
+                      d="M6 18L18 6M6 6l12 12"
+                    />
+                  </svg>
+                </Button>
+              </div>
+            ))}
+            {activeMicrophone && (
+              <p className="pt-1 text-xs text-cf-text-tertiary">
+                {t("settings.sound.microphonePriority.active", {
+                  device: activeMicrophone,
+                })}
+              </p>
+            )}
+          </div>
+        )}
+      </>
+    );
+  });
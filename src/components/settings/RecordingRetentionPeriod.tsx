@@ -1,6 +1,7 @@
 import React from "react";
 import { useTranslation } from "react-i18next";
 import { Dropdown } from "../ui/Dropdown";
+import { Input } from "../ui/Input";
 import { SettingContainer } from "../ui/SettingContainer";
 import { useSettings } from "../../hooks/useSettings";
 import { RecordingRetentionPeriod } from "@/bindings";
@@ -18,6 +19,8 @@ export const RecordingRetentionPeriodSelector: React.FC<RecordingRetentionPeriod
     const selectedRetentionPeriod =
       getSetting("recording_retention_period") || "never";
     const historyLimit = getSetting("history_limit") || 5;
+    const audioRetentionDays = getSetting("audio_retention_days") ?? 30;
+    const recordingsQuotaMb = getSetting("recordings_quota_mb") ?? 1024;
 
     const handleRetentionPeriodSelect = async (period: string) => {
       await updateSetting(
@@ -26,6 +29,24 @@ export const RecordingRetentionPeriodSelector: React.FC<RecordingRetentionPeriod
       );
     };
 
+    const handleAudioRetentionDaysChange = (
+      event: React.ChangeEvent<HTMLInputElement>,
+    ) => {
+      const value = parseInt(event.target.value, 10);
+      if (!isNaN(value) && value >= 1) {
+        updateSetting("audio_retention_days", value);
+      }
+    };
+
+    const handleRecordingsQuotaChange = (
+      event: React.ChangeEvent<HTMLInputElement>,
+    ) => {
+      const value = parseInt(event.target.value, 10);
+      if (!isNaN(value) && value >= 1) {
+        updateSetting("recordings_quota_mb", value);
+      }
+    };
+
     const retentionOptions = [
       { value: "never", label: t("settings.debug.recordingRetention.never") },
       {
@@ -40,23 +61,87 @@ export const RecordingRetentionPeriodSelector: React.FC<RecordingRetentionPeriod
         value: "months3",
         label: t("settings.debug.recordingRetention.months3"),
       },
+      {
+        value: "text_only",
+        label: t("settings.debug.recordingRetention.textOnly"),
+      },
+      {
+        value: "disk_quota",
+        label: t("settings.debug.recordingRetention.diskQuota"),
+      },
     ];
 
     return (
-      <SettingContainer
-        title={t("settings.debug.recordingRetention.title")}
-        description={t("settings.debug.recordingRetention.description")}
-        descriptionMode={descriptionMode}
-        grouped={grouped}
-      >
-        <Dropdown
-          options={retentionOptions}
-          selectedValue={selectedRetentionPeriod}
-          onSelect={handleRetentionPeriodSelect}
-          placeholder={t("settings.debug.recordingRetention.placeholder")}
-          disabled={isUpdating("recording_retention_period")}
-        />
-      </SettingContainer>
+      <>
+        <SettingContainer
+          title={t("settings.debug.recordingRetention.title")}
+          description={t("settings.debug.recordingRetention.description")}
+          descriptionMode={descriptionMode}
+          grouped={grouped}
+        >
+          <Dropdown
+            options={retentionOptions}
+            selectedValue={selectedRetentionPeriod}
+            onSelect={handleRetentionPeriodSelect}
+            placeholder={t("settings.debug.recordingRetention.placeholder")}
+            disabled={isUpdating("recording_retention_period")}
+          />
+        </SettingContainer>
+        {selectedRetentionPeriod === "text_only" && (
+          <SettingContainer
+            title={t(
+              "settings.debug.recordingRetention.audioRetentionDays.title",
+            )}
+            description={t(
+              "settings.debug.recordingRetention.audioRetentionDays.description",
+            )}
+            descriptionMode={descriptionMode}
+            grouped={grouped}
+            layout="horizontal"
+          >
+            <div className="flex items-center space-x-2">
+              <Input
+                type="number"
+                min="1"
+                value={audioRetentionDays}
+                onChange={handleAudioRetentionDaysChange}
+                disabled={isUpdating("audio_retention_days")}
+                className="w-20"
+              />
+              <span className="text-sm text-cf-text-primary">
+                {t("settings.debug.recordingRetention.audioRetentionDays.unit")}
+              </span>
+            </div>
+          </SettingContainer>
+        )}
+        {selectedRetentionPeriod === "disk_quota" && (
+          <SettingContainer
+            title={t(
+              "settings.debug.recordingRetention.recordingsQuota.title",
+            )}
+            description={t(
+              "settings.debug.recordingRetention.recordingsQuota.description",
+            )}
+            descriptionMode={descriptionMode}
+            grouped={grouped}
+            layout="horizontal"
+          >
+            <div className="flex items-center space-x-2">
+              <Input
+                type="number"
+                min="1"
+                value={recordingsQuotaMb}
+                onChange={handleRecordingsQuotaChange}
+                disabled={isUpdating("recordings_quota_mb")}
+                className="w-24"
+              />
+              <span className="text-sm text-cf-text-primary">
+                {t("settings.debug.recordingRetention.recordingsQuota.unit")}
+              </span>
+            </div>
+          </SettingContainer>
+        )}
+      </>
     );
   });
 
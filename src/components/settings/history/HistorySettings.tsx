@@ -1,12 +1,15 @@
-import React, { useState, useEffect, useCallback } from "react";
+import React, { useState, useEffect, useCallback, useRef } from "react";
 import { useTranslation } from "react-i18next";
 import { AudioPlayer } from "../../ui/AudioPlayer";
 import { Button } from "../../ui/Button";
 import { Copy, Star, Check, Trash2, FolderOpen } from "lucide-react";
 import { convertFileSrc } from "@tauri-apps/api/core";
 import { listen } from "@tauri-apps/api/event";
-import { readFile } from "@tauri-apps/plugin-fs";
-import { commands, type HistoryEntry } from "@/bindings";
+import {
+  commands,
+  type HistoryCursor,
+  type HistoryEntry,
+} from "@/bindings";
 import { formatDateTime } from "@/utils/dateFormat";
 import { useOsType } from "@/hooks/useOsType";
 
@@ -31,17 +34,42 @@ const OpenRecordingsButton: React.FC<OpenRecordingsButtonProps> = ({
   </Button>
 );
 
+/** Payload of `history-updated`, naming the entries that changed. */
+interface HistoryUpdate {
+  inserted: number[];
+  updated: number[];
+  deleted: number[];
+}
+
+// Newest first, matching the backend's page order.
+const compareEntries = (a: HistoryEntry, b: HistoryEntry) =>
+  b.timestamp - a.timestamp || b.id - a.id;
+
+const isBeforeCursor = (entry: HistoryEntry, cursor: HistoryCursor) =>
+  entry.timestamp < cursor.timestamp ||
+  (entry.timestamp === cursor.timestamp && entry.id < cursor.id);
+
 export const HistorySettings: React.FC = () => {
   const { t } = useTranslation();
   const osType = useOsType();
   const [historyEntries, setHistoryEntries] = useState<HistoryEntry[]>([]);
+  const [nextCursor, setNextCursor] = useState<HistoryCursor | null>(null);
   const [loading, setLoading] = useState(true);
+  const [loadingMore, setLoadingMore] = useState(false);
+  // Read by the event listener, which outlives a single render
+  const nextCursorRef = useRef<HistoryCursor | null>(null);
 
-  const loadHistoryEntries = useCallback(async () => {
+  const setCursor = (cursor: HistoryCursor | null) => {
+    nextCursorRef.current = cursor;
+    setNextCursor(cursor);
+  };
+
+  const loadFirstPage = useCallback(async () => {
     try {
-      const result = await commands.getHistoryEntries();
+      const result = await commands.getHistoryPage({});
       if (result.status === "ok") {
-        setHistoryEntries(result.data);
+        setHistoryEntries(result.data.entries);
+        setCursor(result.data.next);
       }
     } catch (error) {
       console.error("Failed to load history entries:", error);
@@ -50,15 +78,67 @@ export const HistorySettings: React.FC = () => {
     }
   }, []);
 
+  const loadNextPage = async () => {
+    if (!nextCursor) return;
+    setLoadingMore(true);
+    try {
+      const result = await commands.getHistoryPage({ before: nextCursor });
+      if (result.status === "ok") {
+        setHistoryEntries((entries) => {
+          const loadedIds = new Set(entries.map((entry) => entry.id));
+          return [
+            ...entries,
+            ...result.data.entries.filter((entry) => !loadedIds.has(entry.id)),
+          ];
+        });
+        setCursor(result.data.next);
+      }
+    } catch (error) {
+      console.error("Failed to load more history entries:", error);
+    } finally {
+      setLoadingMore(false);
+    }
+  };
+
+  // Patches the loaded entries instead of reloading the whole history
+  const applyUpdate = useCallback(async (update: HistoryUpdate) => {
+    const changedIds = [...update.inserted, ...update.updated];
+    let changed: HistoryEntry[] = [];
+    if (changedIds.length > 0) {
+      const result = await commands.getHistoryEntriesByIds(changedIds);
+      if (result.status !== "ok") {
+        console.error("Failed to load updated history entries:", result.error);
+        return;
+      }
+      changed = result.data;
+    }
+
+    const removedIds = new Set([...update.deleted, ...changedIds]);
+    setHistoryEntries((entries) => {
+      const cursor = nextCursorRef.current;
+      return [
+        ...entries.filter((entry) => !removedIds.has(entry.id)),
+        // Older entries arrive with a later page
+        ...changed.filter(
+          (entry) => !cursor || !isBeforeCursor(entry, cursor),
+        ),
+      ].sort(compareEntries);
+    });
+  }, []);
+
   useEffect(() => {
-    loadHistoryEntries();
+    loadFirstPage();
 
     // Listen for history update events
     const setupListener = async () => {
-      const unlisten = await listen("history-updated", () => {
-        console.log("History updated, reloading entries...");
-        loadHistoryEntries();
-      });
+      const unlisten = await listen<HistoryUpdate>(
+        "history-updated",
+        (event) => {
+          applyUpdate(event.payload).catch((error) =>
+            console.error("Failed to apply history update:", error),
+          );
+        },
+      );
 
       // Return cleanup function
       return unlisten;
@@ -73,7 +153,7 @@ export const HistorySettings: React.FC = () => {
         }
       });
     };
-  }, [loadHistoryEntries]);
+  }, [loadFirstPage, applyUpdate]);
 
   const toggleSaved = async (id: number) => {
     try {
@@ -95,20 +175,19 @@ export const HistorySettings: React.FC = () => {
   const getAudioUrl = useCallback(
     async (fileName: string) => {
       try {
-        const result = await commands.getAudioFilePath(fileName);
-        if (result.status === "ok") {
-          if (osType === "linux") {
-            const fileData = await readFile(result.data);
-            const blob = new Blob([fileData], { type: "audio/wav" });
-
-            return URL.createObjectURL(blob);
+        // Served from memory by the backend, decrypted if the history is
+        const url = convertFileSrc(fileName, "recording");
+        if (osType === "linux") {
+          const response = await fetch(url);
+          if (!response.ok) {
+            return null;
           }
-
-          return convertFileSrc(result.data, "asset");
+          return URL.createObjectURL(await response.blob());
         }
-        return null;
+
+        return url;
       } catch (error) {
-        console.error("Failed to get audio file path:", error);
+        console.error("Failed to load recording:", error);
         return null;
       }
     },
@@ -209,6 +288,20 @@ export const HistorySettings: React.FC = () => {
               />
             ))}
           </div>
+          {nextCursor && (
+            <div className="px-4 py-3 flex justify-center border-t border-cf-border-subtle">
+              <Button
+                onClick={loadNextPage}
+                disabled={loadingMore}
+                variant="secondary"
+                size="sm"
+              >
+                {loadingMore
+                  ? t("settings.history.loading")
+                  : t("settings.history.loadMore")}
+              </Button>
+            </div>
+          )}
         </div>
       </div>
     </div>
@@ -302,7 +395,13 @@ const HistoryEntryComponent: React.FC<HistoryEntryProps> = ({
       <p className="italic text-cf-text-primary text-sm pb-2 select-text cursor-text">
         {entry.transcription_text}
       </p>
-      <AudioPlayer onLoadRequest={handleLoadAudio} className="w-full" />
+      {entry.audio_pruned ? (
+        <p className="text-xs text-cf-text-tertiary">
+          {t("settings.history.audioPruned")}
+        </p>
+      ) : (
+        <AudioPlayer onLoadRequest={handleLoadAudio} className="w-full" />
+      )}
     </div>
   );
 };
@@ -5,6 +5,8 @@ import {
   MicrophoneIcon,
   TranscriptionIcon,
   CancelIcon,
+  PauseIcon,
+  ResumeIcon,
 } from "../components/icons";
 import "./RecordingOverlay.css";
 import { commands } from "@/bindings";
@@ -12,13 +14,14 @@ import i18n, { syncLanguageFromSettings } from "@/i18n";
 import { getLanguageDirection } from "@/lib/utils/rtl";
 import { syncThemeFromSettings } from "@/lib/utils/theme";
 
-type OverlayState = "recording" | "transcribing";
+type OverlayState = "recording" | "paused" | "transcribing";
 
 const RecordingOverlay: React.FC = () => {
   const { t } = useTranslation();
   const [isVisible, setIsVisible] = useState(false);
   const [state, setState] = useState<OverlayState>("recording");
   const [levels, setLevels] = useState<number[]>(Array(16).fill(0));
+  const [limitRemaining, setLimitRemaining] = useState<number | null>(null);
   const smoothedLevelsRef = useRef<number[]>(Array(16).fill(0));
   const direction = getLanguageDirection(i18n.language);
 
@@ -40,8 +43,17 @@ const RecordingOverlay: React.FC = () => {
       // Listen for hide-overlay event from Rust
       const unlistenHide = await listen("hide-overlay", () => {
         setIsVisible(false);
+        setLimitRemaining(null);
       });
 
+      // Listen for the warning that the recording is about to hit its limit
+      const unlistenLimit = await listen<number>(
+        "recording-limit-approaching",
+        (event) => {
+          setLimitRemaining(event.payload);
+        },
+      );
+
       // Listen for mic-level updates
       const unlistenLevel = await listen<number[]>("mic-level", (event) => {
         const newLevels = event.payload as number[];
@@ -61,12 +73,21 @@ const RecordingOverlay: React.FC = () => {
         unlistenShow();
         unlistenHide();
         unlistenLevel();
+        unlistenLimit();
       };
     };
 
     setupEventListeners();
   }, []);
 
+  // Count the limit warning down while the recording is still running
+  useEffect(() => {
+    if (limitRemaining === null || limitRemaining <= 0) return;
+    if (state !== "recording") return;
+    const timer = setTimeout(() => setLimitRemaining(limitRemaining - 1), 1000);
+    return () => clearTimeout(timer);
+  }, [limitRemaining, state]);
+
   const accentColor =
     getComputedStyle(document.documentElement)
       .getPropertyValue("--cf-accent")
@@ -75,6 +96,8 @@ const RecordingOverlay: React.FC = () => {
   const getIcon = () => {
     if (state === "recording") {
       return <MicrophoneIcon color={accentColor} />;
+    } else if (state === "paused") {
+      return <PauseIcon color={accentColor} />;
     } else {
       return <TranscriptionIcon color={accentColor} />;
     }
@@ -88,7 +111,12 @@ const RecordingOverlay: React.FC = () => {
       <div className="overlay-left">{getIcon()}</div>
 
       <div className="overlay-middle">
-        {state === "recording" && (
+        {state === "recording" && limitRemaining !== null && (
+          <div className="limit-warning-text">
+            {t("overlay.recordingLimitNear", { seconds: limitRemaining })}
+          </div>
+        )}
+        {state === "recording" && limitRemaining === null && (
           <div className="bars-container">
             {levels.map((v, i) => (
               <div
@@ -103,16 +131,30 @@ const RecordingOverlay: React.FC = () => {
             ))}
           </div>
         )}
+        {state === "paused" && (
+          <div className="paused-text">{t("overlay.paused")}</div>
+        )}
         {state === "transcribing" && (
           <div className="transcribing-text">{t("overlay.transcribing")}</div>
         )}
-
       </div>
 
       <div className="overlay-right">
-        {state === "recording" && (
+        {state === "paused" && (
+          <div
+            className="resume-button"
+            aria-label={t("overlay.resume")}
+            onClick={() => {
+              commands.resumeRecording();
+            }}
+          >
+            <ResumeIcon color={accentColor} />
+          </div>
+        )}
+        {(state === "recording" || state === "paused") && (
           <div
             className="cancel-button"
+            aria-label={t("overlay.cancel")}
             onClick={() => {
               commands.cancelOperation();
             }}
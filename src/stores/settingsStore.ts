@@ -65,16 +65,19 @@ const settingUpdaters: {
   update_checks_enabled: (value) =>
     commands.changeUpdateChecksSetting(value as boolean),
   push_to_talk: (value) => commands.changePttSetting(value as boolean),
-  selected_microphone: (value) =>
-    commands.setSelectedMicrophone(
+  // Both microphone settings rewrite the priority list, so reload it after.
+  selected_microphone: async (value) => {
+    await commands.setSelectedMicrophone(
       (value as string) === "Default" || value === null
         ? "default"
         : (value as string),
-    ),
-  clamshell_microphone: (value) =>
-    commands.setClamshellMicrophone(
-      (value as string) === "Default" ? "default" : (value as string),
-    ),
+    );
+    await useSettingsStore.getState().refreshSettings();
+  },
+  microphone_priority: async (value) => {
+    await commands.setMicrophonePriority(value as string[]);
+    await useSettingsStore.getState().refreshSettings();
+  },
   selected_output_device: (value) =>
     commands.setSelectedOutputDevice(
       (value as string) === "Default" || value === null
@@ -83,6 +86,10 @@ const settingUpdaters: {
     ),
   recording_retention_period: (value) =>
     commands.updateRecordingRetentionPeriod(value as string),
+  audio_retention_days: (value) =>
+    commands.updateAudioRetentionDays(value as number),
+  recordings_quota_mb: (value) =>
+    commands.updateRecordingsQuota(value as number),
   translate_to_english: (value) =>
     commands.changeTranslateToEnglishSetting(value as boolean),
   selected_language: (value) =>
@@ -140,8 +147,7 @@ export const useSettingsStore = create<SettingsStore>()(
           const normalizedSettings: Settings = {
             ...settings,
             always_on_microphone: settings.always_on_microphone ?? false,
-            selected_microphone: settings.selected_microphone ?? "Default",
-            clamshell_microphone: settings.clamshell_microphone ?? "Default",
+            selected_microphone: settings.microphone_priority?.[0] ?? "Default",
             selected_output_device:
               settings.selected_output_device ?? "Default",
           };